@@ -0,0 +1,10 @@
+//! Module for analyses of the lexicon against the phonology.
+
+pub use minimal_pairs::{minimal_pairs, MinimalPair, MinimalPairMatrix};
+pub use statistics::{
+    by_frequency, is_vowel, syllabify, LexiconStatistics, Syllable, SyllablePosition,
+    BIGRAM_WORD_BOUNDARY,
+};
+
+mod minimal_pairs;
+mod statistics;
@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use crate::phonology::Inventory;
+use crate::prelude::*;
+use crate::Dictionary;
+
+/// A pronunciation with a segment left out, along with the index of the left out segment.
+type Bucket<'a> = (Vec<&'a str>, usize);
+
+/// Two words whose pronunciations differ only in one segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimalPair {
+    /// The ids of the words. The first word has the first phoneme of the contrast.
+    pub word_ids: [Uuid; 2],
+    /// The index of the differing segment.
+    pub position: usize,
+}
+
+impl MinimalPair {
+    /// Gets the pair with the order of the words swapped.
+    pub fn swapped(&self) -> Self {
+        Self {
+            word_ids: [self.word_ids[1], self.word_ids[0]],
+            position: self.position,
+        }
+    }
+}
+
+/// Minimal pairs of the dictionary for every pair of contrasting phonemes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MinimalPairMatrix {
+    /// Minimal pairs by pair of phoneme ids, where the first id is lesser than the second.
+    pairs: BTreeMap<(Uuid, Uuid), Vec<MinimalPair>>,
+}
+
+impl MinimalPairMatrix {
+    /// Finds minimal pairs in `dictionary`. Pronunciations are split into segments using
+    /// `inventory`, so the contrast of e.g. /t/ and /tʰ/ is found even though one is a prefix of
+    /// the other. Segments not in the inventory must match exactly.
+    pub fn new(dictionary: &Dictionary, inventory: &Inventory) -> Self {
+        let sounds = inventory
+            .iter_phonemes()
+            .filter_map(|p| Some((p.sound(), p.id()?)))
+            .collect::<HashMap<_, _>>();
+
+        // Words by their pronunciation with a segment left out, along with the left out phoneme.
+        let mut buckets: HashMap<Bucket, Vec<(Uuid, Uuid)>> = HashMap::new();
+        let segmented = dictionary
+            .iter_words()
+            .filter_map(|w| Some((w.id()?, inventory.segment_pronunciation(w.pronunciation()))))
+            .collect::<Vec<_>>();
+
+        for (word_id, segments) in segmented.iter() {
+            for (i, segment) in segments.iter().enumerate() {
+                let Some(phoneme_id) = sounds.get(segment.as_str()) else {
+                    continue;
+                };
+                let rest = segments
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, s)| s.as_str())
+                    .collect::<Vec<_>>();
+                buckets
+                    .entry((rest, i))
+                    .or_default()
+                    .push((*word_id, *phoneme_id));
+            }
+        }
+
+        let mut pairs: BTreeMap<(Uuid, Uuid), Vec<MinimalPair>> = BTreeMap::new();
+        for ((_, position), words) in buckets {
+            for (i, (a_word, a)) in words.iter().enumerate() {
+                for (b_word, b) in words[i + 1..].iter() {
+                    if a == b {
+                        continue;
+                    }
+                    let (key, word_ids) = match a < b {
+                        true => ((*a, *b), [*a_word, *b_word]),
+                        false => ((*b, *a), [*b_word, *a_word]),
+                    };
+                    pairs
+                        .entry(key)
+                        .or_default()
+                        .push(MinimalPair { word_ids, position });
+                }
+            }
+        }
+
+        for list in pairs.values_mut() {
+            list.sort_by_key(|p| (p.word_ids, p.position));
+        }
+
+        Self { pairs }
+    }
+
+    /// Gets the minimal pairs contrasting the phonemes of ids `a` and `b`. The first word of each
+    /// pair has phoneme `a`.
+    pub fn get(&self, a: Uuid, b: Uuid) -> Vec<MinimalPair> {
+        match a < b {
+            true => self.pairs.get(&(a, b)).cloned().unwrap_or_default(),
+            false => self
+                .pairs
+                .get(&(b, a))
+                .map(|list| list.iter().map(MinimalPair::swapped).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Iterates over the phonemes contrasting with the phoneme of id `phoneme_id` by at least a
+    /// minimal pair, along with the minimal pairs. The first word of each pair has the phoneme.
+    pub fn contrasts_of(
+        &self,
+        phoneme_id: Uuid,
+    ) -> impl Iterator<Item = (Uuid, Vec<MinimalPair>)> + '_ {
+        self.pairs.keys().filter_map(move |&(a, b)| {
+            if a == phoneme_id {
+                Some((b, self.get(a, b)))
+            } else if b == phoneme_id {
+                Some((a, self.get(b, a)))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Iterates over pairs of phoneme ids with at least a minimal pair, along with the number
+    /// of minimal pairs.
+    pub fn counts(&self) -> impl Iterator<Item = ((Uuid, Uuid), usize)> + '_ {
+        self.pairs.iter().map(|(k, v)| (*k, v.len()))
+    }
+}
+
+/// Finds minimal pairs in `dictionary` contrasting the phonemes of ids `a` and `b`. The first
+/// word of each pair has phoneme `a`.
+pub fn minimal_pairs(
+    dictionary: &Dictionary,
+    inventory: &Inventory,
+    a: Uuid,
+    b: Uuid,
+) -> Vec<MinimalPair> {
+    MinimalPairMatrix::new(dictionary, inventory).get(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+    use crate::Phoneme;
+
+    use super::*;
+
+    #[test]
+    fn matrix() {
+        let mut inv = Inventory::new();
+        let t = inv.add_phoneme(Phoneme::with_sound("t".to_string()));
+        let th = inv.add_phoneme(Phoneme::with_sound("tʰ".to_string()));
+        let d = inv.add_phoneme(Phoneme::with_sound("d".to_string()));
+        let a = inv.add_phoneme(Phoneme::with_sound("a".to_string()));
+        let i = inv.add_phoneme(Phoneme::with_sound("i".to_string()));
+
+        let mut dict = Dictionary::new();
+        let mut add =
+            |pron: &str| dict.add_word(WordBuilder::new().pronunciation(pron.to_string()).build());
+        let tata = add("ˈta.ta");
+        let thata = add("ˈtʰa.ta");
+        let tada = add("ˈta.da");
+        let tati = add("ˈta.ti");
+        add("ˈda.di");
+
+        let matrix = MinimalPairMatrix::new(&dict, &inv);
+        assert_eq!(
+            matrix.get(th, t),
+            vec![MinimalPair {
+                word_ids: [thata, tata],
+                position: 0
+            }]
+        );
+        assert_eq!(
+            matrix.get(t, d),
+            vec![MinimalPair {
+                word_ids: [tata, tada],
+                position: 2
+            }]
+        );
+        assert_eq!(matrix.get(a, i).len(), 1);
+        assert_eq!(matrix.get(a, i)[0].word_ids, [tata, tati]);
+        assert!(matrix.get(th, d).is_empty());
+        assert_eq!(matrix.contrasts_of(t).count(), 2);
+        assert_eq!(matrix.counts().count(), 3);
+        assert_eq!(minimal_pairs(&dict, &inv, i, a)[0].word_ids, [tati, tata]);
+    }
+}
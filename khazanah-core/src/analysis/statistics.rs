@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::ipa::{self, Ipa};
+use crate::phonology::{normalize_syllables, Inventory, PATTERN_SYLLABLE_BOUNDARY};
+use crate::Dictionary;
+
+/// The boundary symbol used at the edges of words in bigrams.
+pub const BIGRAM_WORD_BOUNDARY: &str = "#";
+
+/// The position of a segment in a syllable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SyllablePosition {
+    Onset,
+    Nucleus,
+    Coda,
+}
+
+impl SyllablePosition {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Onset => "Onset",
+            Self::Nucleus => "Nucleus",
+            Self::Coda => "Coda",
+        }
+    }
+}
+
+/// A syllable split into onset, nucleus, and coda.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Syllable {
+    pub onset: Vec<String>,
+    pub nucleus: Vec<String>,
+    pub coda: Vec<String>,
+}
+
+impl Syllable {
+    /// Iterates over the segments of the syllable, along with their positions.
+    pub fn iter_segments(&self) -> impl Iterator<Item = (&str, SyllablePosition)> {
+        [
+            (&self.onset, SyllablePosition::Onset),
+            (&self.nucleus, SyllablePosition::Nucleus),
+            (&self.coda, SyllablePosition::Coda),
+        ]
+        .into_iter()
+        .flat_map(|(v, p)| v.iter().map(move |s| (s.as_str(), p)))
+    }
+
+    /// Gets the shape of the syllable, where consonants are written as `C` and vowels as `V`,
+    /// e.g. `"CVC"`.
+    pub fn shape(&self) -> String {
+        self.iter_segments()
+            .map(|(s, _)| match is_vowel(s) {
+                true => 'V',
+                false => 'C',
+            })
+            .collect()
+    }
+}
+
+/// Returns `true` if the segment is a vowel.
+pub fn is_vowel(segment: &str) -> bool {
+    matches!(ipa::parse_str(segment).first(), Some(Ipa::Vowel { .. }))
+}
+
+/// Splits a pronunciation into syllables. Syllable boundaries and stress marks in the
+/// pronunciation are respected. Within a syllable with several vowel groups, the last consonant
+/// between two groups begins the next syllable. A syllable without any vowel is taken to be a
+/// syllabic consonant.
+pub fn syllabify(pronunciation: &str, inventory: &Inventory) -> Vec<Syllable> {
+    let mut ret = Vec::new();
+    for part in normalize_syllables(pronunciation).split(PATTERN_SYLLABLE_BOUNDARY) {
+        let segments = inventory.segment_pronunciation(part);
+        if segments.is_empty() {
+            continue;
+        }
+        if !segments.iter().any(|s| is_vowel(s)) {
+            ret.push(Syllable {
+                nucleus: segments,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let mut current = Syllable::default();
+        for segment in segments {
+            match (is_vowel(&segment), current.nucleus.is_empty()) {
+                (true, true) => current.nucleus.push(segment),
+                (true, false) if current.coda.is_empty() => current.nucleus.push(segment),
+                (true, false) => {
+                    let onset = current.coda.pop();
+                    ret.push(std::mem::take(&mut current));
+                    current.onset.extend(onset);
+                    current.nucleus.push(segment);
+                }
+                (false, true) => current.onset.push(segment),
+                (false, false) => current.coda.push(segment),
+            }
+        }
+        ret.push(current);
+    }
+    ret
+}
+
+/// Frequency statistics of the phonology of the words of a dictionary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LexiconStatistics {
+    /// The number of words with a pronunciation.
+    pub n_words: usize,
+    /// Occurences of each segment.
+    pub segments: BTreeMap<String, usize>,
+    /// Occurences of each segment in each syllable position.
+    pub positions: BTreeMap<(String, SyllablePosition), usize>,
+    /// Occurences of each syllable shape.
+    pub syllable_shapes: BTreeMap<String, usize>,
+    /// Number of words by length in syllables.
+    pub syllable_lengths: BTreeMap<usize, usize>,
+    /// Number of words by length in morae.
+    pub mora_lengths: BTreeMap<u32, usize>,
+    /// Occurences of each pair of adjacent segments. Word edges are written as
+    /// [`BIGRAM_WORD_BOUNDARY`].
+    pub bigrams: BTreeMap<(String, String), usize>,
+}
+
+impl LexiconStatistics {
+    /// Counts the statistics of the words of `dictionary`. Pronunciations are split into segments
+    /// using `inventory`. The length of a word in morae is the sum of [`crate::Phoneme::mora`]
+    /// of its nucleus and coda segments; segments not in the inventory count as one mora.
+    pub fn new(dictionary: &Dictionary, inventory: &Inventory) -> Self {
+        let morae = inventory
+            .iter_phonemes()
+            .map(|p| (p.sound(), p.mora()))
+            .collect::<HashMap<_, _>>();
+
+        let mut ret = Self::default();
+        for word in dictionary.iter_words() {
+            let syllables = syllabify(word.pronunciation(), inventory);
+            if syllables.is_empty() {
+                continue;
+            }
+            ret.n_words += 1;
+
+            let mut n_morae = 0;
+            let mut prev = BIGRAM_WORD_BOUNDARY.to_string();
+            for syllable in syllables.iter() {
+                *ret.syllable_shapes.entry(syllable.shape()).or_default() += 1;
+                for (segment, position) in syllable.iter_segments() {
+                    *ret.segments.entry(segment.to_string()).or_default() += 1;
+                    *ret.positions
+                        .entry((segment.to_string(), position))
+                        .or_default() += 1;
+                    *ret.bigrams.entry((prev, segment.to_string())).or_default() += 1;
+                    if position != SyllablePosition::Onset {
+                        n_morae += morae.get(segment).copied().unwrap_or(1);
+                    }
+                    prev = segment.to_string();
+                }
+            }
+            *ret.bigrams
+                .entry((prev, BIGRAM_WORD_BOUNDARY.to_string()))
+                .or_default() += 1;
+            *ret.syllable_lengths.entry(syllables.len()).or_default() += 1;
+            *ret.mora_lengths.entry(n_morae).or_default() += 1;
+        }
+        ret
+    }
+
+    /// Gets the number of occurences of `segment` in `position`.
+    pub fn position_count(&self, segment: &str, position: SyllablePosition) -> usize {
+        self.positions
+            .get(&(segment.to_string(), position))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Sorts counts from the most frequent, and then by key.
+pub fn by_frequency<K: Clone + Ord>(counts: &BTreeMap<K, usize>) -> Vec<(K, usize)> {
+    let mut ret = counts
+        .iter()
+        .map(|(k, v)| (k.clone(), *v))
+        .collect::<Vec<_>>();
+    ret.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+    use crate::Phoneme;
+
+    use super::*;
+
+    #[test]
+    fn syllables() {
+        let mut inv = Inventory::new();
+        for s in ["t", "tʰ", "a", "n", "i"] {
+            inv.add_phoneme(Phoneme::with_sound(s.to_string()));
+        }
+
+        let shapes = |pron| {
+            syllabify(pron, &inv)
+                .iter()
+                .map(Syllable::shape)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shapes("ˈtʰan.ti"), vec!["CVC", "CV"]);
+        assert_eq!(shapes("tʰantia"), vec!["CVC", "CVV"]);
+        assert_eq!(shapes("an.n̩"), vec!["VC", "C"]);
+        assert_eq!(syllabify("ˈtʰan", &inv)[0].onset, vec!["tʰ"]);
+    }
+
+    #[test]
+    fn statistics() {
+        let mut inv = Inventory::new();
+        for s in ["t", "n", "i"] {
+            inv.add_phoneme(Phoneme::with_sound(s.to_string()));
+        }
+        let mut a = Phoneme::with_sound("a".to_string());
+        a.set_mora(2);
+        inv.add_phoneme(a);
+
+        let mut dict = Dictionary::new();
+        for pron in ["ˈta.na", "ˈtin", "ˈan", ""] {
+            dict.add_word(WordBuilder::new().pronunciation(pron.to_string()).build());
+        }
+
+        let stats = LexiconStatistics::new(&dict, &inv);
+        assert_eq!(stats.n_words, 3);
+        assert_eq!(stats.segments.get("a"), Some(&3));
+        assert_eq!(stats.position_count("n", SyllablePosition::Onset), 1);
+        assert_eq!(stats.position_count("n", SyllablePosition::Coda), 2);
+        assert_eq!(
+            by_frequency(&stats.syllable_shapes),
+            vec![
+                ("CV".to_string(), 2),
+                ("CVC".to_string(), 1),
+                ("VC".to_string(), 1)
+            ]
+        );
+        assert_eq!(stats.syllable_lengths, BTreeMap::from([(1, 2), (2, 1)]));
+        assert_eq!(stats.mora_lengths, BTreeMap::from([(2, 1), (3, 1), (4, 1)]));
+        assert_eq!(
+            stats
+                .bigrams
+                .get(&(BIGRAM_WORD_BOUNDARY.to_string(), "t".to_string())),
+            Some(&2)
+        );
+        assert_eq!(
+            stats
+                .bigrams
+                .get(&("n".to_string(), BIGRAM_WORD_BOUNDARY.to_string())),
+            Some(&2)
+        );
+    }
+}
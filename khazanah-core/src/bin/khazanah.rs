@@ -0,0 +1,50 @@
+//! Command line tool for Khazanah project files.
+//!
+//! ```txt
+//! khazanah check <file>...
+//! ```
+//!
+//! `check` validates project files, printing the problems found. It exits with status 1 if any
+//! file has problems, and 2 if the arguments are wrong.
+
+use std::process::ExitCode;
+
+use khazanah_core::Project;
+
+const USAGE: &str = "Usage: khazanah check <file>...";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.split_first() {
+        Some((command, files)) if command == "check" && !files.is_empty() => check(files),
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Checks project files and prints the problems found.
+fn check(files: &[String]) -> ExitCode {
+    let mut ok = true;
+    for path in files {
+        match Project::check_file(path) {
+            Ok(problems) if problems.is_empty() => println!("{path}: OK"),
+            Ok(problems) => {
+                ok = false;
+                for problem in problems {
+                    println!("{path}: {problem}");
+                }
+            }
+            Err(e) => {
+                ok = false;
+                println!("{path}: {e}");
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,135 @@
+//! Module for example texts and interlinear glossed text.
+
+pub use error::Error;
+pub use gloss::{
+    propose_gloss, tokenize, word_gloss, GlossProposal, GlossedToken, TokenMatch, UNKNOWN_GLOSS,
+};
+pub use sentence::{Sentence, TokenLink};
+pub use text::Text;
+
+mod error;
+mod gloss;
+mod sentence;
+mod text;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter},
+    Store,
+};
+
+/// Collection of example texts.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Corpus {
+    inner: Store<Text>,
+}
+
+impl Corpus {
+    /// Creates a new corpus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text.
+    pub fn add_text(&mut self, text: Text) -> Uuid {
+        self.inner.add(text)
+    }
+
+    /// Removes a text by id.
+    pub fn remove_text_by_id(&mut self, id: Uuid) -> Option<Text> {
+        self.inner.remove(id)
+    }
+
+    /// Gets the number of texts.
+    pub fn n_texts(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Gets a reference to text by id.
+    pub fn text_by_id(&self, id: Uuid) -> Option<&Text> {
+        self.inner.get(id)
+    }
+
+    /// Gets a mutable reference to text by id.
+    pub fn text_by_id_mut(&mut self, id: Uuid) -> Option<&mut Text> {
+        self.inner.get_mut(id)
+    }
+
+    /// Iterates over texts.
+    pub fn iter_texts(&self) -> impl Iterator<Item = &Text> {
+        self.inner.iter()
+    }
+
+    /// Iterates over texts.
+    pub fn iter_texts_mut(&mut self) -> impl Iterator<Item = &mut Text> {
+        self.inner.iter_mut()
+    }
+
+    /// Iterates over text ids.
+    pub fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.inner.ids()
+    }
+
+    /// Gets a reference to the inner store.
+    pub fn texts(&self) -> &Store<Text> {
+        &self.inner
+    }
+
+    /// Removes every link to the word of id `word_id`.
+    pub fn unlink_word(&mut self, word_id: Uuid) {
+        for text in self.inner.iter_mut() {
+            text.unlink_word(word_id);
+        }
+    }
+}
+
+impl ReadXml for Corpus {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "corpus";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner
+            ._process_tag_start(Self::TAG, reader, state, name, attrs)
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Corpus {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner._serialize_xml("corpus", writer)
+    }
+}
@@ -0,0 +1,6 @@
+/// Error type relating to corpus domain.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Id error: {0}")]
+    Id(#[from] uuid::Error),
+}
@@ -0,0 +1,349 @@
+use uuid::Uuid;
+
+use crate::{
+    lexicon::{segment, AffixKind, Dictionary, Segmentation},
+    phonology::{Categories, Inventory},
+    prelude::*,
+    PartOfSpeech, Word,
+};
+
+use super::Sentence;
+
+/// Punctuation stripped from both ends of a token.
+const PUNCTUATION: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '"', '(', ')', '[', ']', '«', '»', '“', '”', '‘', '’', '—', '–',
+];
+
+/// The gloss proposed for tokens not found in the dictionary.
+pub const UNKNOWN_GLOSS: &str = "?";
+
+/// How a token was found in the dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenMatch {
+    /// The token is the romanization of a word.
+    Exact(Uuid),
+    /// The token is made of several morphemes in the dictionary.
+    Segmented(Segmentation),
+    /// The token is only punctuation.
+    Punctuation,
+    /// The token is not found.
+    Unknown,
+}
+
+/// The proposed gloss of a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlossedToken {
+    /// The token, without surrounding punctuation.
+    pub token: String,
+    /// The token split into morphemes.
+    pub segmented: String,
+    /// The gloss of the token.
+    pub gloss: String,
+    /// How the token was found in the dictionary.
+    pub found: TokenMatch,
+}
+
+impl GlossedToken {
+    /// Returns `true` if the token is not found in the dictionary.
+    pub fn is_unknown(&self) -> bool {
+        self.found == TokenMatch::Unknown
+    }
+
+    /// Gets the id of the word the token links to. For segmented tokens, this is the root.
+    pub fn word_id(&self) -> Option<Uuid> {
+        match &self.found {
+            TokenMatch::Exact(id) => Some(*id),
+            TokenMatch::Segmented(seg) => seg
+                .morphemes
+                .iter()
+                .find(|m| m.kind.is_none())
+                .map(|m| m.word_id),
+            TokenMatch::Punctuation | TokenMatch::Unknown => None,
+        }
+    }
+}
+
+/// The proposed gloss of a sentence.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GlossProposal {
+    pub tokens: Vec<GlossedToken>,
+}
+
+impl GlossProposal {
+    /// Gets the proposed morpheme-segmented line.
+    pub fn segmented_line(&self) -> String {
+        self.join(|t| &t.segmented)
+    }
+
+    /// Gets the proposed gloss line.
+    pub fn gloss_line(&self) -> String {
+        self.join(|t| &t.gloss)
+    }
+
+    fn join<F: Fn(&GlossedToken) -> &str>(&self, f: F) -> String {
+        self.tokens.iter().map(f).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Gets the indices of the tokens not found in the dictionary.
+    pub fn unknown_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.is_unknown())
+            .map(|(i, _)| i)
+    }
+
+    /// Fills the segmented line, gloss line and links of `sentence`.
+    pub fn apply_to(&self, sentence: &mut Sentence) {
+        sentence.segmented = self.segmented_line();
+        sentence.gloss = self.gloss_line();
+        sentence.set_links(self.tokens.iter().map(|t| t.word_id()));
+    }
+}
+
+/// Gets the gloss label of a word: its first translation, with spaces replaced by periods as
+/// in Leipzig glossing, e.g. `"to eat, to consume"` becomes `"to.eat"`.
+pub fn word_gloss(word: &Word) -> String {
+    let gloss = word.glosses().next().unwrap_or(UNKNOWN_GLOSS);
+
+    gloss.split_whitespace().collect::<Vec<_>>().join(".")
+}
+
+/// Gets the gloss line of a segmented word, e.g. `sun-PL` for `nifu-tu`.
+fn segmentation_gloss(seg: &Segmentation, dictionary: &Dictionary) -> String {
+    let gloss = |id| {
+        dictionary
+            .word_by_id(id)
+            .map(word_gloss)
+            .unwrap_or_else(|| UNKNOWN_GLOSS.to_string())
+    };
+
+    let mut ret = String::new();
+    let mut prev_kind = None;
+    let mut glossed_root = false;
+    for m in seg.morphemes.iter() {
+        match m.kind {
+            Some(AffixKind::Infix) => {
+                ret.push('<');
+                ret.push_str(&gloss(m.word_id));
+                ret.push('>');
+            }
+            // The second part of a root split by an infix is already glossed.
+            None if glossed_root && prev_kind == Some(AffixKind::Infix) => {}
+            kind => {
+                if !ret.is_empty() && prev_kind != Some(AffixKind::Infix) {
+                    ret.push('-');
+                }
+                ret.push_str(&gloss(m.word_id));
+                glossed_root |= kind.is_none();
+            }
+        }
+        prev_kind = m.kind;
+    }
+    ret
+}
+
+/// Splits a sentence into tokens, stripping punctuation around each token. Tokens keep the
+/// positions of the whitespace-separated words of `source`, so a word made only of
+/// punctuation yields an empty token.
+pub fn tokenize(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .split_whitespace()
+        .map(|t| t.trim_matches(PUNCTUATION))
+}
+
+/// Looks up a token in the dictionary. Tries an exact romanization match first, then a
+/// segmentation into morphemes.
+fn gloss_token(
+    token: &str,
+    dictionary: &Dictionary,
+    categories: &Categories,
+    inventory: &Inventory,
+) -> GlossedToken {
+    let exact = |s: &str| {
+        dictionary
+            .iter_words()
+            .filter(|w| w.part_of_speech() != Some(PartOfSpeech::Affix) && w.romanization() == s)
+            .min_by_key(|w| w.id())
+    };
+
+    if let Some(word) = exact(token).or_else(|| exact(&token.to_lowercase())) {
+        return GlossedToken {
+            token: token.to_string(),
+            segmented: token.to_string(),
+            gloss: word_gloss(word),
+            found: TokenMatch::Exact(word.id().unwrap_or_default()),
+        };
+    }
+
+    let seg = segment(token, None, dictionary, categories, inventory).or_else(|| {
+        segment(
+            &token.to_lowercase(),
+            None,
+            dictionary,
+            categories,
+            inventory,
+        )
+    });
+    if let Some(seg) = seg {
+        return GlossedToken {
+            token: token.to_string(),
+            segmented: seg.to_string(),
+            gloss: segmentation_gloss(&seg, dictionary),
+            found: TokenMatch::Segmented(seg),
+        };
+    }
+
+    GlossedToken {
+        token: token.to_string(),
+        segmented: token.to_string(),
+        gloss: UNKNOWN_GLOSS.to_string(),
+        found: TokenMatch::Unknown,
+    }
+}
+
+/// Proposes the segmented line and gloss line of a sentence by looking up each token in the
+/// dictionary.
+pub fn propose_gloss(
+    source: &str,
+    dictionary: &Dictionary,
+    categories: &Categories,
+    inventory: &Inventory,
+) -> GlossProposal {
+    GlossProposal {
+        tokens: source
+            .split_whitespace()
+            .zip(tokenize(source))
+            .map(|(raw, t)| {
+                if t.is_empty() {
+                    GlossedToken {
+                        token: raw.to_string(),
+                        segmented: raw.to_string(),
+                        gloss: raw.to_string(),
+                        found: TokenMatch::Punctuation,
+                    }
+                } else {
+                    gloss_token(t, dictionary, categories, inventory)
+                }
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexicon::{Affix, Allomorph, WordBuilder};
+
+    fn word(romanization: &str, translation: &str, pos: PartOfSpeech) -> Word {
+        WordBuilder::new()
+            .romanization(romanization.to_string())
+            .translation(translation.to_string())
+            .part_of_speech(pos)
+            .build()
+    }
+
+    fn affix(romanization: &str, translation: &str, kind: AffixKind, form: &str) -> Word {
+        let mut w = word(romanization, translation, PartOfSpeech::Affix);
+        w.set_affix(Some(Affix {
+            kind,
+            allomorphs: vec![Allomorph::new(form.to_string())],
+        }));
+        w
+    }
+
+    #[test]
+    fn propose() {
+        let mut dict = Dictionary::new();
+        let sun = dict.add_word(word("nifu", "sun, star", PartOfSpeech::Noun));
+        let write = dict.add_word(word("sulat", "to write", PartOfSpeech::Verb));
+        dict.add_word(affix("-tu", "PL", AffixKind::Suffix, "tu"));
+        dict.add_word(affix("-um-", "AV", AffixKind::Infix, "um"));
+
+        let cats = Categories::new();
+        let inv = Inventory::new();
+
+        let p = propose_gloss("Nifu nifutu, sumulat — xyz.", &dict, &cats, &inv);
+        assert_eq!(p.segmented_line(), "Nifu nifu-tu s<um>ulat — xyz");
+        assert_eq!(p.gloss_line(), "sun sun-PL to.write<AV> — ?");
+        assert_eq!(p.unknown_indices().collect::<Vec<_>>(), vec![4]);
+
+        let mut sen = Sentence::new();
+        sen.source = "Nifu nifutu, sumulat — xyz.".to_string();
+        p.apply_to(&mut sen);
+        assert_eq!(
+            sen.token_links(),
+            vec![Some(sun), Some(sun), Some(write), None, None]
+        );
+    }
+
+    #[test]
+    fn propose_ambiguous() {
+        let new_dict = || {
+            let mut dict = Dictionary::new();
+            dict.add_word(word("nifu", "sun", PartOfSpeech::Noun));
+            dict.add_word(word("nifut", "star", PartOfSpeech::Noun));
+            dict.add_word(affix("-tu", "PL", AffixKind::Suffix, "tu"));
+            dict.add_word(affix("-u", "DIM", AffixKind::Suffix, "u"));
+            dict
+        };
+        let (cats, inv) = (Categories::new(), Inventory::new());
+
+        // Both `nifu-tu` and `nifut-u` have two morphemes; the longer root wins whatever the
+        // order of the dictionary.
+        for _ in 0..8 {
+            let p = propose_gloss("nifutu", &new_dict(), &cats, &inv);
+            assert_eq!(p.segmented_line(), "nifut-u");
+            assert_eq!(p.gloss_line(), "star-DIM");
+        }
+
+        let mut dict = new_dict();
+
+        // An exact match wins over any segmentation.
+        let id = dict.add_word(word("nifutu", "galaxy", PartOfSpeech::Noun));
+        let p = propose_gloss("nifutu", &dict, &cats, &inv);
+        assert_eq!(p.tokens[0].found, TokenMatch::Exact(id));
+        assert_eq!(p.gloss_line(), "galaxy");
+    }
+
+    #[test]
+    fn propose_unknown() {
+        let mut dict = Dictionary::new();
+        dict.add_word(word("nifu", "sun", PartOfSpeech::Noun));
+        dict.add_word(affix("-tu", "PL", AffixKind::Suffix, "tu"));
+        let (cats, inv) = (Categories::new(), Inventory::new());
+
+        // Affixes alone and roots with unknown endings are not words.
+        let p = propose_gloss("tu nifuxa xyz nifu", &dict, &cats, &inv);
+        assert_eq!(p.unknown_indices().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(p.segmented_line(), "tu nifuxa xyz nifu");
+        assert_eq!(p.gloss_line(), "? ? ? sun");
+        assert_eq!(
+            p.tokens.iter().map(|t| t.word_id()).collect::<Vec<_>>()[..3],
+            [None, None, None]
+        );
+    }
+
+    #[test]
+    fn propose_punctuation() {
+        let mut dict = Dictionary::new();
+        let sun = dict.add_word(word("nifu", "sun", PartOfSpeech::Noun));
+        let (cats, inv) = (Categories::new(), Inventory::new());
+
+        let p = propose_gloss("«Nifu», — (nifu)?! …", &dict, &cats, &inv);
+        assert_eq!(
+            tokenize("«Nifu», — (nifu)?! …").collect::<Vec<_>>(),
+            vec!["Nifu", "", "nifu", "…"]
+        );
+        assert_eq!(p.segmented_line(), "Nifu — nifu …");
+        assert_eq!(p.gloss_line(), "sun — sun ?");
+        assert_eq!(p.tokens[1].found, TokenMatch::Punctuation);
+        // Punctuation is not counted as unknown, but symbols outside the list are.
+        assert_eq!(p.unknown_indices().collect::<Vec<_>>(), vec![3]);
+
+        let mut sen = Sentence::new();
+        sen.source = "«Nifu», — (nifu)?! …".to_string();
+        p.apply_to(&mut sen);
+        assert_eq!(sen.token_links(), vec![Some(sun), None, Some(sun), None]);
+    }
+}
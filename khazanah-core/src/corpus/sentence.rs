@@ -0,0 +1,430 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+
+use super::Error;
+
+/// A link from a token of the source line to a dictionary entry. The link is kept on the same
+/// token when the source line is edited: it follows the token with the same text nearest to where
+/// it was linked, and is dropped if there is no such token anymore.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct TokenLink {
+    /// The token as written in the source line.
+    pub token: String,
+    /// The byte offset of the token in the source line.
+    pub start: usize,
+    /// The id of the linked word.
+    pub word_id: Uuid,
+}
+
+/// A sentence in interlinear glossed text.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Sentence {
+    /// The sentence as written in the language.
+    pub source: String,
+    /// The sentence with its words split into morphemes, e.g. `nifu-tu`.
+    pub segmented: String,
+    /// The Leipzig-style gloss line, e.g. `sun-PL`.
+    pub gloss: String,
+    /// The free translation of the sentence.
+    pub translation: String,
+    /// The dictionary entries of the tokens in `source`.
+    pub links: Vec<TokenLink>,
+}
+
+impl Sentence {
+    /// Creates a new sentence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Iterates over the tokens of the source line.
+    pub fn tokens(&self) -> impl Iterator<Item = &str> {
+        self.source.split_whitespace()
+    }
+
+    /// Gets the byte offset and the text of each token of the source line.
+    pub fn token_spans(&self) -> Vec<(usize, &str)> {
+        self.source
+            .split_whitespace()
+            .map(|t| (t.as_ptr() as usize - self.source.as_ptr() as usize, t))
+            .collect()
+    }
+
+    /// Finds the index of the token each link belongs to, or `None` if its token is gone. Each
+    /// token takes at most one link.
+    fn resolve_links(&self) -> Vec<Option<usize>> {
+        let spans = self.token_spans();
+        let mut claimed = vec![false; spans.len()];
+        let mut ret = vec![None; self.links.len()];
+
+        let mut order = (0..self.links.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| self.links[i].start);
+        for i in order {
+            let link = &self.links[i];
+            let found = spans
+                .iter()
+                .enumerate()
+                .filter(|(j, (_, t))| !claimed[*j] && *t == link.token)
+                .min_by_key(|(_, (start, _))| start.abs_diff(link.start))
+                .map(|(j, _)| j);
+            if let Some(j) = found {
+                claimed[j] = true;
+            }
+            ret[i] = found;
+        }
+        ret
+    }
+
+    /// Gets the id of the word linked to the token at `index`.
+    pub fn link(&self, index: usize) -> Option<Uuid> {
+        self.resolve_links()
+            .into_iter()
+            .position(|j| j == Some(index))
+            .map(|i| self.links[i].word_id)
+    }
+
+    /// Gets the id of the word linked to each token of the source line.
+    pub fn token_links(&self) -> Vec<Option<Uuid>> {
+        let mut ret = vec![None; self.token_spans().len()];
+        for (i, j) in self.resolve_links().into_iter().enumerate() {
+            if let Some(j) = j {
+                ret[j] = Some(self.links[i].word_id);
+            }
+        }
+        ret
+    }
+
+    /// Links the token at `index` to a word, or unlinks it if `word_id` is `None`.
+    pub fn set_link(&mut self, index: usize, word_id: Option<Uuid>) {
+        let resolved = self.resolve_links();
+        let mut i = 0;
+        self.links.retain(|_| {
+            i += 1;
+            resolved[i - 1] != Some(index)
+        });
+        if let (Some(word_id), Some(&(start, token))) = (word_id, self.token_spans().get(index)) {
+            self.links.push(TokenLink {
+                token: token.to_string(),
+                start,
+                word_id,
+            });
+        }
+    }
+
+    /// Replaces every link, linking the tokens of the source line in order.
+    pub fn set_links<I: IntoIterator<Item = Option<Uuid>>>(&mut self, word_ids: I) {
+        let links = self
+            .token_spans()
+            .into_iter()
+            .zip(word_ids)
+            .filter_map(|((start, token), word_id)| {
+                word_id.map(|word_id| TokenLink {
+                    token: token.to_string(),
+                    start,
+                    word_id,
+                })
+            })
+            .collect();
+        self.links = links;
+    }
+
+    /// Moves the links onto the tokens of the current source line, dropping those whose token is
+    /// gone. Call this after editing `source`.
+    pub fn relink(&mut self) {
+        let spans = self
+            .token_spans()
+            .into_iter()
+            .map(|(start, _)| start)
+            .collect::<Vec<_>>();
+        let resolved = self.resolve_links();
+        let links = std::mem::take(&mut self.links);
+        self.links = links
+            .into_iter()
+            .zip(resolved)
+            .filter_map(|(link, j)| {
+                j.map(|j| TokenLink {
+                    start: spans[j],
+                    ..link
+                })
+            })
+            .collect();
+    }
+
+    /// Sets the source line, keeping the links on their tokens.
+    pub fn set_source(&mut self, source: String) {
+        self.source = source;
+        self.relink();
+    }
+
+    /// Returns `true` if any token links to the word of id `word_id`.
+    pub fn links_to(&self, word_id: Uuid) -> bool {
+        self.links.iter().any(|l| l.word_id == word_id)
+    }
+
+    /// Removes every link to the word of id `word_id`.
+    pub fn unlink_word(&mut self, word_id: Uuid) {
+        self.links.retain(|l| l.word_id != word_id);
+    }
+
+    /// Aligns the source, segmented and gloss lines word by word. Each column holds the words
+    /// at the same position in each line, with missing words left empty.
+    pub fn aligned(&self) -> Vec<[&str; 3]> {
+        let lines = [
+            self.source.split_whitespace().collect::<Vec<_>>(),
+            self.segmented.split_whitespace().collect::<Vec<_>>(),
+            self.gloss.split_whitespace().collect::<Vec<_>>(),
+        ];
+        let n = lines.iter().map(Vec::len).max().unwrap_or_default();
+
+        (0..n)
+            .map(|i| {
+                [
+                    lines[0].get(i).copied().unwrap_or_default(),
+                    lines[1].get(i).copied().unwrap_or_default(),
+                    lines[2].get(i).copied().unwrap_or_default(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl ReadXml for Sentence {
+    type Error = Error;
+
+    /// The number of `link` elements read so far.
+    type ReaderState = usize;
+
+    const TAG: &'static str = "sentence";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        n_links: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {}
+            (Some(Self::TAG), Some("source")) => {
+                self.source.clear();
+            }
+            (Some(Self::TAG), Some("segmented")) => {
+                self.segmented.clear();
+            }
+            (Some(Self::TAG), Some("gloss")) => {
+                self.gloss.clear();
+            }
+            (Some(Self::TAG), Some("translation")) => {
+                self.translation.clear();
+            }
+            (Some(Self::TAG), Some("links")) => {
+                self.links.clear();
+                *n_links = 0;
+            }
+            (Some("links"), Some("link")) => {
+                let attr = |key| attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+                let link = match (attr("token"), attr("start")) {
+                    (Some(token), Some(start)) => TokenLink {
+                        token: token.to_owned(),
+                        start: start.parse().unwrap_or_default(),
+                        word_id: Uuid::nil(),
+                    },
+                    // Older documents link tokens by position.
+                    _ => {
+                        let (start, token) = self
+                            .token_spans()
+                            .get(*n_links)
+                            .map(|&(start, token)| (start, token.to_string()))
+                            .unwrap_or_default();
+                        TokenLink {
+                            token,
+                            start,
+                            word_id: Uuid::nil(),
+                        }
+                    }
+                };
+                self.links.push(link);
+                *n_links += 1;
+            }
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag() {
+            Some("source") => self.source += &text,
+            Some("segmented") => self.segmented += &text,
+            Some("gloss") => self.gloss += &text,
+            Some("translation") => self.translation += &text,
+            Some("link") => {
+                let id = Uuid::parse_str(&text).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                if let Some(link) = self.links.last_mut() {
+                    link.word_id = id;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        // Drop links without a word, and those to tokens not in the source line.
+        if name == "link" && self.links.last().map(|l| l.word_id.is_nil()) == Some(true) {
+            self.links.pop();
+        }
+        if name == Self::TAG {
+            self.links.retain(|l| !l.token.is_empty());
+        }
+        Ok(())
+    }
+}
+
+impl WriteXml for Sentence {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        w: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        w.write_tag_start("sentence")?;
+
+        w.write_tag_start("source")?;
+        w.write_text(&self.source)?;
+        w.write_tag_end("source")?;
+
+        w.write_tag_start("segmented")?;
+        w.write_text(&self.segmented)?;
+        w.write_tag_end("segmented")?;
+
+        w.write_tag_start("gloss")?;
+        w.write_text(&self.gloss)?;
+        w.write_tag_end("gloss")?;
+
+        w.write_tag_start("translation")?;
+        w.write_text(&self.translation)?;
+        w.write_tag_end("translation")?;
+
+        w.write_tag_start("links")?;
+        for link in self.links.iter() {
+            let start = link.start.to_string();
+            w.write_tag_start_with_attributes(
+                "link",
+                [("token", link.token.as_str()), ("start", start.as_str())],
+            )?;
+            w.write_text(link.word_id.to_string().as_str())?;
+            w.write_tag_end("link")?;
+        }
+        w.write_tag_end("links")?;
+
+        w.write_tag_end("sentence")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML1: &str = r#"
+    <sentence>
+        <source>nifutu xahlauraqi</source>
+        <segmented>nifu-tu xahlauraqi</segmented>
+        <gloss>sun-PL story</gloss>
+        <translation>the stories of the suns</translation>
+        <links>
+            <link></link>
+            <link>5ce2f1b7-527f-4779-9c96-71939cb397af</link>
+        </links>
+    </sentence>
+    "#;
+
+    #[test]
+    fn read_xml() {
+        let sen = Sentence::load_xml_str(XML1).unwrap();
+        assert_eq!(
+            sen.tokens().collect::<Vec<_>>(),
+            vec!["nifutu", "xahlauraqi"]
+        );
+        assert_eq!(sen.link(0), None);
+        assert_eq!(
+            sen.link(1),
+            Some(Uuid::parse_str("5ce2f1b7-527f-4779-9c96-71939cb397af").unwrap())
+        );
+        assert_eq!(sen.translation, "the stories of the suns");
+    }
+
+    #[test]
+    fn write_xml() {
+        let sen = Sentence::load_xml_str(XML1).unwrap();
+        let xml2 = sen.save_xml_string().unwrap();
+        let sen2 = Sentence::load_xml_str(&xml2).unwrap();
+        assert_eq!(&sen, &sen2);
+    }
+
+    #[test]
+    fn read_xml_token_links() {
+        let id = Uuid::parse_str("5ce2f1b7-527f-4779-9c96-71939cb397af").unwrap();
+        let sen = Sentence::load_xml_str(&format!(
+            r#"<sentence>
+                <source>a nifu nifu</source>
+                <links><link token="nifu" start="7">{id}</link></links>
+            </sentence>"#
+        ))
+        .unwrap();
+        assert_eq!(sen.token_links(), vec![None, None, Some(id)]);
+    }
+
+    #[test]
+    fn links_follow_tokens() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut sen = Sentence::new();
+        sen.source = "nifu taka nifu".to_string();
+        sen.set_links([Some(a), Some(b), None]);
+        assert_eq!(sen.token_links(), vec![Some(a), Some(b), None]);
+
+        // Inserting a word before the linked tokens keeps the links on them.
+        sen.set_source("xahla nifu taka nifu".to_string());
+        assert_eq!(sen.token_links(), vec![None, Some(a), Some(b), None]);
+        assert_eq!(sen.link(1), Some(a));
+
+        // A link whose token is gone is dropped.
+        sen.set_source("xahla nifu sulat nifu".to_string());
+        assert_eq!(sen.token_links(), vec![None, Some(a), None, None]);
+        assert!(!sen.links_to(b));
+
+        sen.set_link(3, Some(b));
+        sen.set_link(1, None);
+        assert_eq!(sen.token_links(), vec![None, None, None, Some(b)]);
+    }
+
+    #[test]
+    fn aligned() {
+        let mut sen = Sentence::load_xml_str(XML1).unwrap();
+        sen.gloss = "sun-PL".to_string();
+        assert_eq!(
+            sen.aligned(),
+            vec![
+                ["nifutu", "nifu-tu", "sun-PL"],
+                ["xahlauraqi", "xahlauraqi", ""],
+            ]
+        );
+    }
+}
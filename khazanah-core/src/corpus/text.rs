@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    prelude::*,
+    xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter},
+};
+
+use super::{Error, Sentence};
+
+/// A text in the corpus, made of sentences.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Text {
+    /// The id of the text.
+    id: Option<Uuid>,
+    /// The title of the text.
+    title: String,
+    /// The sentences of the text, in order.
+    sentences: Vec<Sentence>,
+}
+
+impl IdAble for Text {
+    /// Gets the id of the text.
+    fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// Generates new id for the text, and then returns it.
+    fn generate_id(&mut self) -> Uuid {
+        let id = Uuid::new_v4();
+        self.id = Some(id);
+        id
+    }
+}
+
+impl Text {
+    /// Creates a new text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new text with specified id.
+    pub fn new_with_id(id: Uuid) -> Self {
+        Self {
+            id: Some(id),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the title of the text.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Sets the title of the text.
+    pub fn set_title(&mut self, value: String) {
+        self.title = value;
+    }
+
+    /// Gets the sentences of the text.
+    pub fn sentences(&self) -> &[Sentence] {
+        &self.sentences
+    }
+
+    /// Gets a mutable reference to the sentences of the text.
+    pub fn sentences_mut(&mut self) -> &mut Vec<Sentence> {
+        &mut self.sentences
+    }
+
+    /// Gets a reference to the sentence at `index`.
+    pub fn sentence(&self, index: usize) -> Option<&Sentence> {
+        self.sentences.get(index)
+    }
+
+    /// Gets a mutable reference to the sentence at `index`.
+    pub fn sentence_mut(&mut self, index: usize) -> Option<&mut Sentence> {
+        self.sentences.get_mut(index)
+    }
+
+    /// Appends a sentence, and then returns its index.
+    pub fn add_sentence(&mut self, sentence: Sentence) -> usize {
+        self.sentences.push(sentence);
+        self.sentences.len() - 1
+    }
+
+    /// Removes the sentence at `index`.
+    pub fn remove_sentence(&mut self, index: usize) -> Option<Sentence> {
+        (index < self.sentences.len()).then(|| self.sentences.remove(index))
+    }
+
+    /// Returns `true` if any sentence links to the word of id `word_id`.
+    pub fn links_to(&self, word_id: Uuid) -> bool {
+        self.sentences.iter().any(|s| s.links_to(word_id))
+    }
+
+    /// Removes every link to the word of id `word_id`.
+    pub fn unlink_word(&mut self, word_id: Uuid) {
+        for sentence in self.sentences.iter_mut() {
+            sentence.unlink_word(word_id);
+        }
+    }
+}
+
+impl ReadXml for Text {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "text";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {
+                let id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "id")
+                    .map(|x| Uuid::parse_str(&x.1))
+                    .unwrap_or_else(|| Ok(Uuid::new_v4()))
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.id = Some(id);
+            }
+            (Some(Self::TAG), Some("title")) => {
+                self.title.clear();
+            }
+            (Some(Self::TAG), Some(Sentence::TAG)) => {
+                self.sentences
+                    .push(Sentence::deserialize_xml(reader, Some((name, attrs)))?);
+            }
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        if let Some("title") = reader.last_tag() {
+            self.title += &text;
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Text {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        w: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        w.write_tag_start_with_attributes(
+            "text",
+            [("id", self.id.unwrap_or_default().to_string().as_str())],
+        )?;
+
+        w.write_tag_start("title")?;
+        w.write_text(&self.title)?;
+        w.write_tag_end("title")?;
+
+        for sentence in self.sentences.iter() {
+            sentence.serialize_xml(w)?;
+        }
+
+        w.write_tag_end("text")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML1: &str = r#"
+    <text id="0f9e7e3c-3b5d-4a54-8cf5-2f4c1b0d9a11">
+        <title>The Sun</title>
+        <sentence>
+            <source>nifutu</source>
+            <segmented>nifu-tu</segmented>
+            <gloss>sun-PL</gloss>
+            <translation>suns</translation>
+            <links><link>5ce2f1b7-527f-4779-9c96-71939cb397af</link></links>
+        </sentence>
+        <sentence>
+            <source>xahlauraqi</source>
+            <segmented></segmented>
+            <gloss></gloss>
+            <translation>story</translation>
+            <links><link></link></links>
+        </sentence>
+    </text>
+    "#;
+
+    #[test]
+    fn read_xml() {
+        let text = Text::load_xml_str(XML1).unwrap();
+        assert_eq!(
+            text.id(),
+            Some(Uuid::parse_str("0f9e7e3c-3b5d-4a54-8cf5-2f4c1b0d9a11").unwrap())
+        );
+        assert_eq!(text.title(), "The Sun");
+        assert_eq!(text.sentences().len(), 2);
+        assert_eq!(text.sentence(1).unwrap().translation, "story");
+    }
+
+    #[test]
+    fn write_xml() {
+        let text = Text::load_xml_str(XML1).unwrap();
+        let xml2 = text.save_xml_string().unwrap();
+        let text2 = Text::load_xml_str(&xml2).unwrap();
+        assert_eq!(&text, &text2);
+    }
+
+    #[test]
+    fn unlink_word() {
+        let mut text = Text::load_xml_str(XML1).unwrap();
+        let id = Uuid::parse_str("5ce2f1b7-527f-4779-9c96-71939cb397af").unwrap();
+        assert!(text.links_to(id));
+        text.unlink_word(id);
+        assert!(!text.links_to(id));
+        assert!(text.sentence(0).unwrap().links.is_empty());
+    }
+}
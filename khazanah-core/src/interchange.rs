@@ -0,0 +1,22 @@
+//! Module for importing and exporting the dictionary in formats of other tools.
+
+pub use self::csv::{
+    detect_delimiter, export as export_csv, CsvColumn, CsvOptions, CsvTable, ALL_CSV_COLUMNS,
+};
+pub use document::{export_document, DocumentFormat, DocumentOptions, ALL_DOCUMENT_FORMATS};
+pub use error::Error;
+pub use import::{
+    apply_import, preview_import, DuplicatePolicy, ImportAction, ImportReport, ImportSummary,
+    ALL_DUPLICATE_POLICIES,
+};
+pub use lift::{export_lift, import_lift, LiftOptions, LIFT_VERSION};
+pub use polyglot::{
+    import_polyglot, import_polyglot_xml, POLYGLOT_DEFINITION_FIELD, POLYGLOT_DICTIONARY_FILE,
+};
+
+mod csv;
+mod document;
+mod error;
+mod import;
+mod lift;
+mod polyglot;
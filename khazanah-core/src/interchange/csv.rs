@@ -0,0 +1,387 @@
+use std::io::{Read, Write};
+
+use crate::lexicon::{WordStatus, ALL_PARTS_OF_SPEECH};
+use crate::{Dictionary, PartOfSpeech, Word};
+
+use super::Error;
+
+use super::DuplicatePolicy;
+
+/// Separator of tags in a single cell.
+const TAG_SEPARATOR: char = ',';
+
+/// The field of a word stored in a column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// A column that is not imported, or left empty on export.
+    Ignore,
+    Romanization,
+    Pronunciation,
+    XSampa,
+    Translation,
+    PartOfSpeech,
+    Tags,
+    Status,
+}
+
+impl CsvColumn {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ignore => "Ignore",
+            Self::Romanization => "Romanization",
+            Self::Pronunciation => "Pronunciation",
+            Self::XSampa => "X-SAMPA",
+            Self::Translation => "Translation",
+            Self::PartOfSpeech => "Part of Speech",
+            Self::Tags => "Tags",
+            Self::Status => "Status",
+        }
+    }
+
+    /// Guesses the column from a header cell. Returns `None` if it is not recognized.
+    pub fn from_header(value: &str) -> Option<Self> {
+        let ret = match value.trim().to_lowercase().as_str() {
+            "romanization" | "word" | "rom" | "lemma" | "headword" | "conword" => {
+                Self::Romanization
+            }
+            "pronunciation" | "ipa" | "pron" => Self::Pronunciation,
+            "x-sampa" | "xsampa" => Self::XSampa,
+            "translation" | "gloss" | "meaning" | "definition" | "tr" => Self::Translation,
+            "part of speech" | "part-of-speech" | "pos" | "class" => Self::PartOfSpeech,
+            "tags" | "tag" => Self::Tags,
+            "status" => Self::Status,
+            _ => return None,
+        };
+        Some(ret)
+    }
+
+    /// Gets the value of the column for `word`.
+    fn value(&self, word: &Word) -> String {
+        match self {
+            Self::Ignore => String::new(),
+            Self::Romanization => word.romanization().to_string(),
+            Self::Pronunciation => word.pronunciation().to_string(),
+            Self::XSampa => word.xsampa_pronunciation().unwrap_or_default().to_string(),
+            Self::Translation => word.translation().to_string(),
+            Self::PartOfSpeech => word.part_of_speech().map(String::from).unwrap_or_default(),
+            Self::Tags => word.tags().collect::<Vec<_>>().join(", "),
+            Self::Status => word.status().name().to_string(),
+        }
+    }
+
+    /// Sets the field of `word` from the value of the column.
+    fn set_value(&self, word: &mut Word, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        match self {
+            Self::Ignore => {}
+            Self::Romanization => word.set_romanization(value.to_string()),
+            Self::Pronunciation => word.set_pronunciation(value.to_string()),
+            Self::XSampa => word.set_xsampa_pronunciation(Some(value.to_string())),
+            Self::Translation => word.set_translation(value.to_string()),
+            Self::PartOfSpeech => word.set_part_of_speech(parse_part_of_speech(value)),
+            Self::Tags => {
+                for tag in value.split(TAG_SEPARATOR) {
+                    word.add_tag(tag.to_string());
+                }
+            }
+            Self::Status => word.set_status(WordStatus::from(value)),
+        }
+    }
+}
+
+pub const ALL_CSV_COLUMNS: &[CsvColumn] = &[
+    CsvColumn::Ignore,
+    CsvColumn::Romanization,
+    CsvColumn::Pronunciation,
+    CsvColumn::XSampa,
+    CsvColumn::Translation,
+    CsvColumn::PartOfSpeech,
+    CsvColumn::Tags,
+    CsvColumn::Status,
+];
+
+/// Parses a part of speech by its name or its abbreviation, ignoring case.
+pub(super) fn parse_part_of_speech(value: &str) -> Option<PartOfSpeech> {
+    let value = value.to_lowercase();
+    ALL_PARTS_OF_SPEECH.iter().flatten().copied().find(|pos| {
+        pos.name().to_lowercase() == value
+            || pos.label().trim_end_matches('.').to_lowercase() == value.trim_end_matches('.')
+    })
+}
+
+/// Guesses the delimiter of delimited text from its first line: a tab, a semicolon, or a comma.
+pub fn detect_delimiter(text: &str) -> u8 {
+    let line = text.lines().next().unwrap_or_default();
+    [b'\t', b';', b',']
+        .into_iter()
+        .max_by_key(|&d| line.bytes().filter(|&b| b == d).count())
+        .filter(|&d| line.bytes().any(|b| b == d))
+        .unwrap_or(b',')
+}
+
+/// Options of reading and writing delimited text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The separator of cells.
+    pub delimiter: u8,
+    /// Whether the first row holds the column names.
+    pub has_header: bool,
+    /// The field of each column, from the left.
+    pub columns: Vec<CsvColumn>,
+    /// What to do with imported words already in the dictionary.
+    pub duplicates: DuplicatePolicy,
+    /// Whether to export only approved words.
+    pub approved_only: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            columns: vec![
+                CsvColumn::Romanization,
+                CsvColumn::Pronunciation,
+                CsvColumn::Translation,
+                CsvColumn::PartOfSpeech,
+            ],
+            duplicates: DuplicatePolicy::default(),
+            approved_only: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Creates options for tab separated text.
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: b'\t',
+            ..Default::default()
+        }
+    }
+}
+
+/// Raw cells of delimited text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CsvTable {
+    /// The cells of the first row, if it is a header.
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Reads delimited text. The first row is taken to be the header if `options.has_header` is
+    /// set.
+    pub fn read<R: Read>(reader: R, options: &CsvOptions) -> Result<Self, Error> {
+        let mut rdr = ::csv::ReaderBuilder::new()
+            .delimiter(options.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut ret = Self::default();
+        for record in rdr.records() {
+            let row = record?.iter().map(str::to_string).collect::<Vec<_>>();
+            if options.has_header && ret.header.is_none() {
+                ret.header = Some(row);
+            } else {
+                ret.rows.push(row);
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Reads delimited text, guessing the delimiter, whether the first row is a header, and the
+    /// column mapping. Returns the table along with the guessed options.
+    pub fn read_detect(text: &str) -> Result<(Self, CsvOptions), Error> {
+        let mut options = CsvOptions {
+            delimiter: detect_delimiter(text),
+            has_header: false,
+            ..Default::default()
+        };
+        let mut ret = Self::read(text.as_bytes(), &options)?;
+
+        let first = ret.rows.first().cloned().unwrap_or_default();
+        let detected = first
+            .iter()
+            .map(|c| CsvColumn::from_header(c))
+            .collect::<Vec<_>>();
+        if detected.iter().any(Option::is_some) {
+            options.has_header = true;
+            options.columns = detected
+                .into_iter()
+                .map(|c| c.unwrap_or(CsvColumn::Ignore))
+                .collect();
+            ret.header = Some(ret.rows.remove(0));
+        } else {
+            options.columns.resize(first.len(), CsvColumn::Ignore);
+        }
+        Ok((ret, options))
+    }
+
+    /// Gets the number of columns of the widest row.
+    pub fn n_columns(&self) -> usize {
+        self.header
+            .iter()
+            .chain(self.rows.iter())
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Converts rows into words by the column mapping of `options`. Rows without romanization
+    /// and translation are left out.
+    pub fn words(&self, options: &CsvOptions) -> Vec<Word> {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let mut word = Word::new();
+                for (column, value) in options.columns.iter().zip(row.iter()) {
+                    column.set_value(&mut word, value);
+                }
+                (!word.romanization().is_empty() || !word.translation().is_empty()).then_some(word)
+            })
+            .collect()
+    }
+}
+
+/// Writes the words of `dictionary` as delimited text, sorted by romanization. Returns the
+/// number of words written.
+pub fn export<W: Write>(
+    dictionary: &Dictionary,
+    writer: W,
+    options: &CsvOptions,
+) -> Result<usize, Error> {
+    let mut wtr = ::csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_writer(writer);
+
+    if options.has_header {
+        wtr.write_record(options.columns.iter().map(|c| match c {
+            CsvColumn::Ignore => "",
+            c => c.name(),
+        }))?;
+    }
+
+    let mut words = dictionary
+        .iter_words()
+        .filter(|w| !options.approved_only || w.status() == WordStatus::Approved)
+        .collect::<Vec<_>>();
+    words.sort_by(|a, b| a.romanization().cmp(b.romanization()));
+
+    for word in words.iter() {
+        wtr.write_record(options.columns.iter().map(|c| c.value(word)))?;
+    }
+    wtr.flush()?;
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interchange::{apply_import, preview_import, ImportAction};
+    use crate::lexicon::WordBuilder;
+
+    use super::*;
+
+    #[test]
+    fn detect_and_read() {
+        let text = "Word;IPA;Meaning;Notes\nnifu;nifu;sun;x\ntaka;taka;\"go; walk\";\n";
+        let (table, options) = CsvTable::read_detect(text).unwrap();
+        assert_eq!(options.delimiter, b';');
+        assert!(options.has_header);
+        assert_eq!(
+            options.columns,
+            vec![
+                CsvColumn::Romanization,
+                CsvColumn::Pronunciation,
+                CsvColumn::Translation,
+                CsvColumn::Ignore,
+            ]
+        );
+
+        let words = table.words(&options);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].translation(), "go; walk");
+
+        let (_, options) = CsvTable::read_detect("nifu\tsun\n").unwrap();
+        assert_eq!(options.delimiter, b'\t');
+        assert!(!options.has_header);
+        assert_eq!(options.columns.len(), 2);
+    }
+
+    #[test]
+    fn import_duplicates() {
+        let mut dict = Dictionary::new();
+        let id = dict.add_word(
+            WordBuilder::new()
+                .romanization("nifu".to_string())
+                .translation("sun".to_string())
+                .build(),
+        );
+
+        let options = CsvOptions {
+            columns: vec![
+                CsvColumn::Romanization,
+                CsvColumn::Pronunciation,
+                CsvColumn::PartOfSpeech,
+            ],
+            ..Default::default()
+        };
+        let table = CsvTable::read(
+            "rom,ipa,pos\nNifu,nifu,n\ntaka,taka,verb\n".as_bytes(),
+            &options,
+        )
+        .unwrap();
+        let words = table.words(&options);
+        assert_eq!(words[1].part_of_speech(), Some(PartOfSpeech::Verb));
+
+        let preview = preview_import(&dict, words.clone(), DuplicatePolicy::Skip);
+        assert_eq!(preview[0].1, ImportAction::Skip(id));
+        assert_eq!(preview[1].1, ImportAction::Add);
+
+        let preview = preview_import(&dict, words, DuplicatePolicy::Merge);
+        let summary = apply_import(&mut dict, preview);
+        assert_eq!((summary.added, summary.merged, summary.skipped), (1, 1, 0));
+        let merged = dict.word_by_id(id).unwrap();
+        assert_eq!(merged.pronunciation(), "nifu");
+        assert_eq!(merged.part_of_speech(), Some(PartOfSpeech::Noun));
+    }
+
+    #[test]
+    fn export_approved_only() {
+        let mut dict = Dictionary::new();
+        dict.add_word(
+            WordBuilder::new()
+                .romanization("taka".to_string())
+                .translation("go, walk".to_string())
+                .build(),
+        );
+        dict.add_word(
+            WordBuilder::new()
+                .romanization("nifu".to_string())
+                .status(WordStatus::Draft)
+                .build(),
+        );
+
+        let options = CsvOptions {
+            columns: vec![CsvColumn::Romanization, CsvColumn::Translation],
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        assert_eq!(export(&dict, &mut buf, &options).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "Romanization,Translation\nnifu,\ntaka,\"go, walk\"\n"
+        );
+
+        let options = CsvOptions {
+            approved_only: true,
+            ..CsvOptions::tsv()
+        };
+        let mut buf = Vec::new();
+        assert_eq!(export(&dict, &mut buf, &options).unwrap(), 1);
+    }
+}
@@ -0,0 +1,517 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::lexicon::{Collation, WordStatus};
+use crate::{IdAble, Language, Word};
+
+use super::Error;
+
+/// A format of typeset documents.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DocumentFormat {
+    Markdown,
+    #[default]
+    Html,
+    Latex,
+}
+
+impl DocumentFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Html => "HTML",
+            Self::Latex => "LaTeX",
+        }
+    }
+
+    /// Gets the usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Latex => "tex",
+        }
+    }
+}
+
+pub const ALL_DOCUMENT_FORMATS: &[DocumentFormat] = &[
+    DocumentFormat::Html,
+    DocumentFormat::Latex,
+    DocumentFormat::Markdown,
+];
+
+/// Options of exporting the dictionary as a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentOptions {
+    pub format: DocumentFormat,
+    /// Whether to export only approved words.
+    pub approved_only: bool,
+    /// Whether to add an index of words by their glosses.
+    pub reverse_index: bool,
+}
+
+impl Default for DocumentOptions {
+    fn default() -> Self {
+        Self {
+            format: DocumentFormat::default(),
+            approved_only: false,
+            reverse_index: true,
+        }
+    }
+}
+
+/// A dictionary entry, as typeset.
+struct Entry<'a> {
+    headword: &'a str,
+    pronunciation: &'a str,
+    part_of_speech: Option<(&'static str, &'static str)>,
+    glosses: Vec<&'a str>,
+}
+
+impl<'a> From<&'a Word> for Entry<'a> {
+    fn from(word: &'a Word) -> Self {
+        Self {
+            headword: word.romanization(),
+            pronunciation: word.pronunciation(),
+            part_of_speech: word.part_of_speech().map(|pos| (pos.label(), pos.name())),
+            glosses: word.glosses().collect(),
+        }
+    }
+}
+
+/// The layout of a document format.
+trait Template {
+    fn escape(&self, s: &str) -> String;
+
+    fn begin(&self, w: &mut dyn Write, title: &str, description: &str) -> std::io::Result<()>;
+
+    /// Starts a group of entries sharing their first letter.
+    fn section(&self, w: &mut dyn Write, heading: &str) -> std::io::Result<()>;
+
+    fn entry(&self, w: &mut dyn Write, entry: &Entry) -> std::io::Result<()>;
+
+    /// Starts the reverse index.
+    fn index(&self, w: &mut dyn Write, title: &str) -> std::io::Result<()>;
+
+    fn index_entry(
+        &self,
+        w: &mut dyn Write,
+        gloss: &str,
+        headwords: &[&str],
+    ) -> std::io::Result<()>;
+
+    fn end(&self, w: &mut dyn Write) -> std::io::Result<()>;
+}
+
+struct MarkdownTemplate;
+
+impl Template for MarkdownTemplate {
+    fn escape(&self, s: &str) -> String {
+        let mut ret = String::new();
+        for c in s.chars() {
+            if "\\`*_[]<>#|".contains(c) {
+                ret.push('\\');
+            }
+            ret.push(c);
+        }
+        ret
+    }
+
+    fn begin(&self, w: &mut dyn Write, title: &str, description: &str) -> std::io::Result<()> {
+        writeln!(w, "# {}", self.escape(title))?;
+        if !description.is_empty() {
+            writeln!(w, "\n{}", self.escape(description))?;
+        }
+        Ok(())
+    }
+
+    fn section(&self, w: &mut dyn Write, heading: &str) -> std::io::Result<()> {
+        writeln!(w, "\n## {}\n", self.escape(heading))
+    }
+
+    fn entry(&self, w: &mut dyn Write, entry: &Entry) -> std::io::Result<()> {
+        write!(w, "**{}**", self.escape(entry.headword))?;
+        if !entry.pronunciation.is_empty() {
+            write!(w, " /{}/", self.escape(entry.pronunciation))?;
+        }
+        if let Some((label, _)) = entry.part_of_speech {
+            write!(w, " *{}*", self.escape(label))?;
+        }
+        if !entry.glosses.is_empty() {
+            write!(w, " {}", self.escape(&entry.glosses.join("; ")))?;
+        }
+        // Two trailing spaces make a line break.
+        writeln!(w, "  ")
+    }
+
+    fn index(&self, w: &mut dyn Write, title: &str) -> std::io::Result<()> {
+        writeln!(w, "\n## {}\n", self.escape(title))
+    }
+
+    fn index_entry(
+        &self,
+        w: &mut dyn Write,
+        gloss: &str,
+        headwords: &[&str],
+    ) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "- {}: **{}**",
+            self.escape(gloss),
+            self.escape(&headwords.join(", "))
+        )
+    }
+
+    fn end(&self, _w: &mut dyn Write) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct HtmlTemplate;
+
+impl Template for HtmlTemplate {
+    fn escape(&self, s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn begin(&self, w: &mut dyn Write, title: &str, description: &str) -> std::io::Result<()> {
+        let title = self.escape(title);
+        writeln!(w, "<!DOCTYPE html>")?;
+        writeln!(w, "<html>")?;
+        writeln!(w, "<head>")?;
+        writeln!(w, "<meta charset=\"utf-8\">")?;
+        writeln!(w, "<title>{}</title>", title)?;
+        writeln!(w, "<style>")?;
+        writeln!(
+            w,
+            "body {{ font-family: serif; max-width: 48em; margin: auto; }}"
+        )?;
+        writeln!(w, ".entry {{ margin: 0 0 0.4em; }}")?;
+        writeln!(w, ".headword {{ font-weight: bold; }}")?;
+        writeln!(w, ".pos {{ font-style: italic; text-decoration: none; }}")?;
+        writeln!(w, "</style>")?;
+        writeln!(w, "</head>")?;
+        writeln!(w, "<body>")?;
+        writeln!(w, "<h1>{}</h1>", title)?;
+        if !description.is_empty() {
+            writeln!(w, "<p>{}</p>", self.escape(description))?;
+        }
+        Ok(())
+    }
+
+    fn section(&self, w: &mut dyn Write, heading: &str) -> std::io::Result<()> {
+        writeln!(w, "<h2>{}</h2>", self.escape(heading))
+    }
+
+    fn entry(&self, w: &mut dyn Write, entry: &Entry) -> std::io::Result<()> {
+        write!(
+            w,
+            "<p class=\"entry\"><span class=\"headword\">{}</span>",
+            self.escape(entry.headword)
+        )?;
+        if !entry.pronunciation.is_empty() {
+            write!(
+                w,
+                " <span class=\"ipa\">/{}/</span>",
+                self.escape(entry.pronunciation)
+            )?;
+        }
+        if let Some((label, name)) = entry.part_of_speech {
+            write!(
+                w,
+                " <abbr class=\"pos\" title=\"{}\">{}</abbr>",
+                self.escape(name),
+                self.escape(label)
+            )?;
+        }
+        if !entry.glosses.is_empty() {
+            write!(
+                w,
+                " <span class=\"gloss\">{}</span>",
+                self.escape(&entry.glosses.join("; "))
+            )?;
+        }
+        writeln!(w, "</p>")
+    }
+
+    fn index(&self, w: &mut dyn Write, title: &str) -> std::io::Result<()> {
+        writeln!(w, "<h2>{}</h2>", self.escape(title))
+    }
+
+    fn index_entry(
+        &self,
+        w: &mut dyn Write,
+        gloss: &str,
+        headwords: &[&str],
+    ) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "<p class=\"entry\">{}: <span class=\"headword\">{}</span></p>",
+            self.escape(gloss),
+            self.escape(&headwords.join(", "))
+        )
+    }
+
+    fn end(&self, w: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(w, "</body>")?;
+        writeln!(w, "</html>")
+    }
+}
+
+struct LatexTemplate;
+
+impl Template for LatexTemplate {
+    fn escape(&self, s: &str) -> String {
+        let mut ret = String::new();
+        for c in s.chars() {
+            match c {
+                '\\' => ret.push_str("\\textbackslash{}"),
+                '~' => ret.push_str("\\textasciitilde{}"),
+                '^' => ret.push_str("\\textasciicircum{}"),
+                '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                    ret.push('\\');
+                    ret.push(c);
+                }
+                c => ret.push(c),
+            }
+        }
+        ret
+    }
+
+    fn begin(&self, w: &mut dyn Write, title: &str, description: &str) -> std::io::Result<()> {
+        // Compiled with XeLaTeX or LuaLaTeX, so IPA needs no special markup.
+        writeln!(w, "\\documentclass[a4paper]{{article}}")?;
+        writeln!(w, "\\usepackage{{fontspec}}")?;
+        writeln!(w, "\\usepackage{{multicol}}")?;
+        writeln!(
+            w,
+            "\\newcommand{{\\entry}}[4]{{\\par\\noindent\\textbf{{#1}}\\ #2\\ \\textit{{#3}}\\ #4}}"
+        )?;
+        writeln!(w, "\\title{{{}}}", self.escape(title))?;
+        writeln!(w, "\\date{{}}")?;
+        writeln!(w, "\\begin{{document}}")?;
+        writeln!(w, "\\maketitle")?;
+        if !description.is_empty() {
+            writeln!(w, "{}", self.escape(description))?;
+        }
+        Ok(())
+    }
+
+    fn section(&self, w: &mut dyn Write, heading: &str) -> std::io::Result<()> {
+        writeln!(w, "\\section*{{{}}}", self.escape(heading))
+    }
+
+    fn entry(&self, w: &mut dyn Write, entry: &Entry) -> std::io::Result<()> {
+        let pronunciation = match entry.pronunciation {
+            "" => String::new(),
+            s => format!("/{}/", self.escape(s)),
+        };
+        writeln!(
+            w,
+            "\\entry{{{}}}{{{}}}{{{}}}{{{}}}",
+            self.escape(entry.headword),
+            pronunciation,
+            self.escape(entry.part_of_speech.map(|p| p.0).unwrap_or_default()),
+            self.escape(&entry.glosses.join("; "))
+        )
+    }
+
+    fn index(&self, w: &mut dyn Write, title: &str) -> std::io::Result<()> {
+        writeln!(w, "\\section*{{{}}}", self.escape(title))
+    }
+
+    fn index_entry(
+        &self,
+        w: &mut dyn Write,
+        gloss: &str,
+        headwords: &[&str],
+    ) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "\\par\\noindent {}: \\textbf{{{}}}",
+            self.escape(gloss),
+            self.escape(&headwords.join(", "))
+        )
+    }
+
+    fn end(&self, w: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(w, "\\end{{document}}")
+    }
+}
+
+/// Writes the document with `template`.
+fn write_document(
+    template: &dyn Template,
+    language: &Language,
+    words: &[&Word],
+    collation: &Collation,
+    options: &DocumentOptions,
+    w: &mut dyn Write,
+) -> std::io::Result<()> {
+    let meta = language.meta();
+    template.begin(w, &meta.name, &meta.description)?;
+
+    let mut initial = None;
+    for word in words.iter() {
+        let word_initial = collation.initial(word.romanization());
+        if word_initial.is_some() && word_initial != initial {
+            template.section(
+                w,
+                &word_initial.as_deref().unwrap_or_default().to_uppercase(),
+            )?;
+            initial = word_initial;
+        }
+        template.entry(w, &Entry::from(*word))?;
+    }
+
+    if options.reverse_index {
+        // Headwords are already in alphabetical order.
+        let mut index: BTreeMap<String, (&str, Vec<&str>)> = BTreeMap::new();
+        for word in words.iter() {
+            for gloss in word.glosses() {
+                let (_, headwords) = index
+                    .entry(gloss.to_lowercase())
+                    .or_insert_with(|| (gloss, Vec::new()));
+                if !headwords.contains(&word.romanization()) {
+                    headwords.push(word.romanization());
+                }
+            }
+        }
+
+        if !index.is_empty() {
+            let title = match meta.local_lang.as_str() {
+                "" => "Index".to_string(),
+                lang => format!("{} Index", lang),
+            };
+            template.index(w, &title)?;
+            for (gloss, headwords) in index.values() {
+                template.index_entry(w, gloss, headwords)?;
+            }
+        }
+    }
+
+    template.end(w)
+}
+
+/// Writes the dictionary of `language` as a typeset document, in the alphabetical order of the
+/// language. Returns the number of words written.
+pub fn export_document<W: Write>(
+    language: &Language,
+    mut writer: W,
+    options: &DocumentOptions,
+) -> Result<usize, Error> {
+    let collation = language.collation();
+    let mut words = language
+        .dictionary()
+        .iter_words()
+        .filter(|w| !w.romanization().is_empty())
+        .filter(|w| !options.approved_only || w.status() == WordStatus::Approved)
+        .collect::<Vec<_>>();
+    words.sort_by(|a, b| {
+        collation
+            .compare(a.romanization(), b.romanization())
+            .then(a.id().cmp(&b.id()))
+    });
+
+    let template: &dyn Template = match options.format {
+        DocumentFormat::Markdown => &MarkdownTemplate,
+        DocumentFormat::Html => &HtmlTemplate,
+        DocumentFormat::Latex => &LatexTemplate,
+    };
+    write_document(template, language, &words, &collation, options, &mut writer)?;
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+    use crate::PartOfSpeech;
+
+    use super::*;
+
+    fn test_language() -> Language {
+        let mut language = Language::new();
+        language.meta_mut().name = "Nifuan".to_string();
+        language.meta_mut().local_lang = "English".to_string();
+        language.meta_mut().alphabet = "a ch i k n ng s t u".to_string();
+
+        let dict = language.dictionary_mut();
+        for (rom, pron, tr, pos) in [
+            ("ngata", "ˈŋata", "sun", Some(PartOfSpeech::Noun)),
+            ("nika", "ˈnika", "sun & moon", None),
+            ("chuki", "ˈtʃuki", "walk, go", Some(PartOfSpeech::Verb)),
+            ("asu", "", "go", None),
+        ] {
+            let mut builder = WordBuilder::new()
+                .romanization(rom.to_string())
+                .pronunciation(pron.to_string())
+                .translation(tr.to_string());
+            if let Some(pos) = pos {
+                builder = builder.part_of_speech(pos);
+            }
+            dict.add_word(builder.build());
+        }
+        dict.add_word(
+            WordBuilder::new()
+                .romanization("sika".to_string())
+                .translation("draft".to_string())
+                .status(WordStatus::Draft)
+                .build(),
+        );
+        language
+    }
+
+    fn export(language: &Language, options: &DocumentOptions) -> (usize, String) {
+        let mut buf = Vec::new();
+        let n = export_document(language, &mut buf, options).unwrap();
+        (n, String::from_utf8(buf).unwrap())
+    }
+
+    #[test]
+    fn export_html() {
+        let language = test_language();
+        let (n, html) = export(&language, &DocumentOptions::default());
+        assert_eq!(n, 5);
+
+        let pos = |s: &str| html.find(s).unwrap();
+        // "ng" is a letter after "n".
+        assert!(pos(">asu<") < pos(">chuki<"));
+        assert!(pos(">chuki<") < pos(">nika<"));
+        assert!(pos(">nika<") < pos(">ngata<"));
+        assert!(pos("<h2>CH</h2>") < pos("<h2>NG</h2>"));
+        assert!(html.contains("<abbr class=\"pos\" title=\"Verb\">v.</abbr>"));
+        assert!(html.contains("sun &amp; moon"));
+        assert!(html.contains("<h2>English Index</h2>"));
+        assert!(html.contains("go: <span class=\"headword\">asu, chuki</span>"));
+    }
+
+    #[test]
+    fn export_latex_and_markdown() {
+        let language = test_language();
+        let options = DocumentOptions {
+            format: DocumentFormat::Latex,
+            approved_only: true,
+            reverse_index: false,
+        };
+        let (n, tex) = export(&language, &options);
+        assert_eq!(n, 4);
+        assert!(tex.contains("\\entry{chuki}{/ˈtʃuki/}{v.}{walk; go}"));
+        assert!(tex.contains("sun \\& moon"));
+        assert!(!tex.contains("sika"));
+        assert!(!tex.contains("Index"));
+
+        let options = DocumentOptions {
+            format: DocumentFormat::Markdown,
+            ..Default::default()
+        };
+        let (_, md) = export(&language, &options);
+        assert!(md.starts_with("# Nifuan\n"));
+        assert!(md.contains("\n## NG\n"));
+        assert!(md.contains("**ngata** /ˈŋata/ *n.* sun  \n"));
+        assert!(md.contains("- sun: **ngata**\n"));
+        assert!(md.contains("- sun & moon: **nika**\n"));
+    }
+}
@@ -0,0 +1,22 @@
+use std::convert::Infallible;
+
+use zip::result::ZipError;
+
+use crate::xml::XmlError;
+
+/// Error type that can be emitted by importing or exporting the dictionary.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Filesystem error.
+    #[error("Filesystem error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Malformed CSV data.
+    #[error("CSV error: {0}")]
+    Csv(#[from] ::csv::Error),
+    /// Error at ZIP operation.
+    #[error("Error at ZIP operation: {0}")]
+    Zip(#[from] ZipError),
+    /// Malformed XML data.
+    #[error("Error at XML parsing: {0}")]
+    Xml(#[from] XmlError<Infallible>),
+}
@@ -0,0 +1,208 @@
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::{Dictionary, Word};
+
+/// What to do with an imported word that is already in the dictionary.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Keeps the existing word and discards the imported one.
+    #[default]
+    Skip,
+    /// Fills the empty fields of the existing word from the imported one.
+    Merge,
+    /// Adds the imported word as a new word.
+    Add,
+}
+
+impl DuplicatePolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Skip => "Skip",
+            Self::Merge => "Merge",
+            Self::Add => "Add",
+        }
+    }
+}
+
+pub const ALL_DUPLICATE_POLICIES: &[DuplicatePolicy] = &[
+    DuplicatePolicy::Skip,
+    DuplicatePolicy::Merge,
+    DuplicatePolicy::Add,
+];
+
+/// What an import will do with an imported word.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImportAction {
+    /// Adds the word to the dictionary.
+    Add,
+    /// Discards the word, which duplicates the word of this id.
+    Skip(Uuid),
+    /// Merges the word into the word of this id.
+    Merge(Uuid),
+}
+
+/// Counts of words by what an import did with them.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub merged: usize,
+    pub skipped: usize,
+}
+
+/// Notes on what an import could not convert.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    messages: Vec<String>,
+}
+
+impl ImportReport {
+    /// Adds a note.
+    pub fn warn(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    /// Iterates over notes.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().map(String::as_str)
+    }
+
+    /// Gets the number of notes.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if everything was converted.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+/// Returns `true` if `a` and `b` are taken to be the same word: they have the same romanization,
+/// ignoring case, and the same part of speech if both have one.
+fn is_duplicate(a: &Word, b: &Word) -> bool {
+    a.romanization().to_lowercase() == b.romanization().to_lowercase()
+        && match (a.part_of_speech(), b.part_of_speech()) {
+            (Some(x), Some(y)) => x == y,
+            _ => true,
+        }
+}
+
+/// Decides what to do with each of `words` when imported into `dictionary`, without changing
+/// the dictionary. A word duplicating an earlier word of the batch is treated like one
+/// duplicating a word of the dictionary. Words without romanization are always added.
+pub fn preview_import(
+    dictionary: &Dictionary,
+    words: Vec<Word>,
+    policy: DuplicatePolicy,
+) -> Vec<(Word, ImportAction)> {
+    let mut ret: Vec<(Word, ImportAction)> = Vec::with_capacity(words.len());
+    for mut word in words {
+        let duplicate = match (policy, word.romanization().is_empty()) {
+            (DuplicatePolicy::Add, _) | (_, true) => None,
+            _ => dictionary
+                .iter_words()
+                .chain(
+                    ret.iter()
+                        .filter(|(_, action)| *action == ImportAction::Add)
+                        .map(|(w, _)| w),
+                )
+                .find(|w| is_duplicate(w, &word))
+                .and_then(|w| w.id()),
+        };
+        let action = match (duplicate, policy) {
+            (Some(id), DuplicatePolicy::Merge) => ImportAction::Merge(id),
+            (Some(id), _) => ImportAction::Skip(id),
+            (None, _) => ImportAction::Add,
+        };
+        // Later duplicates refer to the word by its id.
+        if action == ImportAction::Add && word.id().is_none() {
+            word.generate_id();
+        }
+        ret.push((word, action));
+    }
+    ret
+}
+
+/// Fills the empty fields of `word` from `other`, and adds the tags of `other`.
+fn merge_word(word: &mut Word, other: &Word) {
+    if word.translation().is_empty() {
+        word.set_translation(other.translation().to_string());
+    }
+    if word.pronunciation().is_empty() {
+        match other.xsampa_pronunciation() {
+            Some(s) => word.set_xsampa_pronunciation(Some(s.to_string())),
+            None => word.set_pronunciation(other.pronunciation().to_string()),
+        }
+    }
+    if word.part_of_speech().is_none() {
+        word.set_part_of_speech(other.part_of_speech());
+    }
+    for tag in other.tags() {
+        word.add_tag(tag.to_string());
+    }
+}
+
+/// Imports words into `dictionary` as decided by [`preview_import`].
+pub fn apply_import(
+    dictionary: &mut Dictionary,
+    preview: Vec<(Word, ImportAction)>,
+) -> ImportSummary {
+    let mut ret = ImportSummary::default();
+    for (word, action) in preview {
+        match action {
+            ImportAction::Add => {
+                dictionary.add_word(word);
+                ret.added += 1;
+            }
+            ImportAction::Merge(id) => match dictionary.word_by_id_mut(id) {
+                Some(existing) => {
+                    merge_word(existing, &word);
+                    ret.merged += 1;
+                }
+                None => {
+                    dictionary.add_word(word);
+                    ret.added += 1;
+                }
+            },
+            ImportAction::Skip(_) => ret.skipped += 1,
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+
+    use super::*;
+
+    #[test]
+    fn duplicates_in_batch() {
+        let dict = Dictionary::new();
+        let word = |rom: &str, trans: &str| {
+            WordBuilder::new()
+                .romanization(rom.to_string())
+                .translation(trans.to_string())
+                .build()
+        };
+        let words = vec![word("nifu", ""), word("Nifu", "sun"), word("taka", "go")];
+
+        let preview = preview_import(&dict, words.clone(), DuplicatePolicy::Skip);
+        let id = preview[0].0.id().unwrap();
+        assert_eq!(preview[0].1, ImportAction::Add);
+        assert_eq!(preview[1].1, ImportAction::Skip(id));
+        assert_eq!(preview[2].1, ImportAction::Add);
+
+        let preview = preview_import(&dict, words.clone(), DuplicatePolicy::Add);
+        assert!(preview.iter().all(|(_, a)| *a == ImportAction::Add));
+
+        let mut dict = Dictionary::new();
+        let preview = preview_import(&dict, words, DuplicatePolicy::Merge);
+        let id = preview[0].0.id().unwrap();
+        assert_eq!(preview[1].1, ImportAction::Merge(id));
+        let summary = apply_import(&mut dict, preview);
+        assert_eq!((summary.added, summary.merged, summary.skipped), (2, 1, 0));
+        assert_eq!(dict.word_by_id(id).unwrap().translation(), "sun");
+    }
+}
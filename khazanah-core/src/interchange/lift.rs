@@ -0,0 +1,778 @@
+use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
+
+use uuid::Uuid;
+
+use crate::lexicon::{
+    Affix, AffixKind, Allomorph, DerivationLink, FieldDefinition, FieldDefinitions, FieldKind,
+    WordStatus,
+};
+use crate::prelude::*;
+use crate::xml::{XmlError, XmlReader, XmlWriter};
+use crate::{Dictionary, Word};
+
+use super::csv::parse_part_of_speech;
+use super::{Error, ImportReport};
+
+/// The version of LIFT written.
+pub const LIFT_VERSION: &str = "0.13";
+
+/// Relation type linking a derived word to its root.
+const RELATION_COMPONENT: &str = "_component-lexeme";
+/// Relation type linking a deprecated word to its replacement.
+const RELATION_REPLACED_BY: &str = "replaced-by";
+/// Trait of a component relation holding the id of the derivation rule.
+const TRAIT_DERIVATION_RULE: &str = "derivation-rule";
+const TRAIT_MORPH_TYPE: &str = "morph-type";
+const TRAIT_STATUS: &str = "status";
+const TRAIT_TAG: &str = "tag";
+const TRAIT_ENVIRONMENT_LEFT: &str = "environment-left";
+const TRAIT_ENVIRONMENT_RIGHT: &str = "environment-right";
+
+/// Options of writing LIFT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiftOptions {
+    /// Language code of the conlang.
+    pub vernacular: String,
+    /// Language code of translations.
+    pub analysis: String,
+    /// Whether to export only approved words.
+    pub approved_only: bool,
+}
+
+impl Default for LiftOptions {
+    fn default() -> Self {
+        Self {
+            // A code reserved for local use.
+            vernacular: "qaa".to_string(),
+            analysis: "en".to_string(),
+            approved_only: false,
+        }
+    }
+}
+
+/// A relation between entries.
+#[derive(Debug, Default, Clone)]
+struct LiftRelation {
+    kind: String,
+    target: String,
+    rule: Option<String>,
+}
+
+/// An entry as written in LIFT.
+#[derive(Debug, Default, Clone)]
+struct LiftEntry {
+    id: String,
+    guid: Option<String>,
+    romanization: String,
+    pronunciation: String,
+    xsampa: Option<String>,
+    /// Grammatical info of each sense that has one.
+    parts_of_speech: Vec<String>,
+    glosses: Vec<String>,
+    /// Definitions of each sense that has one.
+    definitions: Vec<String>,
+    traits: Vec<(String, String)>,
+    fields: Vec<(String, String)>,
+    relations: Vec<LiftRelation>,
+    variants: Vec<Allomorph>,
+}
+
+/// The contents of a LIFT file.
+#[derive(Debug, Default, Clone)]
+struct LiftDocument {
+    entries: Vec<LiftEntry>,
+    /// Elements of entries that are not converted.
+    skipped: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct LiftReaderState {
+    /// Language of the current form.
+    lang: String,
+    /// Type of the current field.
+    field: String,
+}
+
+impl LiftDocument {
+    /// Gets the path of the current element below `<entry>`.
+    fn entry_path<R>(reader: &XmlReader<R>) -> Vec<&str> {
+        reader.context.iter().skip(2).map(String::as_str).collect()
+    }
+}
+
+impl ReadXml for LiftDocument {
+    type Error = Infallible;
+
+    type ReaderState = LiftReaderState;
+
+    const TAG: &'static str = "lift";
+
+    fn process_tag_start<R: BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        let attr = |key: &str| attrs.iter().find(|x| x.0 == key).map(|x| x.1.clone());
+
+        if reader.context.len() == 2 && name == "entry" {
+            self.entries.push(LiftEntry {
+                id: attr("id").unwrap_or_default(),
+                guid: attr("guid"),
+                ..Default::default()
+            });
+            return Ok(());
+        }
+        let path = Self::entry_path(reader);
+        let Some(entry) = self.entries.last_mut() else {
+            return Ok(());
+        };
+        if reader.context.get(1).map(String::as_str) != Some("entry") {
+            return Ok(());
+        }
+
+        match path.as_slice() {
+            [.., "form"] => state.lang = attr("lang").unwrap_or_default(),
+            ["field"] => {
+                state.field = attr("type").unwrap_or_default();
+                entry.fields.push((state.field.clone(), String::new()));
+            }
+            ["sense", "grammatical-info"] => entry.parts_of_speech.extend(attr("value")),
+            ["sense", "definition"] => entry.definitions.push(String::new()),
+            ["sense", "gloss"] => entry.glosses.push(String::new()),
+            ["trait"] => entry.traits.push((
+                attr("name").unwrap_or_default(),
+                attr("value").unwrap_or_default(),
+            )),
+            ["relation"] | ["sense", "relation"] => entry.relations.push(LiftRelation {
+                kind: attr("type").unwrap_or_default(),
+                target: attr("ref").unwrap_or_default(),
+                rule: None,
+            }),
+            ["relation", "trait"] if attr("name").as_deref() == Some(TRAIT_DERIVATION_RULE) => {
+                if let Some(relation) = entry.relations.last_mut() {
+                    relation.rule = attr("value");
+                }
+            }
+            ["variant"] => entry.variants.push(Allomorph::new(String::new())),
+            ["variant", "trait"] => {
+                if let Some(variant) = entry.variants.last_mut() {
+                    match attr("name").as_deref() {
+                        Some(TRAIT_ENVIRONMENT_LEFT) => variant.left = attr("value"),
+                        Some(TRAIT_ENVIRONMENT_RIGHT) => variant.right = attr("value"),
+                        _ => {}
+                    }
+                }
+            }
+            ["lexical-unit" | "pronunciation" | "sense"]
+            | [.., "text" | "span"]
+            | ["sense", "definition" | "gloss", ..]
+            | ["field" | "lexical-unit" | "pronunciation" | "variant", ..] => {}
+            [tag] | ["sense", tag] => {
+                self.skipped.insert(tag.to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn process_text<R: BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        let path = Self::entry_path(reader);
+        let Some(entry) = self.entries.last_mut() else {
+            return Ok(());
+        };
+        // Text of spans is part of the enclosing text.
+        let path = match path.as_slice() {
+            [rest @ .., "text", "span"] | [rest @ .., "text"] => rest,
+            _ => return Ok(()),
+        };
+
+        match path {
+            ["lexical-unit", "form"] => entry.romanization += &text,
+            ["pronunciation", "form"] => {
+                if state.lang.ends_with("-x-sampa") {
+                    entry.xsampa.get_or_insert_with(String::new).push_str(&text);
+                } else {
+                    entry.pronunciation += &text;
+                }
+            }
+            ["sense", "gloss"] => {
+                if let Some(gloss) = entry.glosses.last_mut() {
+                    *gloss += &text;
+                }
+            }
+            ["sense", "definition", "form"] => {
+                if let Some(definition) = entry.definitions.last_mut() {
+                    *definition += &text;
+                }
+            }
+            ["field", "form"] => {
+                if let Some(field) = entry.fields.last_mut() {
+                    field.1 += &text;
+                }
+            }
+            ["variant", "form"] => {
+                if let Some(variant) = entry.variants.last_mut() {
+                    variant.form += &text;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn process_tag_end<R: BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+/// Converts a LIFT morph type into an affix kind, or `None` if it is not an affix.
+fn affix_kind(morph_type: &str) -> Option<AffixKind> {
+    match morph_type {
+        "prefix" => Some(AffixKind::Prefix),
+        "suffix" => Some(AffixKind::Suffix),
+        "infix" => Some(AffixKind::Infix),
+        "circumfix" => Some(AffixKind::Circumfix),
+        _ => None,
+    }
+}
+
+/// Reads the entries of a LIFT file as words, each with a new id. Entries whose `guid` is the id
+/// of a word of `dictionary` are skipped, so that importing a file again doesn't overwrite edited
+/// words; relations to them resolve to the existing words. Custom fields are matched to `fields`
+/// by name, and added to it if missing. Returns the words along with a report of what cannot be
+/// converted.
+pub fn import_lift<R: BufRead>(
+    reader: R,
+    dictionary: &Dictionary,
+    fields: &mut FieldDefinitions,
+) -> Result<(Vec<Word>, ImportReport), Error> {
+    let (document, _) = LiftDocument::read_xml(reader)?;
+    let mut report = ImportReport::default();
+
+    // Ids of the words of entries, in the order of entries, or `None` for skipped entries.
+    let mut word_ids = Vec::with_capacity(document.entries.len());
+    // Word ids by entry id, to resolve relations.
+    let mut ids = HashMap::new();
+    for entry in document.entries.iter() {
+        let existing = entry
+            .guid
+            .as_deref()
+            .and_then(|g| Uuid::parse_str(g).ok())
+            .and_then(|id| dictionary.word_by_id(id));
+        let id = match existing {
+            Some(word) => {
+                report.warn(format!(
+                    "Entry “{}” is already in the dictionary as “{}” and was skipped",
+                    entry.id,
+                    word.romanization()
+                ));
+                word_ids.push(None);
+                word.id()
+            }
+            None => {
+                let id = Uuid::new_v4();
+                word_ids.push(Some(id));
+                Some(id)
+            }
+        };
+        if entry.id.is_empty() {
+            continue;
+        }
+        if ids.contains_key(&entry.id) {
+            report.warn(format!(
+                "Entry id “{}” is used more than once; relations refer to the first entry",
+                entry.id
+            ));
+        } else if let Some(id) = id {
+            ids.insert(entry.id.clone(), id);
+        }
+    }
+
+    let mut words = Vec::new();
+    for (entry, id) in document.entries.into_iter().zip(word_ids) {
+        let Some(id) = id else {
+            continue;
+        };
+        let mut word = Word::new_with_id(id);
+        word.set_romanization(entry.romanization);
+        if let Some(xsampa) = entry.xsampa {
+            word.set_xsampa_pronunciation(Some(xsampa));
+        }
+        if !entry.pronunciation.is_empty() {
+            word.set_pronunciation(entry.pronunciation);
+        }
+        // Definitions and glosses of every sense are kept, like the senses of a translation.
+        let definitions = entry
+            .definitions
+            .into_iter()
+            .filter(|d| !d.is_empty())
+            .collect::<Vec<_>>();
+        word.set_translation(match definitions.is_empty() {
+            true => entry.glosses.join("; "),
+            false => definitions.join("; "),
+        });
+
+        let mut parts_of_speech = entry.parts_of_speech.into_iter();
+        if let Some(pos) = parts_of_speech.next() {
+            for other in parts_of_speech.filter(|other| *other != pos) {
+                report.warn(format!(
+                    "Entry “{}” has senses of grammatical info “{}” and “{}”; the first is kept",
+                    entry.id, pos, other
+                ));
+            }
+            match parse_part_of_speech(&pos) {
+                Some(pos) => word.set_part_of_speech(Some(pos)),
+                None => {
+                    report.warn(format!(
+                        "Grammatical info “{}” has no equivalent and is kept as a tag",
+                        pos
+                    ));
+                    word.add_tag(pos);
+                }
+            }
+        }
+
+        for (name, value) in entry.traits {
+            match name.as_str() {
+                TRAIT_STATUS => word.set_status(WordStatus::from(value.as_str())),
+                TRAIT_TAG => {
+                    word.add_tag(value);
+                }
+                TRAIT_MORPH_TYPE => {
+                    if let Some(kind) = affix_kind(&value) {
+                        word.set_affix(Some(Affix::new(kind)));
+                    }
+                }
+                _ => report.warn(format!(
+                    "Trait “{}” of “{}” was not imported",
+                    name, entry.id
+                )),
+            }
+        }
+        if let Some(affix) = word.affix_mut() {
+            affix.allomorphs = entry.variants;
+        }
+
+        for (name, value) in entry.fields {
+            let id = match fields.field_by_name(&name).and_then(|f| f.id()) {
+                Some(id) => id,
+                None => fields.add_field(FieldDefinition::new(name, FieldKind::Text)),
+            };
+            word.set_field(id, Some(value));
+        }
+
+        for relation in entry.relations {
+            let Some(target) = ids.get(&relation.target).copied() else {
+                report.warn(format!(
+                    "Relation of “{}” to missing entry “{}” was not imported",
+                    entry.id, relation.target
+                ));
+                continue;
+            };
+            match relation.kind.as_str() {
+                RELATION_REPLACED_BY => word.set_replaced_by(Some(target)),
+                RELATION_COMPONENT => {
+                    match relation.rule.as_deref().and_then(|r| Uuid::parse_str(r).ok()) {
+                        Some(rule_id) => word.set_derivation(Some(DerivationLink {
+                            root_id: target,
+                            rule_id,
+                        })),
+                        None => report.warn(format!(
+                            "Component relation of “{}” has no derivation rule and was not imported",
+                            entry.id
+                        )),
+                    }
+                }
+                kind => report.warn(format!(
+                    "Relation “{}” of “{}” was not imported",
+                    kind, entry.id
+                )),
+            }
+        }
+
+        words.push(word);
+    }
+
+    for element in document.skipped {
+        report.warn(format!("Element <{}> of entries was not imported", element));
+    }
+    Ok((words, report))
+}
+
+/// Writes a `<form>` with text.
+fn write_form<W: Write>(
+    w: &mut XmlWriter<W>,
+    lang: &str,
+    text: &str,
+) -> Result<(), XmlError<Infallible>> {
+    w.write_tag_start_with_attributes("form", [("lang", lang)])?;
+    w.write_tag_start("text")?;
+    w.write_text(text)?;
+    w.write_tag_end("text")?;
+    w.write_tag_end("form")
+}
+
+/// Writes a `<trait>`.
+fn write_trait<W: Write>(
+    w: &mut XmlWriter<W>,
+    name: &str,
+    value: &str,
+) -> Result<(), XmlError<Infallible>> {
+    w.write_tag_start_with_attributes("trait", [("name", name), ("value", value)])?;
+    w.write_tag_end("trait")
+}
+
+/// Writes an entry for `word`.
+fn write_entry<W: Write>(
+    w: &mut XmlWriter<W>,
+    word: &Word,
+    fields: &FieldDefinitions,
+    options: &LiftOptions,
+) -> Result<(), XmlError<Infallible>> {
+    let id = word.id().unwrap_or_default().to_string();
+    w.write_tag_start_with_attributes("entry", [("id", id.as_str()), ("guid", id.as_str())])?;
+
+    w.write_tag_start("lexical-unit")?;
+    write_form(w, &options.vernacular, word.romanization())?;
+    w.write_tag_end("lexical-unit")?;
+
+    if !word.pronunciation().is_empty() || word.xsampa_pronunciation().is_some() {
+        w.write_tag_start("pronunciation")?;
+        write_form(
+            w,
+            &format!("{}-fonipa", options.vernacular),
+            word.pronunciation(),
+        )?;
+        if let Some(xsampa) = word.xsampa_pronunciation() {
+            write_form(w, &format!("{}-x-sampa", options.vernacular), xsampa)?;
+        }
+        w.write_tag_end("pronunciation")?;
+    }
+
+    if let Some(affix) = word.affix() {
+        write_trait(w, TRAIT_MORPH_TYPE, &affix.kind.name().to_lowercase())?;
+        for allomorph in affix.allomorphs.iter() {
+            w.write_tag_start("variant")?;
+            write_form(w, &options.vernacular, &allomorph.form)?;
+            if let Some(left) = &allomorph.left {
+                write_trait(w, TRAIT_ENVIRONMENT_LEFT, left)?;
+            }
+            if let Some(right) = &allomorph.right {
+                write_trait(w, TRAIT_ENVIRONMENT_RIGHT, right)?;
+            }
+            w.write_tag_end("variant")?;
+        }
+    }
+    if word.status() != WordStatus::default() {
+        write_trait(w, TRAIT_STATUS, word.status().name())?;
+    }
+    for tag in word.tags() {
+        write_trait(w, TRAIT_TAG, tag)?;
+    }
+
+    for (field_id, value) in word.fields() {
+        if let Some(field) = fields.field_by_id(field_id) {
+            w.write_tag_start_with_attributes("field", [("type", field.name())])?;
+            write_form(w, &options.analysis, value)?;
+            w.write_tag_end("field")?;
+        }
+    }
+
+    if let Some(link) = word.derivation() {
+        let root = link.root_id.to_string();
+        w.write_tag_start_with_attributes(
+            "relation",
+            [("type", RELATION_COMPONENT), ("ref", root.as_str())],
+        )?;
+        write_trait(w, TRAIT_DERIVATION_RULE, &link.rule_id.to_string())?;
+        w.write_tag_end("relation")?;
+    }
+    if let Some(target) = word.replaced_by() {
+        let target = target.to_string();
+        w.write_tag_start_with_attributes(
+            "relation",
+            [("type", RELATION_REPLACED_BY), ("ref", target.as_str())],
+        )?;
+        w.write_tag_end("relation")?;
+    }
+
+    w.write_tag_start("sense")?;
+    if let Some(pos) = word.part_of_speech() {
+        w.write_tag_start_with_attributes("grammatical-info", [("value", pos.name())])?;
+        w.write_tag_end("grammatical-info")?;
+    }
+    for gloss in word.glosses() {
+        w.write_tag_start_with_attributes("gloss", [("lang", options.analysis.as_str())])?;
+        w.write_tag_start("text")?;
+        w.write_text(gloss)?;
+        w.write_tag_end("text")?;
+        w.write_tag_end("gloss")?;
+    }
+    // The definition keeps the translation as written.
+    if !word.translation().is_empty() {
+        w.write_tag_start("definition")?;
+        write_form(w, &options.analysis, word.translation())?;
+        w.write_tag_end("definition")?;
+    }
+    w.write_tag_end("sense")?;
+
+    w.write_tag_end("entry")
+}
+
+/// Writes the words of `dictionary` as LIFT, sorted by romanization. Returns the number of words
+/// written.
+pub fn export_lift<W: Write>(
+    dictionary: &Dictionary,
+    fields: &FieldDefinitions,
+    writer: W,
+    options: &LiftOptions,
+) -> Result<usize, Error> {
+    let mut words = dictionary
+        .iter_words()
+        .filter(|w| !options.approved_only || w.status() == WordStatus::Approved)
+        .collect::<Vec<_>>();
+    words.sort_by(|a, b| {
+        a.romanization()
+            .cmp(b.romanization())
+            .then(a.id().cmp(&b.id()))
+    });
+
+    let mut w = XmlWriter::new(writer);
+    w.write_init()?;
+    w.write_tag_start_with_attributes("lift", [("version", LIFT_VERSION)])?;
+    for word in words.iter() {
+        write_entry(&mut w, word, fields, options)?;
+    }
+    w.write_tag_end("lift")?;
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+    use crate::PartOfSpeech;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut fields = FieldDefinitions::new();
+        let register = fields.add_field(FieldDefinition::new(
+            "Register".to_string(),
+            FieldKind::Text,
+        ));
+
+        let mut dict = Dictionary::new();
+        let root = dict.add_word(
+            WordBuilder::new()
+                .romanization("taka".to_string())
+                .xsampa_pronunciation("taka".to_string())
+                .translation("go, walk".to_string())
+                .part_of_speech(PartOfSpeech::Verb)
+                .build(),
+        );
+        let mut derived = WordBuilder::new()
+            .romanization("takatu".to_string())
+            .pronunciation("ˈta.ka.tu".to_string())
+            .translation("walker".to_string())
+            .status(WordStatus::Deprecated)
+            .build();
+        derived.set_derivation(Some(DerivationLink {
+            root_id: root,
+            rule_id: Uuid::new_v4(),
+        }));
+        derived.set_replaced_by(Some(root));
+        derived.add_tag("archaic".to_string());
+        derived.set_field(register, Some("formal & <old>".to_string()));
+        dict.add_word(derived);
+        let mut affix = Word::new();
+        affix.set_romanization("-tu".to_string());
+        let mut suffix = Affix::new(AffixKind::Suffix);
+        suffix.allomorphs = vec![
+            Allomorph {
+                form: "tu".to_string(),
+                left: Some("V".to_string()),
+                right: None,
+            },
+            Allomorph::new("u".to_string()),
+        ];
+        affix.set_affix(Some(suffix));
+        dict.add_word(affix);
+
+        let mut buf = Vec::new();
+        let n = export_lift(&dict, &fields, &mut buf, &LiftOptions::default()).unwrap();
+        assert_eq!(n, 3);
+
+        let mut fields2 = fields.clone();
+        let (words, report) = import_lift(&buf[..], &Dictionary::new(), &mut fields2).unwrap();
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(fields2, fields);
+
+        // Imported words have new ids, so they are compared by romanization.
+        let mut dict2 = Dictionary::new();
+        for word in words {
+            dict2.add_word(word);
+        }
+        let find = |dict: &Dictionary, id: Uuid| {
+            let rom = dict.word_by_id(id).unwrap().romanization().to_string();
+            move |w: &&Word| w.romanization() == rom
+        };
+        let root2 = dict2.iter_words().find(find(&dict, root)).unwrap().id();
+        assert_ne!(root2, Some(root));
+        for word in dict.iter_words() {
+            let word2 = dict2
+                .iter_words()
+                .find(find(&dict, word.id().unwrap()))
+                .unwrap();
+            assert_eq!(word2.translation(), word.translation());
+            assert_eq!(word2.pronunciation(), word.pronunciation());
+            assert_eq!(word2.part_of_speech(), word.part_of_speech());
+            assert_eq!(word2.status(), word.status());
+            assert_eq!(word2.affix(), word.affix());
+            assert!(word2.tags().eq(word.tags()));
+            assert!(word2.fields().eq(word.fields()));
+            assert_eq!(word2.replaced_by().is_some(), word.replaced_by().is_some());
+            assert_eq!(
+                word2.derivation().map(|d| d.rule_id),
+                word.derivation().map(|d| d.rule_id)
+            );
+        }
+        let derived2 = dict2
+            .iter_words()
+            .find(|w| w.romanization() == "takatu")
+            .unwrap();
+        assert_eq!(derived2.replaced_by(), root2);
+        assert_eq!(derived2.derivation().map(|d| d.root_id), root2);
+    }
+
+    #[test]
+    fn import_fresh_ids() {
+        let xml = r#"
+            <lift version="0.13">
+                <entry><lexical-unit><form lang="xyz"><text>nifu</text></form></lexical-unit></entry>
+                <entry><lexical-unit><form lang="xyz"><text>taka</text></form></lexical-unit></entry>
+                <entry id="pe" guid="00000000-0000-0000-0000-000000000001">
+                    <lexical-unit><form lang="xyz"><text>pe</text></form></lexical-unit>
+                </entry>
+            </lift>
+        "#;
+        let mut dict = Dictionary::new();
+        let (words, report) =
+            import_lift(xml.as_bytes(), &dict, &mut FieldDefinitions::new()).unwrap();
+        assert!(report.is_empty(), "{:?}", report);
+        assert_eq!(words.len(), 3);
+        assert_ne!(
+            words[2].id(),
+            Uuid::parse_str("00000000-0000-0000-0000-000000000001").ok()
+        );
+        for word in words {
+            dict.add_word(word);
+        }
+        assert_eq!(dict.iter_words().count(), 3);
+    }
+
+    #[test]
+    fn import_existing_guid() {
+        let mut dict = Dictionary::new();
+        let id = dict.add_word(
+            WordBuilder::new()
+                .romanization("nifu".to_string())
+                .translation("sun, edited".to_string())
+                .build(),
+        );
+        let xml = format!(
+            r#"
+            <lift version="0.13">
+                <entry id="nifu" guid="{id}">
+                    <lexical-unit><form lang="xyz"><text>nifu</text></form></lexical-unit>
+                </entry>
+                <entry id="nifutu">
+                    <lexical-unit><form lang="xyz"><text>nifutu</text></form></lexical-unit>
+                    <relation type="replaced-by" ref="nifu"/>
+                </entry>
+            </lift>
+        "#
+        );
+        let (words, report) =
+            import_lift(xml.as_bytes(), &dict, &mut FieldDefinitions::new()).unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].replaced_by(), Some(id));
+        assert_eq!(report.len(), 1);
+        assert!(report
+            .iter()
+            .any(|m| m.contains("already in the dictionary")));
+        assert_eq!(dict.word_by_id(id).unwrap().translation(), "sun, edited");
+    }
+
+    #[test]
+    fn import_foreign() {
+        let xml = r#"
+            <lift version="0.13">
+                <header><ranges/></header>
+                <entry id="nifu_1">
+                    <lexical-unit><form lang="xyz"><text>nifu</text></form></lexical-unit>
+                    <sense id="s1">
+                        <grammatical-info value="Noun"/>
+                        <gloss lang="en"><text>sun</text></gloss>
+                        <gloss lang="en"><text>day</text></gloss>
+                        <example><form lang="xyz"><text>nifu taka</text></form></example>
+                    </sense>
+                    <relation type="synonym" ref="nifa_2"/>
+                    <note><form lang="en"><text>common</text></form></note>
+                </entry>
+                <entry id="nifa_2">
+                    <lexical-unit><form lang="xyz"><text>ni<span>fa</span></text></form></lexical-unit>
+                </entry>
+            </lift>
+        "#;
+        let mut fields = FieldDefinitions::new();
+        let (words, report) = import_lift(xml.as_bytes(), &Dictionary::new(), &mut fields).unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].romanization(), "nifu");
+        assert_eq!(words[0].translation(), "sun; day");
+        assert_eq!(words[0].part_of_speech(), Some(PartOfSpeech::Noun));
+        assert_eq!(words[1].romanization(), "nifa");
+        assert_eq!(report.len(), 3);
+    }
+
+    #[test]
+    fn import_senses() {
+        let xml = r#"
+            <lift version="0.13">
+                <entry id="nifu_1">
+                    <lexical-unit><form lang="xyz"><text>nifu</text></form></lexical-unit>
+                    <sense id="s1">
+                        <grammatical-info value="Noun"/>
+                        <definition><form lang="en"><text>sun</text></form></definition>
+                    </sense>
+                    <sense id="s2">
+                        <grammatical-info value="Verb"/>
+                        <definition><form lang="en"><text>to shine</text></form></definition>
+                    </sense>
+                </entry>
+            </lift>
+        "#;
+        let mut fields = FieldDefinitions::new();
+        let (words, report) = import_lift(xml.as_bytes(), &Dictionary::new(), &mut fields).unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].translation(), "sun; to shine");
+        assert_eq!(words[0].part_of_speech(), Some(PartOfSpeech::Noun));
+        assert_eq!(
+            report.iter().collect::<Vec<_>>(),
+            ["Entry “nifu_1” has senses of grammatical info “Noun” and “Verb”; the first is kept"]
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::io::{BufReader, Read, Seek};
+
+use crate::lexicon::{FieldDefinition, FieldKind};
+use crate::prelude::*;
+use crate::xml::{XmlError, XmlReader};
+use crate::{Language, PartOfSpeech, Phoneme, Word};
+
+use super::csv::parse_part_of_speech;
+use super::{Error, ImportReport};
+
+/// Name of the dictionary file in a PolyGlot archive.
+pub const POLYGLOT_DICTIONARY_FILE: &str = "PGDictionary.xml";
+
+/// Name of the custom field holding PolyGlot definitions.
+pub const POLYGLOT_DEFINITION_FIELD: &str = "Definition";
+
+/// Sections of a PolyGlot dictionary that are converted.
+const CONVERTED_SECTIONS: &[&str] = &[
+    "PolyGlotVer",
+    "languageProperties",
+    "partsOfSpeech",
+    "lexicon",
+    "pronunciationCollection",
+];
+
+/// Children of a PolyGlot word that are converted, or that need no conversion.
+const CONVERTED_WORD_CHILDREN: &[&str] = &[
+    "wordId",
+    "conWord",
+    "localWord",
+    "wordTypeId",
+    "pronunciation",
+    "definition",
+    "wordProcOverride",
+    "autoDeclOverride",
+    "wordRuleOverride",
+];
+
+/// A word of a PolyGlot lexicon.
+#[derive(Debug, Default, Clone)]
+struct PgdWord {
+    con_word: String,
+    local_word: String,
+    class_id: String,
+    pronunciation: String,
+    definition: String,
+}
+
+/// A part of speech of a PolyGlot dictionary.
+#[derive(Debug, Default, Clone)]
+struct PgdClass {
+    id: String,
+    name: String,
+    gloss: String,
+}
+
+/// A pronunciation rule of a PolyGlot dictionary, which maps a pattern of the romanization to
+/// a sound.
+#[derive(Debug, Default, Clone)]
+struct PgdGuide {
+    base: String,
+    phon: String,
+}
+
+/// The contents of `PGDictionary.xml`.
+#[derive(Debug, Default, Clone)]
+struct PgdDocument {
+    version: String,
+    name: String,
+    local_lang: String,
+    author: String,
+    words: Vec<PgdWord>,
+    classes: Vec<PgdClass>,
+    guides: Vec<PgdGuide>,
+    /// Sections that have contents but are not converted.
+    skipped: BTreeSet<String>,
+    /// Children of words that have contents but are not converted, with the number of words
+    /// having them.
+    skipped_word_children: BTreeMap<String, usize>,
+    /// Children of the current word already counted in `skipped_word_children`.
+    counted_word_children: BTreeSet<String>,
+}
+
+impl PgdDocument {
+    /// Notes that the current word has contents in `child`, which is not converted. Each child
+    /// is counted once per word, however many elements or texts it has.
+    fn skip_word_child(&mut self, child: &str) {
+        if !CONVERTED_WORD_CHILDREN.contains(&child)
+            && self.counted_word_children.insert(child.to_string())
+        {
+            *self
+                .skipped_word_children
+                .entry(child.to_string())
+                .or_default() += 1;
+        }
+    }
+}
+
+impl ReadXml for PgdDocument {
+    type Error = Infallible;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "dictionary";
+
+    fn process_tag_start<R: BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+        _attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (Some("lexicon"), Some("word")) => {
+                self.words.push(PgdWord::default());
+                self.counted_word_children.clear();
+            }
+            (Some("partsOfSpeech"), Some("class")) => self.classes.push(PgdClass::default()),
+            (Some("pronunciationCollection"), Some("proGuide")) => {
+                self.guides.push(PgdGuide::default())
+            }
+            _ => {
+                if let Some(section) = reader.context.get(1) {
+                    if reader.context.len() == 3 && !CONVERTED_SECTIONS.contains(&section.as_str())
+                    {
+                        self.skipped.insert(section.clone());
+                    }
+                }
+                // An element nested in a child of a word, such as a root in <roots>.
+                if reader.context.len() == 5
+                    && reader.context[1] == "lexicon"
+                    && reader.context[2] == "word"
+                {
+                    let child = reader.context[3].clone();
+                    self.skip_word_child(&child);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn process_text<R: BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (Some(Self::TAG), Some("PolyGlotVer")) => self.version += &text,
+            (Some("languageProperties"), Some("langName")) => self.name += &text,
+            (Some("languageProperties"), Some("langPropLocalLangName")) => self.local_lang += &text,
+            (Some("languageProperties"), Some("langPropAuthorCopyright")) => self.author += &text,
+            (Some("word"), Some(tag)) => {
+                if let Some(word) = self.words.last_mut() {
+                    match tag {
+                        "conWord" => word.con_word += &text,
+                        "localWord" => word.local_word += &text,
+                        "wordTypeId" => word.class_id += &text,
+                        "pronunciation" => word.pronunciation += &text,
+                        "definition" => word.definition += &text,
+                        _ if !text.trim().is_empty() => {
+                            let tag = tag.to_string();
+                            self.skip_word_child(&tag);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            (Some("class"), Some(tag)) => {
+                if let Some(class) = self.classes.last_mut() {
+                    match tag {
+                        "classId" => class.id += &text,
+                        "className" => class.name += &text,
+                        "classGloss" => class.gloss += &text,
+                        _ => {}
+                    }
+                }
+            }
+            (Some("proGuide"), Some(tag)) => {
+                if let Some(guide) = self.guides.last_mut() {
+                    match tag {
+                        "proBase" => guide.base += &text,
+                        "proPhon" => guide.phon += &text,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn process_tag_end<R: BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+/// Removes HTML markup from a PolyGlot definition.
+fn strip_html(s: &str) -> String {
+    let mut ret = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => ret.push(c),
+            _ => {}
+        }
+    }
+    ret.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns `true` if a PolyGlot pronunciation pattern is plain text rather than a regular
+/// expression.
+fn is_literal(pattern: &str) -> bool {
+    !pattern.chars().any(|c| "^$.*+?()[]{}|\\".contains(c))
+}
+
+impl PgdDocument {
+    /// Converts the document into a language, noting what cannot be converted in `report`.
+    fn into_language(self, report: &mut ImportReport) -> Language {
+        let mut language = Language::new();
+
+        let meta = language.meta_mut();
+        meta.name = self.name;
+        meta.local_lang = self.local_lang;
+        meta.author = self.author;
+        if !self.version.is_empty() {
+            meta.description = format!("Imported from PolyGlot {}.", self.version);
+        }
+
+        // Parts of speech that Khazanah doesn't have are kept as tags.
+        let mut classes: BTreeMap<String, Result<PartOfSpeech, String>> = BTreeMap::new();
+        for class in self.classes {
+            let pos = parse_part_of_speech(&class.name)
+                .or_else(|| parse_part_of_speech(&class.gloss))
+                .ok_or(class.name.clone());
+            if pos.is_err() {
+                report.warn(format!(
+                    "Part of speech “{}” has no equivalent and is kept as a tag",
+                    class.name
+                ));
+            }
+            classes.insert(class.id, pos);
+        }
+
+        for guide in self.guides {
+            if guide.phon.is_empty() {
+                continue;
+            }
+            if !is_literal(&guide.base) {
+                report.warn(format!(
+                    "Pronunciation rule “{}” → “{}” is a pattern and was not converted",
+                    guide.base, guide.phon
+                ));
+                continue;
+            }
+            let inventory = language.phonemic_inventory_mut();
+            if inventory.iter_phonemes().any(|p| p.sound() == guide.phon) {
+                continue;
+            }
+            let mut phoneme = Phoneme::with_sound(guide.phon);
+            phoneme.set_romanization(Some(guide.base));
+            inventory.add_phoneme(phoneme);
+        }
+
+        let mut definition_field = None;
+        for pgd_word in self.words {
+            let mut word = Word::new();
+            word.set_romanization(pgd_word.con_word);
+            word.set_pronunciation(pgd_word.pronunciation);
+            match classes.get(&pgd_word.class_id) {
+                Some(Ok(pos)) => word.set_part_of_speech(Some(*pos)),
+                Some(Err(name)) => {
+                    word.add_tag(name.clone());
+                }
+                None => {}
+            }
+
+            let definition = strip_html(&pgd_word.definition);
+            if pgd_word.local_word.is_empty() {
+                word.set_translation(definition);
+            } else {
+                word.set_translation(pgd_word.local_word);
+                if !definition.is_empty() {
+                    let id = *definition_field.get_or_insert_with(|| {
+                        language
+                            .field_definitions_mut()
+                            .add_field(FieldDefinition::new(
+                                POLYGLOT_DEFINITION_FIELD.to_string(),
+                                FieldKind::Text,
+                            ))
+                    });
+                    word.set_field(id, Some(definition));
+                }
+            }
+            language.dictionary_mut().add_word(word);
+        }
+
+        for section in self.skipped {
+            report.warn(format!("Section <{}> was not converted", section));
+        }
+        for (child, n) in self.skipped_word_children {
+            report.warn(format!(
+                "Word field <{}> of {} word(s) was not converted",
+                child, n
+            ));
+        }
+
+        language
+    }
+}
+
+/// Imports a language from the XML of a PolyGlot dictionary.
+pub fn import_polyglot_xml<R: BufRead>(reader: R) -> Result<(Language, ImportReport), Error> {
+    let (document, _) = PgdDocument::read_xml(reader)?;
+    let mut report = ImportReport::default();
+    let language = document.into_language(&mut report);
+    Ok((language, report))
+}
+
+/// Imports a language from a PolyGlot archive (`.pgd`). Returns the language along with a report
+/// of what cannot be converted.
+pub fn import_polyglot<R: Read + Seek>(reader: R) -> Result<(Language, ImportReport), Error> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let file = archive.by_name(POLYGLOT_DICTIONARY_FILE)?;
+    import_polyglot_xml(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn import_sample() {
+        let data = include_bytes!("../../data/tests/sample.pgd");
+        let (language, report) = import_polyglot(Cursor::new(&data[..])).unwrap();
+
+        assert_eq!(language.meta().name, "Nifuan");
+        assert_eq!(language.meta().local_lang, "English");
+        assert_eq!(language.meta().author, "Jane Doe");
+
+        let dict = language.dictionary();
+        let word = |rom: &str| dict.iter_words().find(|w| w.romanization() == rom).unwrap();
+        assert_eq!(dict.iter_words().count(), 3);
+        assert_eq!(word("nifu").translation(), "sun");
+        assert_eq!(word("nifu").pronunciation(), "ˈnifu");
+        assert_eq!(word("nifu").part_of_speech(), Some(PartOfSpeech::Noun));
+        assert_eq!(word("taka").translation(), "to walk");
+        assert_eq!(word("taka").part_of_speech(), Some(PartOfSpeech::Verb));
+        assert!(word("pe").has_tag("Classifier"));
+
+        let field = language
+            .field_definitions()
+            .field_by_name(POLYGLOT_DEFINITION_FIELD)
+            .and_then(|f| f.id())
+            .unwrap();
+        assert_eq!(
+            word("nifu").field(field),
+            Some("The star at the centre of the sky.")
+        );
+
+        let inventory = language.phonemic_inventory();
+        assert_eq!(inventory.iter_phonemes().count(), 2);
+        assert!(inventory
+            .iter_phonemes()
+            .any(|p| p.sound() == "ŋ" && p.romanization() == Some("ng")));
+
+        assert_eq!(report.len(), 3);
+        assert!(report.iter().any(|m| m.contains("Classifier")));
+        assert!(report.iter().any(|m| m.contains("^a")));
+        assert!(report.iter().any(|m| m.contains("etymologyCollection")));
+    }
+
+    #[test]
+    fn report_word_children() {
+        let xml = r#"<dictionary>
+  <lexicon>
+    <word>
+      <wordId>1</wordId>
+      <conWord>ni&amp;fu</conWord>
+      <localWord>sun</localWord>
+      <wordProcOverride>F</wordProcOverride>
+      <wordEtymNotes>from <i>*nip</i> and *fu</wordEtymNotes>
+      <roots><rootId>2</rootId><rootId>3</rootId></roots>
+    </word>
+    <word>
+      <conWord>taka</conWord>
+      <wordEtymNotes>   </wordEtymNotes>
+      <roots><rootId>1</rootId></roots>
+    </word>
+  </lexicon>
+</dictionary>"#;
+        let (language, report) = import_polyglot_xml(xml.as_bytes()).unwrap();
+
+        let dict = language.dictionary();
+        assert!(dict.iter_words().any(|w| w.romanization() == "ni&fu"));
+        assert_eq!(
+            report.iter().collect::<Vec<_>>(),
+            vec![
+                "Word field <roots> of 2 word(s) was not converted",
+                "Word field <wordEtymNotes> of 1 word(s) was not converted",
+            ]
+        );
+    }
+}
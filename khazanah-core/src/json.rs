@@ -0,0 +1,328 @@
+//! JSON reading and writing, as an alternative to XML for scripts.
+//!
+//! ---
+//!
+//! The schema follows the XML format: field names are the XML tag names, except that X-SAMPA
+//! attributes become fields of their own, and collections of items with ids are arrays sorted
+//! by id. Optional fields are omitted when absent, and empty collections may be omitted. A
+//! project looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 3,
+//!   "language": {
+//!     "meta": {
+//!       "name": "Nifuan", "local-lang": "English", "author": "", "description": "",
+//!       "alphabet": "a i k n u"
+//!     },
+//!     "phonemic-inventory": [
+//!       { "id": "…", "sound": "n", "xsampa-sound": "n", "romanization": "n", "mora": 0 }
+//!     ],
+//!     "phoneme-categories": [
+//!       { "id": "…", "name": "C", "phonemes": ["…"] }
+//!     ],
+//!     "phonotactic": {
+//!       "onset": [
+//!         {
+//!           "pattern": "C", "mora": 0, "likeliness": 1,
+//!           "learned-weights": { "likeliness": 3, "phonemes": { "<phoneme id>": 3 } }
+//!         }
+//!       ],
+//!       "nucleus": [],
+//!       "coda": []
+//!     },
+//!     "dictionary": [
+//!       {
+//!         "id": "…", "romanization": "nifu", "translation": "sun",
+//!         "pronunciation": "ˈnifu", "xsampa-pronunciation": "\"nifu",
+//!         "part-of-speech": "Noun", "status": "Approved",
+//!         "tags": ["nature"], "fields": { "<field id>": "value" },
+//!         "derived-from": { "root": "…", "rule": "…" }, "replaced-by": "…",
+//!         "affix": { "kind": "Suffix", "allomorphs": [{ "form": "tu", "left": "V" }] },
+//!         "media": ["<asset id>"]
+//!       }
+//!     ],
+//!     "derivations": [
+//!       {
+//!         "id": "…", "name": "Agent", "prefix": "", "suffix": "tu",
+//!         "from-part-of-speech": "Verb", "to-part-of-speech": "Noun",
+//!         "gloss-template": "one who {gloss}s"
+//!       }
+//!     ],
+//!     "field-definitions": [
+//!       { "id": "…", "name": "Register", "kind": "Enum", "options": ["formal"] }
+//!     ]
+//!   },
+//!   "corpus": [
+//!     {
+//!       "id": "…", "title": "Sunrise",
+//!       "sentences": [
+//!         {
+//!           "source": "nifu taka", "segmented": "nifu taka", "gloss": "sun walk",
+//!           "translation": "The sun walks.",
+//!           "links": [{ "token": "nifu", "start": 0, "word-id": "…" }]
+//!         }
+//!       ]
+//!     }
+//!   ],
+//!   "history": {
+//!     "<word id>": [
+//!       {
+//!         "timestamp": 1700000000, "field": "translation",
+//!         "old-value": "moon", "new-value": "sun", "reason": "typo"
+//!       }
+//!     ]
+//!   },
+//!   "media": [
+//!     { "id": "…", "name": "nifu.ogg", "kind": "Audio", "mime-type": "audio/ogg" }
+//!   ]
+//! }
+//! ```
+//!
+//! Ids are UUIDs in hyphenated form. A history field is one of `romanization`,
+//! `translation`, `pronunciation`, `part-of-speech`, `tags`, `status`, or
+//! `{ "field": "<field id>" }` for custom fields.
+//!
+//! The contents of media assets are not part of the JSON. They are stored as files next to it in
+//! the project archive; see [`crate::project`].
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use serde_json::Error as JsonError;
+
+/// A trait for objects that can be read from JSON.
+pub trait ReadJson
+where
+    Self: Sized + DeserializeOwned,
+{
+    /// Reads from JSON.
+    fn read_json<R: Read>(reader: R) -> Result<Self, JsonError> {
+        serde_json::from_reader(reader)
+    }
+
+    /// Loads from JSON file.
+    fn load_json_file<P: AsRef<Path>>(path: P) -> Result<Self, JsonError> {
+        let f = File::open(path).map_err(JsonError::io)?;
+        Self::read_json(BufReader::new(f))
+    }
+
+    /// Loads from JSON string.
+    fn load_json_str(s: &str) -> Result<Self, JsonError> {
+        serde_json::from_str(s)
+    }
+}
+
+/// A trait for objects that can be written into JSON.
+pub trait WriteJson
+where
+    Self: Serialize,
+{
+    /// Writes to JSON, indented for readability.
+    fn write_json<W: Write>(&self, mut writer: W) -> Result<W, JsonError> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        Ok(writer)
+    }
+
+    /// Saves to JSON file.
+    fn save_json_file<P: AsRef<Path>>(&self, path: P) -> Result<(), JsonError> {
+        let f = File::create(path).map_err(JsonError::io)?;
+        self.write_json(BufWriter::new(f))?
+            .flush()
+            .map_err(JsonError::io)
+    }
+
+    /// Saves to JSON string.
+    fn save_json_string(&self) -> Result<String, JsonError> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use uuid::Uuid;
+
+    use crate::corpus::{Sentence, Text};
+    use crate::lexicon::{
+        Affix, AffixKind, Allomorph, Derivation, DerivationLink, FieldDefinition, FieldKind,
+        WordBuilder, WordStatus,
+    };
+    use crate::phonology::{Categories, Category, Inventory, LearnedWeights, Pattern, Phonotactic};
+    use crate::{Dictionary, PartOfSpeech, Phoneme, Project, ProjectFormat, Word};
+
+    use super::*;
+
+    fn test_project() -> Project {
+        let mut project = Project::new();
+        let language = project.language_mut();
+
+        let meta = language.meta_mut();
+        meta.name = "Nifuan".to_string();
+        meta.local_lang = "English".to_string();
+        meta.alphabet = "a i k n u".to_string();
+
+        let mut phoneme = Phoneme::with_sound("ŋ".to_string());
+        phoneme.set_xsampa_sound(Some("N".to_string()));
+        phoneme.set_romanization(Some("ng".to_string()));
+        let phoneme_id = language.phonemic_inventory_mut().add_phoneme(phoneme);
+        language
+            .phonemic_inventory_mut()
+            .add_phoneme(Phoneme::with_sound("a".to_string()));
+
+        let mut category = Category::new();
+        category.set_name("C".to_string());
+        category.add_phoneme_id(phoneme_id);
+        language.phoneme_categories_mut().add_category(category);
+
+        let mut pattern = Pattern::new("C".to_string());
+        pattern.learned = Some(LearnedWeights {
+            likeliness: 3,
+            phonemes: [(phoneme_id, 3)].into(),
+        });
+        language
+            .phonotactic_mut()
+            .onset_patterns_mut()
+            .push(pattern);
+
+        let field_id = language
+            .field_definitions_mut()
+            .add_field(FieldDefinition::new(
+                "Register".to_string(),
+                FieldKind::Enum,
+            ));
+        let mut rule = Derivation::new();
+        rule.set_name("Agent".to_string());
+        rule.set_suffix("tu".to_string());
+        rule.set_to_part_of_speech(Some(PartOfSpeech::ProperNoun));
+        let rule_id = language.derivations_mut().add_derivation(rule);
+
+        let dict = language.dictionary_mut();
+        let root_id = dict.add_word(
+            WordBuilder::new()
+                .romanization("taka".to_string())
+                .xsampa_pronunciation("taka".to_string())
+                .translation("walk".to_string())
+                .part_of_speech(PartOfSpeech::Verb)
+                .build(),
+        );
+        let mut derived = WordBuilder::new()
+            .romanization("takatu".to_string())
+            .translation("walker".to_string())
+            .status(WordStatus::Deprecated)
+            .build();
+        derived.set_derivation(Some(DerivationLink { root_id, rule_id }));
+        derived.set_replaced_by(Some(root_id));
+        derived.add_tag("archaic".to_string());
+        derived.set_field(field_id, Some("formal".to_string()));
+        let derived_id = dict.add_word(derived);
+        let mut affix = Word::new();
+        affix.set_romanization("-tu".to_string());
+        affix.set_affix(Some(Affix {
+            kind: AffixKind::Suffix,
+            allomorphs: vec![Allomorph {
+                form: "tu".to_string(),
+                left: Some("V".to_string()),
+                right: None,
+            }],
+        }));
+        dict.add_word(affix);
+
+        let mut text = Text::new();
+        text.set_title("Walking".to_string());
+        let mut sentence = Sentence {
+            source: "taka takatu".to_string(),
+            translation: "The walker walks.".to_string(),
+            ..Default::default()
+        };
+        sentence.set_links([Some(root_id), None]);
+        text.add_sentence(sentence);
+        project.corpus_mut().add_text(text);
+
+        project.update_word(derived_id, |w| w.set_translation("wanderer".to_string()));
+        project
+    }
+
+    #[test]
+    fn project_round_trip() {
+        let project = test_project();
+
+        // Load through the XML path first, so both sides went through a file.
+        let xml = project.save(Cursor::new(Vec::new())).unwrap().into_inner();
+        let from_xml = Project::load(Cursor::new(xml)).unwrap();
+
+        let json = from_xml.save_json_string().unwrap();
+        let from_json = Project::load_json_str(&json).unwrap();
+        assert_eq!(from_json.language(), from_xml.language());
+        assert_eq!(from_json.corpus(), from_xml.corpus());
+        assert_eq!(from_json.history(), from_xml.history());
+        assert_eq!(from_json.save_json_string().unwrap(), json);
+    }
+
+    #[test]
+    fn project_stored_as_json() {
+        let mut project = test_project();
+        project.set_format(ProjectFormat::Json);
+
+        let data = project.save(Cursor::new(Vec::new())).unwrap().into_inner();
+        let loaded = Project::load(Cursor::new(data)).unwrap();
+        assert_eq!(loaded.format(), ProjectFormat::Json);
+        assert_eq!(
+            loaded.save_json_string().unwrap(),
+            project.save_json_string().unwrap()
+        );
+
+        assert!(Project::load_json_str(r#"{ "version": 99 }"#).is_err());
+    }
+
+    #[test]
+    fn stores_round_trip() {
+        let project = test_project();
+        let language = project.language();
+
+        let json = language.dictionary().save_json_string().unwrap();
+        assert!(json.contains(r#""xsampa-pronunciation":"#));
+        assert_eq!(
+            &Dictionary::load_json_str(&json).unwrap(),
+            language.dictionary()
+        );
+        let json = language.phoneme_categories().save_json_string().unwrap();
+        assert_eq!(
+            &Categories::load_json_str(&json).unwrap(),
+            language.phoneme_categories()
+        );
+        let json = language.phonotactic().save_json_string().unwrap();
+        assert!(json.contains(r#""learned-weights":"#));
+        assert_eq!(
+            &Phonotactic::load_json_str(&json).unwrap(),
+            language.phonotactic()
+        );
+        let json = language.phonemic_inventory().save_json_string().unwrap();
+        let inventory = Inventory::load_json_str(&json).unwrap();
+        assert_eq!(
+            inventory
+                .iter_phonemes()
+                .collect::<std::collections::BTreeSet<_>>(),
+            language.phonemic_inventory().iter_phonemes().collect()
+        );
+
+        let json = Dictionary::new().save_json_string().unwrap();
+        assert_eq!(json, "[]");
+        let word: serde_json::Value =
+            serde_json::from_str(&language.dictionary().save_json_string().unwrap()).unwrap();
+        let derived = word
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|w| w["romanization"] == "takatu")
+            .unwrap();
+        assert_eq!(derived["status"], "Deprecated");
+        assert!(Uuid::parse_str(derived["derived-from"]["root"].as_str().unwrap()).is_ok());
+    }
+}
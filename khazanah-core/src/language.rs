@@ -1,7 +1,14 @@
-use crate::phonology::{Categories, Category, Phonotactic};
+use crate::analysis::{self, LexiconStatistics, MinimalPairMatrix};
+use crate::lexicon::{
+    self, Collation, Derivation, Derivations, FieldDefinition, FieldDefinitions, HomonymReport,
+    Query, ReverseIndex, Segmentation,
+};
+use crate::phonology::{self, Categories, Category, Pattern, Phonotactic};
+use crate::prelude::*;
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 use crate::{phonology::Inventory, Dictionary};
 use crate::{Phoneme, Word};
+use serde::{Deserialize, Serialize};
 
 pub use error::Error;
 pub use meta::Meta;
@@ -11,13 +18,16 @@ mod error;
 mod meta;
 
 /// A language.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Language {
     meta: Meta,
     phonemic_inventory: Inventory,
     phoneme_categories: Categories,
     phonotactic: Phonotactic,
     dictionary: Dictionary,
+    derivations: Derivations,
+    field_definitions: FieldDefinitions,
 }
 
 pub struct LanguageStores<'a> {
@@ -25,6 +35,8 @@ pub struct LanguageStores<'a> {
     pub phoneme_categories: &'a Categories,
     pub phonotactic: &'a Phonotactic,
     pub dictionary: &'a Dictionary,
+    pub derivations: &'a Derivations,
+    pub field_definitions: &'a FieldDefinitions,
 }
 
 pub struct LanguageStoresMut<'a> {
@@ -32,6 +44,8 @@ pub struct LanguageStoresMut<'a> {
     pub phoneme_categories: &'a mut Categories,
     pub phonotactic: &'a mut Phonotactic,
     pub dictionary: &'a mut Dictionary,
+    pub derivations: &'a mut Derivations,
+    pub field_definitions: &'a mut FieldDefinitions,
 }
 
 impl Language {
@@ -47,6 +61,8 @@ impl Language {
             phoneme_categories: &self.phoneme_categories,
             phonotactic: &self.phonotactic,
             dictionary: &self.dictionary,
+            derivations: &self.derivations,
+            field_definitions: &self.field_definitions,
         }
     }
 
@@ -58,6 +74,8 @@ impl Language {
             phoneme_categories: &mut self.phoneme_categories,
             phonotactic: &mut self.phonotactic,
             dictionary: &mut self.dictionary,
+            derivations: &mut self.derivations,
+            field_definitions: &mut self.field_definitions,
         }
     }
 
@@ -69,6 +87,11 @@ impl Language {
         &mut self.meta
     }
 
+    /// Gets the alphabetical order of the language, from its alphabet.
+    pub fn collation(&self) -> Collation {
+        Collation::new(&self.meta.alphabet)
+    }
+
     /// PHONEMIC INVENTORY
 
     /// Gets a reference to phonemic inventory store.
@@ -97,6 +120,11 @@ impl Language {
         )
     }
 
+    /// Finds minimal pairs in the dictionary for every pair of phonemes in the inventory.
+    pub fn phonemic_inventory_minimal_pairs(&self) -> MinimalPairMatrix {
+        MinimalPairMatrix::new(&self.dictionary, &self.phonemic_inventory)
+    }
+
     // PHONEME CATEGORIES
 
     /// Gets a reference to phoneme categories store.
@@ -131,6 +159,32 @@ impl Language {
         &mut self.phonotactic
     }
 
+    /// Learns the weights of the phonotactic patterns from the pronunciations of the words in
+    /// the dictionary. Returns the number of syllables matching the phonotactic.
+    pub fn phonotactic_learn_weights(&mut self) -> Result<usize, phonology::Error> {
+        let syllables = self
+            .dictionary
+            .iter_words()
+            .flat_map(|w| analysis::syllabify(w.pronunciation(), &self.phonemic_inventory))
+            .map(|s| s.iter_segments().map(|(s, _)| s).collect::<String>())
+            .collect::<Vec<_>>();
+        self.phonotactic.learn_weights(
+            syllables.iter().map(String::as_str),
+            &self.phoneme_categories,
+            &self.phonemic_inventory,
+        )
+    }
+
+    /// Resets the weights of the phonotactic patterns to uniform.
+    pub fn phonotactic_reset_weights(&mut self) {
+        self.phonotactic.reset_weights();
+    }
+
+    /// Clears the weights of the phonotactic patterns, so their likeliness is used again.
+    pub fn phonotactic_clear_weights(&mut self) {
+        self.phonotactic.clear_weights();
+    }
+
     // DICTIONARY
 
     /// Gets a reference to dictionary store.
@@ -146,11 +200,187 @@ impl Language {
     /// Removes a word of id `id` from lexicon. Returns `true` if removal is successful.
     /// If `cascade` is `true`, any reference to the word is also removed.
     /// If `cascade` is `false`, the operation fails if any reference to the word exists.
-    pub fn dictionary_remove_word_by_id(&mut self, id: Uuid, _cascade: bool) -> Option<Word> {
+    pub fn dictionary_remove_word_by_id(&mut self, id: Uuid, cascade: bool) -> Option<Word> {
+        let derivatives: Vec<Uuid> = self.dictionary.derivative_ids(id).collect();
+        if !derivatives.is_empty() {
+            if !cascade {
+                return None;
+            }
+            for did in derivatives {
+                if let Some(word) = self.dictionary.word_by_id_mut(did) {
+                    word.set_derivation(None);
+                }
+            }
+        }
+
+        for word in self.dictionary.iter_words_mut() {
+            if word.replaced_by() == Some(id) {
+                word.set_replaced_by(None);
+            }
+        }
+
         self.dictionary.remove_word_by_id(id)
     }
+
+    /// Derives a new word from the word of id `root_id` using rule of id `rule_id`, adds it to
+    /// the dictionary and returns its id. Returns `None` if either doesn't exist, or if the rule
+    /// doesn't apply to the word.
+    pub fn dictionary_derive_word(&mut self, root_id: Uuid, rule_id: Uuid) -> Option<Uuid> {
+        let root = self.dictionary.word_by_id(root_id)?;
+        let rule = self.derivations.derivation_by_id(rule_id)?;
+        if !rule.applies_to(root) {
+            return None;
+        }
+
+        let word = rule.derive(root, &self.phonemic_inventory);
+        Some(self.dictionary.add_word(word))
+    }
+
+    /// Recomputes every word derived from the word of id `root_id`, including derivatives of
+    /// derivatives. Returns the number of refreshed words.
+    pub fn dictionary_refresh_derivatives(&mut self, root_id: Uuid) -> usize {
+        let mut ret = 0;
+        let mut visited = vec![root_id];
+        let mut queue = vec![root_id];
+
+        while let Some(rid) = queue.pop() {
+            let Some(root) = self.dictionary.word_by_id(rid).cloned() else {
+                continue;
+            };
+            let derivatives: Vec<Uuid> = self.dictionary.derivative_ids(rid).collect();
+
+            for did in derivatives {
+                if visited.contains(&did) {
+                    continue;
+                }
+                visited.push(did);
+
+                let rule = self
+                    .dictionary
+                    .word_by_id(did)
+                    .and_then(Word::derivation)
+                    .and_then(|der| self.derivations.derivation_by_id(der.rule_id));
+                if let (Some(rule), Some(word)) = (rule, self.dictionary.word_by_id_mut(did)) {
+                    rule.derive_into(&root, word, &self.phonemic_inventory);
+                    ret += 1;
+                    queue.push(did);
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Splits the romanization of the word of id `id` into morphemes known by the dictionary.
+    /// The word itself is not used as a morpheme.
+    pub fn dictionary_segment_word(&self, id: Uuid) -> Option<Segmentation> {
+        let word = self.dictionary.word_by_id(id)?;
+        lexicon::segment(
+            word.romanization(),
+            Some(id),
+            &self.dictionary,
+            &self.phoneme_categories,
+            &self.phonemic_inventory,
+        )
+    }
+
+    /// Gets the ids of words satisfying `query`.
+    pub fn dictionary_search(&self, query: &Query) -> Vec<Uuid> {
+        self.dictionary
+            .iter_words()
+            .filter(|w| query.matches(w, &self.field_definitions))
+            .filter_map(|w| w.id())
+            .collect()
+    }
+
+    /// Gets the ids of words whose pronunciation matches the phonological pattern `pattern`,
+    /// such as `"#CVNC"`.
+    pub fn dictionary_search_pronunciation(
+        &self,
+        pattern: &str,
+    ) -> Result<Vec<Uuid>, phonology::Error> {
+        self.dictionary.search_pronunciation(
+            &Pattern::new(pattern.to_string()),
+            &self.phoneme_categories,
+            &self.phonemic_inventory,
+        )
+    }
+
+    /// Builds a reverse index of the dictionary, from keywords of translations to words.
+    /// Stemming and stopwords are used unless the local language is set to one other than
+    /// English.
+    pub fn dictionary_reverse_index(&self) -> ReverseIndex {
+        let local_lang = self.meta.local_lang.trim();
+        let english = local_lang.is_empty() || local_lang.eq_ignore_ascii_case("english");
+        ReverseIndex::new(&self.dictionary, english)
+    }
+
+    /// Finds homonyms in the dictionary, and near-duplicates whose pronunciations are at most
+    /// `max_distance` segments apart.
+    pub fn dictionary_homonyms(&self, max_distance: usize) -> HomonymReport {
+        HomonymReport::new(&self.dictionary, &self.phonemic_inventory, max_distance)
+    }
+
+    /// Gets the ids of words sharing a pronunciation or a romanization with the word of id `id`.
+    pub fn dictionary_homonyms_of(&self, id: Uuid) -> Vec<Uuid> {
+        self.dictionary
+            .word_by_id(id)
+            .map(|word| lexicon::homonyms_of(word, &self.dictionary, &self.phonemic_inventory))
+            .unwrap_or_default()
+    }
+
+    /// Counts phoneme and syllable statistics of the words in the dictionary.
+    pub fn dictionary_statistics(&self) -> LexiconStatistics {
+        LexiconStatistics::new(&self.dictionary, &self.phonemic_inventory)
+    }
+
+    // DERIVATIONS
+
+    /// Gets a reference to derivation rules store.
+    pub fn derivations(&self) -> &Derivations {
+        &self.derivations
+    }
+
+    /// Gets a mutable reference to derivation rules store.
+    pub fn derivations_mut(&mut self) -> &mut Derivations {
+        &mut self.derivations
+    }
+
+    /// Removes a derivation rule by id. Words derived with the rule are unlinked from it.
+    pub fn derivations_remove_derivation_by_id(&mut self, id: Uuid) -> Option<Derivation> {
+        for word in self.dictionary.iter_words_mut() {
+            if word.derivation().map(|der| der.rule_id) == Some(id) {
+                word.set_derivation(None);
+            }
+        }
+        self.derivations.remove_derivation_by_id(id)
+    }
+
+    // CUSTOM FIELDS
+
+    /// Gets a reference to custom field definitions store.
+    pub fn field_definitions(&self) -> &FieldDefinitions {
+        &self.field_definitions
+    }
+
+    /// Gets a mutable reference to custom field definitions store.
+    pub fn field_definitions_mut(&mut self) -> &mut FieldDefinitions {
+        &mut self.field_definitions
+    }
+
+    /// Removes a custom field definition by id, along with the values of the field in words.
+    pub fn field_definitions_remove_field_by_id(&mut self, id: Uuid) -> Option<FieldDefinition> {
+        for word in self.dictionary.iter_words_mut() {
+            word.set_field(id, None);
+        }
+        self.field_definitions.remove_field_by_id(id)
+    }
 }
 
+impl ReadJson for Language {}
+
+impl WriteJson for Language {}
+
 impl ReadXml for Language {
     type Error = Error;
 
@@ -188,6 +418,15 @@ impl ReadXml for Language {
                 self.dictionary = Dictionary::deserialize_xml(reader, Some((name, attrs)))
                     .map_err(|xe| xe.map_into())?;
             }
+            (Some("lexicon"), Some(Derivations::TAG)) => {
+                self.derivations = Derivations::deserialize_xml(reader, Some((name, attrs)))
+                    .map_err(|xe| xe.map_into())?;
+            }
+            (Some("lexicon"), Some(FieldDefinitions::TAG)) => {
+                self.field_definitions =
+                    FieldDefinitions::deserialize_xml(reader, Some((name, attrs)))
+                        .map_err(|xe| xe.map_into())?;
+            }
             _ => return Err(XmlError::InvalidTag(name)),
         }
 
@@ -240,6 +479,12 @@ impl WriteXml for Language {
         self.dictionary
             .serialize_xml(writer)
             .map_err(|xe| xe.map_into())?;
+        self.derivations
+            .serialize_xml(writer)
+            .map_err(|xe| xe.map_into())?;
+        self.field_definitions
+            .serialize_xml(writer)
+            .map_err(|xe| xe.map_into())?;
         writer.write_tag_end("lexicon")?;
 
         writer.write_tag_end("language")?;
@@ -2,9 +2,11 @@ use std::io::{BufRead, Write};
 
 use super::Error;
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+use serde::{Deserialize, Serialize};
 
 /// Metadata for a language.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Meta {
     /// Name of the project, as well as the language family.
     pub name: String,
@@ -14,6 +16,8 @@ pub struct Meta {
     pub author: String,
     /// The description of the project.
     pub description: String,
+    /// Letters of the language in alphabetical order, separated by whitespace.
+    pub alphabet: String,
 }
 
 impl Meta {
@@ -60,6 +64,9 @@ impl ReadXml for Meta {
             (Some(Self::TAG), Some("description")) => {
                 self.description.clear();
             }
+            (Some(Self::TAG), Some("alphabet")) => {
+                self.alphabet.clear();
+            }
             // Invalid tag
             _ => return Err(XmlError::InvalidTag(tag.unwrap_or_default().to_string())),
         }
@@ -79,6 +86,7 @@ impl ReadXml for Meta {
             Some("local-lang") => self.local_lang += &text,
             Some("author") => self.author += &text,
             Some("description") => self.description += &text,
+            Some("alphabet") => self.alphabet += &text,
             _ => {}
         }
         Ok(())
@@ -116,6 +124,12 @@ impl WriteXml for Meta {
         w.write_text(&self.description)?;
         w.write_tag_end("description")?;
 
+        if !self.alphabet.is_empty() {
+            w.write_tag_start("alphabet")?;
+            w.write_text(&self.alphabet)?;
+            w.write_tag_end("alphabet")?;
+        }
+
         w.write_tag_end("meta")?;
 
         Ok(())
@@ -132,6 +146,7 @@ mod tests {
             local_lang: "English".to_owned(),
             author: "ManEnfu".to_owned(),
             description: "This is a language.".to_owned(),
+            alphabet: "a e i k m n u".to_owned(),
         }
     }
 
@@ -143,6 +158,7 @@ mod tests {
                 <local-lang>English</local-lang>
                 <author>ManEnfu</author>
                 <description>This is a language.</description>
+                <alphabet>a e i k m n u</alphabet>
             </meta>
         "#
         .to_string()
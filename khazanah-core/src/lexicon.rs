@@ -1,11 +1,41 @@
 //! Module for lexicon and related data structures.
 
+pub use affix::{Affix, AffixKind, Allomorph, ALL_AFFIX_KINDS, CIRCUMFIX_SEPARATOR, WORD_BOUNDARY};
+pub use collation::Collation;
+pub use concept_list::{
+    normalize_gloss, Concept, ConceptList, Coverage, CONCEPT_ALTERNATIVE_SEPARATOR,
+};
+pub use derivation::{Derivation, DerivationLink, GLOSS_PLACEHOLDER};
+pub use derivations::Derivations;
 pub use dictionary::Dictionary;
 pub use error::Error;
+pub use field::{FieldDefinition, FieldKind, FieldValue, ALL_FIELD_KINDS};
+pub use field_definitions::FieldDefinitions;
+pub use history::{now, History, Revision, WordField, BUILTIN_WORD_FIELDS, HISTORY_MERGE_SECONDS};
+pub use homonyms::{homonyms_of, HomonymGroup, HomonymKind, HomonymReport, NearPair};
 pub use pos::{PartOfSpeech, ALL_PARTS_OF_SPEECH};
+pub use query::{
+    Query, QueryError, QueryErrorKind, QueryKey, QueryMatcher, QueryTerm, PATTERN_KEYS,
+};
+pub use reverse_index::{keywords, stem, ReverseIndex, ReverseMatch, STOPWORDS};
+pub use segmentation::{segment, Morpheme, Segmentation};
+pub use status::{WordStatus, ALL_WORD_STATUSES};
 pub use word::{Word, WordBuilder};
 
+mod affix;
+mod collation;
+mod concept_list;
+mod derivation;
+mod derivations;
 mod dictionary;
 mod error;
+mod field;
+mod field_definitions;
+mod history;
+mod homonyms;
 mod pos;
+mod query;
+mod reverse_index;
+mod segmentation;
+mod status;
 mod word;
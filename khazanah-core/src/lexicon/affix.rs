@@ -0,0 +1,218 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::phonology::{Categories, Inventory};
+
+/// The separator between the two parts of a circumfix form, as in `"ge...t"`.
+pub const CIRCUMFIX_SEPARATOR: &str = "...";
+
+/// The environment symbol for a word boundary.
+pub const WORD_BOUNDARY: &str = "#";
+
+/// How an affix attaches to its base.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+    Infix,
+    Circumfix,
+}
+
+impl AffixKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Prefix => "Prefix",
+            Self::Suffix => "Suffix",
+            Self::Infix => "Infix",
+            Self::Circumfix => "Circumfix",
+        }
+    }
+}
+
+impl From<&str> for AffixKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "Prefix" => Self::Prefix,
+            "Suffix" => Self::Suffix,
+            "Infix" => Self::Infix,
+            "Circumfix" => Self::Circumfix,
+            _ => Self::Suffix,
+        }
+    }
+}
+
+/// All affix kinds, preceded by `None` for words that are not affixes.
+pub const ALL_AFFIX_KINDS: &[Option<AffixKind>] = &[
+    None,
+    Some(AffixKind::Prefix),
+    Some(AffixKind::Suffix),
+    Some(AffixKind::Infix),
+    Some(AffixKind::Circumfix),
+];
+
+/// A surface form of an affix, optionally conditioned by its neighbouring sounds.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Allomorph {
+    /// The romanization of the form. Circumfix forms separate both parts with `...`.
+    pub form: String,
+    /// Category name the preceding sound must belong to, or `#` for a word boundary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<String>,
+    /// Category name the following sound must belong to, or `#` for a word boundary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<String>,
+}
+
+impl Allomorph {
+    /// Creates a new unconditioned allomorph.
+    pub fn new(form: String) -> Self {
+        Self {
+            form,
+            ..Default::default()
+        }
+    }
+
+    /// Splits a circumfix form into its leading and trailing parts.
+    pub fn circumfix_parts(&self) -> (&str, &str) {
+        self.form
+            .split_once(CIRCUMFIX_SEPARATOR)
+            .unwrap_or((self.form.as_str(), ""))
+    }
+
+    /// Returns `true` if the allomorph can occur between `left_text` and `right_text`.
+    pub fn matches_environment(
+        &self,
+        left_text: &str,
+        right_text: &str,
+        categories: &Categories,
+        inventory: &Inventory,
+    ) -> bool {
+        let check = |cond: &Option<String>, text: &str, at_end: bool| match cond.as_deref() {
+            None | Some("") => true,
+            Some(WORD_BOUNDARY) => text.is_empty(),
+            Some(name) => categories
+                .category_by_name(name)
+                .map(|cat| {
+                    cat.iter_phonemes(inventory).any(|p| {
+                        let r = p.display_romanization();
+                        !r.is_empty()
+                            && if at_end {
+                                text.ends_with(r)
+                            } else {
+                                text.starts_with(r)
+                            }
+                    })
+                })
+                .unwrap_or(false),
+        };
+
+        check(&self.left, left_text, true) && check(&self.right, right_text, false)
+    }
+}
+
+impl Display for Allomorph {
+    /// Formats the allomorph with its environment in sound change notation, e.g. `tu / V_`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.form)?;
+        if self.left.is_some() || self.right.is_some() {
+            write!(
+                f,
+                " / {}_{}",
+                self.left.as_deref().unwrap_or_default(),
+                self.right.as_deref().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Allomorph {
+    type Err = Infallible;
+
+    /// Parses an allomorph in the notation of its `Display` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (form, env) = s.split_once('/').unwrap_or((s, ""));
+        let (left, right) = env.split_once('_').unwrap_or((env, ""));
+        let cond = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        Ok(Self {
+            form: form.trim().to_string(),
+            left: cond(left),
+            right: cond(right),
+        })
+    }
+}
+
+/// Attachment information of an affix entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affix {
+    /// How the affix attaches to its base.
+    pub kind: AffixKind,
+    /// The possible surface forms of the affix.
+    pub allomorphs: Vec<Allomorph>,
+}
+
+impl Affix {
+    /// Creates a new affix with no allomorphs.
+    pub fn new(kind: AffixKind) -> Self {
+        Self {
+            kind,
+            allomorphs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{phonology::Category, Phoneme};
+
+    #[test]
+    fn environment() {
+        let mut cats = Categories::new();
+        let mut inv = Inventory::new();
+
+        let mut cat = Category::new();
+        cat.set_name("V".to_string());
+        cat.add_phoneme_id(inv.add_phoneme(Phoneme::with_sound("a".to_string())));
+        cat.add_phoneme_id(inv.add_phoneme(Phoneme::with_sound("u".to_string())));
+        let _ = cats.add_category(cat);
+
+        let allo = Allomorph {
+            form: "tu".to_string(),
+            left: Some("V".to_string()),
+            right: Some(WORD_BOUNDARY.to_string()),
+        };
+        assert!(allo.matches_environment("nifu", "", &cats, &inv));
+        assert!(!allo.matches_environment("nif", "", &cats, &inv));
+        assert!(!allo.matches_environment("nifu", "n", &cats, &inv));
+
+        let allo = Allomorph::new("ge...t".to_string());
+        assert_eq!(allo.circumfix_parts(), ("ge", "t"));
+        assert!(allo.matches_environment("", "", &cats, &inv));
+    }
+
+    #[test]
+    fn allomorph_notation() {
+        let allo = Allomorph {
+            form: "tu".to_string(),
+            left: Some("V".to_string()),
+            right: None,
+        };
+        assert_eq!(allo.to_string(), "tu / V_");
+        assert_eq!("tu / V_".parse::<Allomorph>().unwrap(), allo);
+        assert_eq!(
+            "u".parse::<Allomorph>().unwrap(),
+            Allomorph::new("u".to_string())
+        );
+        assert_eq!(
+            " ge...t / _# "
+                .parse::<Allomorph>()
+                .unwrap()
+                .right
+                .as_deref(),
+            Some(WORD_BOUNDARY)
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::cmp::Ordering;
+
+/// A custom alphabetical order of romanizations. Letters may span several characters, such as
+/// `"ng"`, and are matched case-insensitively. Characters that are not in the alphabet sort
+/// after all letters, by their code point.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Collation {
+    /// Letters in alphabetical order, in lowercase.
+    letters: Vec<String>,
+    /// The length of the longest letter, in bytes.
+    max_len: usize,
+}
+
+/// A unit of a sort key: either a letter of the alphabet by its position, or another character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SortUnit {
+    Letter(usize),
+    Other(char),
+}
+
+impl Collation {
+    /// Creates a collation from an alphabet of whitespace-separated letters, as in
+    /// `"a b c ch d"`. An empty alphabet orders by code point, ignoring case.
+    pub fn new(alphabet: &str) -> Self {
+        let mut letters: Vec<String> = Vec::new();
+        for letter in alphabet.split_whitespace().map(str::to_lowercase) {
+            if !letters.contains(&letter) {
+                letters.push(letter);
+            }
+        }
+        let max_len = letters.iter().map(String::len).max().unwrap_or_default();
+        Self { letters, max_len }
+    }
+
+    /// Returns `true` if the collation has no letters.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Iterates over the letters, in order.
+    pub fn letters(&self) -> impl Iterator<Item = &str> {
+        self.letters.iter().map(String::as_str)
+    }
+
+    /// Splits `s` into sort units, matching the longest letter first.
+    fn sort_key(&self, s: &str) -> Vec<SortUnit> {
+        let s = s.to_lowercase();
+        let mut ret = Vec::new();
+        let mut rest = s.as_str();
+        while let Some(c) = rest.chars().next() {
+            let letter = (1..=self.max_len.min(rest.len()))
+                .rev()
+                .filter_map(|n| rest.get(..n))
+                .find_map(|p| {
+                    self.letters
+                        .iter()
+                        .position(|l| l == p)
+                        .map(|i| (i, p.len()))
+                });
+            match letter {
+                Some((i, len)) => {
+                    ret.push(SortUnit::Letter(i));
+                    rest = &rest[len..];
+                }
+                None => {
+                    // Marks and punctuation don't affect the order.
+                    if c.is_alphanumeric() {
+                        ret.push(SortUnit::Other(c));
+                    }
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        ret
+    }
+
+    /// Compares two romanizations. Ties are broken by code point, so the order is total.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.sort_key(a)
+            .cmp(&self.sort_key(b))
+            .then_with(|| a.cmp(b))
+    }
+
+    /// Gets the first letter of `s`, used to group entries under a heading. Returns `None` if
+    /// `s` has no letters.
+    pub fn initial(&self, s: &str) -> Option<String> {
+        match self.sort_key(s).first()? {
+            SortUnit::Letter(i) => self.letters.get(*i).cloned(),
+            SortUnit::Other(c) => Some(c.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_with_digraphs() {
+        let collation = Collation::new("a c ch d h n ng");
+        let mut words = vec!["da", "Cha", "ngo", "na", "ca", "x", "ha"];
+        words.sort_by(|a, b| collation.compare(a, b));
+        assert_eq!(words, ["ca", "Cha", "da", "ha", "na", "ngo", "x"]);
+
+        assert_eq!(collation.initial("Chaka").as_deref(), Some("ch"));
+        assert_eq!(collation.initial("-nga").as_deref(), Some("ng"));
+        assert_eq!(collation.initial("zu").as_deref(), Some("z"));
+    }
+
+    #[test]
+    fn compare_without_alphabet() {
+        let collation = Collation::default();
+        assert!(collation.is_empty());
+        assert_eq!(collation.compare("Bo", "ab"), Ordering::Greater);
+        assert_eq!(collation.compare("ab", "Ab"), Ordering::Greater);
+    }
+}
@@ -0,0 +1,296 @@
+use uuid::Uuid;
+
+use crate::prelude::*;
+
+use super::{Dictionary, Word, WordBuilder};
+
+/// The separator between alternative glosses of a concept, as in `"flesh / meat"`.
+pub const CONCEPT_ALTERNATIVE_SEPARATOR: char = '/';
+
+/// A concept in a reference wordlist.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Concept {
+    /// The English gloss of the concept, e.g. `"bark (of a tree)"` or `"flesh / meat"`.
+    pub gloss: String,
+}
+
+impl Concept {
+    /// Creates a new concept.
+    pub fn new(gloss: String) -> Self {
+        Self { gloss }
+    }
+
+    /// Iterates over the normalized glosses of the concept that a word translation can match.
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.gloss
+            .split(CONCEPT_ALTERNATIVE_SEPARATOR)
+            .map(normalize_gloss)
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Returns `true` if the word has a sense matching the concept.
+    pub fn matches(&self, word: &Word) -> bool {
+        let senses = word.glosses().map(normalize_gloss).collect::<Vec<_>>();
+        self.keys().any(|k| senses.contains(&k))
+    }
+
+    /// Creates a word with the concept as its translation and no romanization, to be filled
+    /// later.
+    pub fn placeholder_word(&self) -> Word {
+        let translation = self
+            .gloss
+            .split(CONCEPT_ALTERNATIVE_SEPARATOR)
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(", ");
+        WordBuilder::new().translation(translation).build()
+    }
+}
+
+/// Normalizes a gloss for matching. Lowercases, and removes parenthesized remarks and the
+/// infinitive marker `to`, so `"To lie (as in a bed)"` becomes `"lie"`.
+pub fn normalize_gloss(s: &str) -> String {
+    let mut ret = String::new();
+    let mut depth = 0;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = std::cmp::max(depth - 1, 0),
+            '?' | '!' => {}
+            _ if depth == 0 => ret.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    let ret = ret.split_whitespace().collect::<Vec<_>>();
+    match ret.split_first() {
+        Some((&"to", rest)) if !rest.is_empty() => rest.join(" "),
+        _ => ret.join(" "),
+    }
+}
+
+/// A reference wordlist, such as the Swadesh list.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConceptList {
+    /// The name of the list.
+    pub name: String,
+    /// The concepts of the list, in order.
+    pub concepts: Vec<Concept>,
+}
+
+/// Coverage of a concept list by a dictionary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// For each concept of the list, by index, the ids of the words matching it.
+    pub matches: Vec<Vec<Uuid>>,
+}
+
+impl Coverage {
+    /// Iterates over the indices of concepts matched by at least a word.
+    pub fn covered(&self) -> impl Iterator<Item = usize> + '_ {
+        self.matches
+            .iter()
+            .enumerate()
+            .filter(|(_, ids)| !ids.is_empty())
+            .map(|(i, _)| i)
+    }
+
+    /// Iterates over the indices of concepts not matched by any word.
+    pub fn missing(&self) -> impl Iterator<Item = usize> + '_ {
+        self.matches
+            .iter()
+            .enumerate()
+            .filter(|(_, ids)| ids.is_empty())
+            .map(|(i, _)| i)
+    }
+
+    /// Gets the number of concepts matched by at least a word.
+    pub fn n_covered(&self) -> usize {
+        self.covered().count()
+    }
+
+    /// Gets the fraction of the concepts matched by at least a word.
+    pub fn ratio(&self) -> f64 {
+        if self.matches.is_empty() {
+            0.0
+        } else {
+            self.n_covered() as f64 / self.matches.len() as f64
+        }
+    }
+}
+
+impl ConceptList {
+    /// Creates a new, empty concept list.
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            concepts: Vec::new(),
+        }
+    }
+
+    /// Gets the Swadesh 207-word list.
+    pub fn swadesh_207() -> Self {
+        Self::from_text(
+            "Swadesh 207".to_string(),
+            include_str!("../../data/concepts/swadesh_207.txt"),
+        )
+    }
+
+    /// Gets the Leipzig–Jakarta list of basic vocabulary.
+    pub fn leipzig_jakarta() -> Self {
+        Self::from_text(
+            "Leipzig–Jakarta".to_string(),
+            include_str!("../../data/concepts/leipzig_jakarta.txt"),
+        )
+    }
+
+    /// Gets the built-in concept lists.
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::swadesh_207(), Self::leipzig_jakarta()]
+    }
+
+    /// Parses a concept list from text with one concept per line. Empty lines and lines
+    /// starting with `#` are skipped, and a leading number such as `12.` or `12<tab>` is
+    /// removed.
+    pub fn from_text(name: String, text: &str) -> Self {
+        let concepts = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| {
+                let rest = l.trim_start_matches(|c: char| c.is_ascii_digit());
+                let rest = if rest.len() < l.len() {
+                    rest.trim_start_matches(['.', ')', '\t', ' '])
+                } else {
+                    l
+                };
+                Concept::new(rest.trim().to_string())
+            })
+            .filter(|c| !c.gloss.is_empty())
+            .collect();
+
+        Self { name, concepts }
+    }
+
+    /// Gets the number of concepts.
+    pub fn len(&self) -> usize {
+        self.concepts.len()
+    }
+
+    /// Returns `true` if the list has no concepts.
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
+
+    /// Matches the concepts against the translations of words in `dictionary`.
+    pub fn coverage(&self, dictionary: &Dictionary) -> Coverage {
+        let words = dictionary
+            .iter_words()
+            .map(|w| {
+                (
+                    w.id().unwrap_or_default(),
+                    w.glosses().map(normalize_gloss).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let matches = self
+            .concepts
+            .iter()
+            .map(|c| {
+                let keys = c.keys().collect::<Vec<_>>();
+                let mut ids = words
+                    .iter()
+                    .filter(|(_, senses)| senses.iter().any(|s| keys.contains(s)))
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>();
+                ids.sort();
+                ids
+            })
+            .collect();
+
+        Coverage { matches }
+    }
+
+    /// Adds placeholder words to `dictionary` for the concepts of the given indices, and
+    /// then returns their ids.
+    pub fn add_placeholders<I>(&self, dictionary: &mut Dictionary, indices: I) -> Vec<Uuid>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        indices
+            .into_iter()
+            .filter_map(|i| self.concepts.get(i))
+            .map(|c| dictionary.add_word(c.placeholder_word()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_lists() {
+        assert_eq!(ConceptList::swadesh_207().len(), 207);
+        assert_eq!(ConceptList::leipzig_jakarta().len(), 100);
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_gloss("To lie (as in a bed)"), "lie");
+        assert_eq!(normalize_gloss(" who? "), "who");
+        assert_eq!(normalize_gloss("to"), "to");
+        assert_eq!(
+            Concept::new("to do / to make".to_string())
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["do", "make"]
+        );
+    }
+
+    #[test]
+    fn from_text() {
+        let list = ConceptList::from_text(
+            "Custom".to_string(),
+            "# header\n1. sun\n2\tmoon\n\nstar\n3)\n",
+        );
+        assert_eq!(
+            list.concepts,
+            vec![
+                Concept::new("sun".to_string()),
+                Concept::new("moon".to_string()),
+                Concept::new("star".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage() {
+        let list = ConceptList::from_text("Custom".to_string(), "sun\nto eat\nflesh / meat\nwater");
+        let mut dict = Dictionary::new();
+        let sun = dict.add_word(
+            WordBuilder::new()
+                .translation("star, sun".to_string())
+                .build(),
+        );
+        let eat = dict.add_word(WordBuilder::new().translation("eat".to_string()).build());
+        let meat = dict.add_word(
+            WordBuilder::new()
+                .translation("meat (food)".to_string())
+                .build(),
+        );
+
+        let cov = list.coverage(&dict);
+        assert_eq!(cov.matches[0], vec![sun]);
+        assert_eq!(cov.matches[1], vec![eat]);
+        assert_eq!(cov.matches[2], vec![meat]);
+        assert_eq!(cov.missing().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(cov.ratio(), 0.75);
+
+        let ids = list.add_placeholders(&mut dict, cov.missing().collect::<Vec<_>>());
+        assert_eq!(ids.len(), 1);
+        assert_eq!(dict.word_by_id(ids[0]).unwrap().translation(), "water");
+        assert_eq!(list.coverage(&dict).missing().count(), 0);
+    }
+}
@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    phonology::Inventory,
+    prelude::*,
+    xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter},
+};
+
+use super::{Error, PartOfSpeech, Word};
+
+/// The placeholder in a gloss template that will be replaced by the gloss of the root word.
+pub const GLOSS_PLACEHOLDER: &str = "{gloss}";
+
+/// A derivational morphology rule. Creates a new word from an existing one, for example
+/// an agentive noun from a verb.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Derivation {
+    /// The id of the rule.
+    id: Option<Uuid>,
+    /// The name of the rule.
+    name: String,
+    /// The romanization attached before the root.
+    prefix: String,
+    /// The romanization attached after the root.
+    suffix: String,
+    /// The part of speech this rule applies to. Applies to any word if `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from_part_of_speech: Option<PartOfSpeech>,
+    /// The part of speech of the derived word. Keeps the root's if `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to_part_of_speech: Option<PartOfSpeech>,
+    /// The translation of the derived word. `{gloss}` is replaced by the root's translation.
+    gloss_template: String,
+}
+
+/// A link from a derived word to its root and the rule that produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivationLink {
+    /// The id of the root word.
+    #[serde(rename = "root")]
+    pub root_id: Uuid,
+    /// The id of the derivation rule.
+    #[serde(rename = "rule")]
+    pub rule_id: Uuid,
+}
+
+impl IdAble for Derivation {
+    /// Gets the id of the rule.
+    fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// Generates new id for the rule, and then returns it.
+    fn generate_id(&mut self) -> Uuid {
+        let id = Uuid::new_v4();
+        self.id = Some(id);
+        id
+    }
+}
+
+impl Derivation {
+    /// Creates a new rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new rule with specified id.
+    pub fn new_with_id(id: Uuid) -> Self {
+        Self {
+            id: Some(id),
+            ..Default::default()
+        }
+    }
+
+    /// Gets the name of the rule.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the name of the rule.
+    pub fn set_name(&mut self, value: String) {
+        self.name = value;
+    }
+
+    /// Gets the prefix of the rule.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Sets the prefix of the rule.
+    pub fn set_prefix(&mut self, value: String) {
+        self.prefix = value;
+    }
+
+    /// Gets the suffix of the rule.
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// Sets the suffix of the rule.
+    pub fn set_suffix(&mut self, value: String) {
+        self.suffix = value;
+    }
+
+    /// Gets the part of speech this rule applies to.
+    pub fn from_part_of_speech(&self) -> Option<PartOfSpeech> {
+        self.from_part_of_speech
+    }
+
+    /// Sets the part of speech this rule applies to.
+    pub fn set_from_part_of_speech(&mut self, value: Option<PartOfSpeech>) {
+        self.from_part_of_speech = value;
+    }
+
+    /// Gets the part of speech of the derived word.
+    pub fn to_part_of_speech(&self) -> Option<PartOfSpeech> {
+        self.to_part_of_speech
+    }
+
+    /// Sets the part of speech of the derived word.
+    pub fn set_to_part_of_speech(&mut self, value: Option<PartOfSpeech>) {
+        self.to_part_of_speech = value;
+    }
+
+    /// Gets the gloss template of the rule.
+    pub fn gloss_template(&self) -> &str {
+        &self.gloss_template
+    }
+
+    /// Sets the gloss template of the rule.
+    pub fn set_gloss_template(&mut self, value: String) {
+        self.gloss_template = value;
+    }
+
+    /// Returns `true` if the rule can be applied to `word`.
+    pub fn applies_to(&self, word: &Word) -> bool {
+        self.from_part_of_speech.is_none() || self.from_part_of_speech == word.part_of_speech()
+    }
+
+    /// Writes the derived form of `root` into `target`, leaving its other properties intact.
+    /// `inventory` is used to pronunce the affixes.
+    pub fn derive_into(&self, root: &Word, target: &mut Word, inventory: &Inventory) {
+        target.set_romanization(format!(
+            "{}{}{}",
+            self.prefix,
+            root.romanization(),
+            self.suffix
+        ));
+        target.set_xsampa_pronunciation(None);
+        target.set_pronunciation(format!(
+            "{}{}{}",
+            inventory.pronunce_romanization(&self.prefix),
+            root.pronunciation(),
+            inventory.pronunce_romanization(&self.suffix)
+        ));
+        target.set_translation(if self.gloss_template.is_empty() {
+            root.translation().to_string()
+        } else {
+            self.gloss_template
+                .replace(GLOSS_PLACEHOLDER, root.translation())
+        });
+        target.set_part_of_speech(self.to_part_of_speech.or(root.part_of_speech()));
+
+        if let (Some(root_id), Some(rule_id)) = (root.id(), self.id) {
+            target.set_derivation(Some(DerivationLink { root_id, rule_id }));
+        }
+    }
+
+    /// Creates a new word derived from `root`.
+    pub fn derive(&self, root: &Word, inventory: &Inventory) -> Word {
+        let mut ret = Word::new();
+        self.derive_into(root, &mut ret, inventory);
+        ret
+    }
+}
+
+impl ReadXml for Derivation {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "derivation";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {
+                let id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "id")
+                    .map(|x| Uuid::parse_str(&x.1))
+                    .unwrap_or_else(|| Ok(Uuid::new_v4()))
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.id = Some(id);
+            }
+            (Some(Self::TAG), Some("name")) => {
+                self.name.clear();
+            }
+            (Some(Self::TAG), Some("prefix")) => {
+                self.prefix.clear();
+            }
+            (Some(Self::TAG), Some("suffix")) => {
+                self.suffix.clear();
+            }
+            (Some(Self::TAG), Some("from-part-of-speech")) => {
+                self.from_part_of_speech = None;
+            }
+            (Some(Self::TAG), Some("to-part-of-speech")) => {
+                self.to_part_of_speech = None;
+            }
+            (Some(Self::TAG), Some("gloss-template")) => {
+                self.gloss_template.clear();
+            }
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag() {
+            Some("name") => self.name += &text,
+            Some("prefix") => self.prefix += &text,
+            Some("suffix") => self.suffix += &text,
+            Some("from-part-of-speech") => {
+                self.from_part_of_speech = Some(text.as_str().into());
+            }
+            Some("to-part-of-speech") => {
+                self.to_part_of_speech = Some(text.as_str().into());
+            }
+            Some("gloss-template") => self.gloss_template += &text,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Derivation {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        w: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        w.write_tag_start_with_attributes(
+            "derivation",
+            [("id", self.id.unwrap_or_default().to_string().as_str())],
+        )?;
+
+        w.write_tag_start("name")?;
+        w.write_text(&self.name)?;
+        w.write_tag_end("name")?;
+
+        w.write_tag_start("prefix")?;
+        w.write_text(&self.prefix)?;
+        w.write_tag_end("prefix")?;
+
+        w.write_tag_start("suffix")?;
+        w.write_text(&self.suffix)?;
+        w.write_tag_end("suffix")?;
+
+        if let Some(pos) = &self.from_part_of_speech {
+            w.write_tag_start("from-part-of-speech")?;
+            w.write_text(pos.name())?;
+            w.write_tag_end("from-part-of-speech")?;
+        }
+
+        if let Some(pos) = &self.to_part_of_speech {
+            w.write_tag_start("to-part-of-speech")?;
+            w.write_text(pos.name())?;
+            w.write_tag_end("to-part-of-speech")?;
+        }
+
+        w.write_tag_start("gloss-template")?;
+        w.write_text(&self.gloss_template)?;
+        w.write_tag_end("gloss-template")?;
+
+        w.write_tag_end("derivation")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexicon::WordBuilder, Language, Phoneme};
+
+    const XML1: &str = r#"
+    <derivation id="74a61b73-2830-4d23-80d7-fe3222741e80">
+        <name>Agentive</name>
+        <prefix></prefix>
+        <suffix>ari</suffix>
+        <from-part-of-speech>Verb</from-part-of-speech>
+        <to-part-of-speech>Noun</to-part-of-speech>
+        <gloss-template>one who {gloss}s</gloss-template>
+    </derivation>
+    "#;
+
+    fn test_language() -> (Language, Uuid, Uuid) {
+        let mut lang = Language::new();
+        for s in ["a", "r", "i", "k", "t"] {
+            lang.phonemic_inventory_mut()
+                .add_phoneme(Phoneme::with_sound(s.to_string()));
+        }
+
+        let root_id = lang.dictionary_mut().add_word(
+            WordBuilder::new()
+                .romanization("kit".to_string())
+                .pronunciation("ˈkit".to_string())
+                .translation("sing".to_string())
+                .part_of_speech(PartOfSpeech::Verb)
+                .build(),
+        );
+        let rule_id = lang
+            .derivations_mut()
+            .add_derivation(Derivation::load_xml_str(XML1).unwrap());
+
+        (lang, root_id, rule_id)
+    }
+
+    #[test]
+    fn read_xml() {
+        let der = Derivation::load_xml_str(XML1).unwrap();
+        assert_eq!(der.name(), "Agentive");
+        assert_eq!(der.prefix(), "");
+        assert_eq!(der.suffix(), "ari");
+        assert_eq!(der.from_part_of_speech(), Some(PartOfSpeech::Verb));
+        assert_eq!(der.to_part_of_speech(), Some(PartOfSpeech::Noun));
+        assert_eq!(der.gloss_template(), "one who {gloss}s");
+    }
+
+    #[test]
+    fn write_xml() {
+        let der = Derivation::load_xml_str(XML1).unwrap();
+        let xml2 = der.save_xml_string().unwrap();
+        let der2 = Derivation::load_xml_str(&xml2).unwrap();
+        assert_eq!(&der, &der2);
+    }
+
+    #[test]
+    fn derive() {
+        let (mut lang, root_id, rule_id) = test_language();
+
+        let id = lang.dictionary_derive_word(root_id, rule_id).unwrap();
+        let word = lang.dictionary().word_by_id(id).unwrap();
+        assert_eq!(word.romanization(), "kitari");
+        assert_eq!(word.pronunciation(), "ˈkitari");
+        assert_eq!(word.translation(), "one who sings");
+        assert_eq!(word.part_of_speech(), Some(PartOfSpeech::Noun));
+        assert_eq!(word.derivation(), Some(DerivationLink { root_id, rule_id }));
+
+        // Rule does not apply to nouns.
+        assert_eq!(lang.dictionary_derive_word(id, rule_id), None);
+    }
+
+    #[test]
+    fn refresh_derivatives() {
+        let (mut lang, root_id, rule_id) = test_language();
+        let id = lang.dictionary_derive_word(root_id, rule_id).unwrap();
+
+        let root = lang.dictionary_mut().word_by_id_mut(root_id).unwrap();
+        root.set_romanization("tik".to_string());
+        root.set_pronunciation("ˈtik".to_string());
+        root.set_translation("dance".to_string());
+
+        assert_eq!(lang.dictionary_refresh_derivatives(root_id), 1);
+        let word = lang.dictionary().word_by_id(id).unwrap();
+        assert_eq!(word.romanization(), "tikari");
+        assert_eq!(word.pronunciation(), "ˈtikari");
+        assert_eq!(word.translation(), "one who dances");
+
+        // Root can't be removed without cascading while it has derivatives.
+        assert_eq!(lang.dictionary_remove_word_by_id(root_id, false), None);
+        assert!(lang.dictionary_remove_word_by_id(root_id, true).is_some());
+        assert_eq!(lang.dictionary().word_by_id(id).unwrap().derivation(), None);
+    }
+}
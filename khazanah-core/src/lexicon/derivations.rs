@@ -0,0 +1,120 @@
+use crate::Store;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+
+use super::{Derivation, Error};
+
+/// Collection of derivation rules.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Derivations {
+    inner: Store<Derivation>,
+}
+
+impl Derivations {
+    /// Creates a new collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a derivation rule.
+    pub fn add_derivation(&mut self, derivation: Derivation) -> Uuid {
+        self.inner.add(derivation)
+    }
+
+    /// Removes a derivation rule by id.
+    pub fn remove_derivation_by_id(&mut self, id: Uuid) -> Option<Derivation> {
+        self.inner.remove(id)
+    }
+
+    /// Gets the number of derivation rules.
+    pub fn n_derivations(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Gets a reference to derivation rule by id.
+    pub fn derivation_by_id(&self, id: Uuid) -> Option<&Derivation> {
+        self.inner.get(id)
+    }
+
+    /// Gets a mutable reference to derivation rule by id.
+    pub fn derivation_by_id_mut(&mut self, id: Uuid) -> Option<&mut Derivation> {
+        self.inner.get_mut(id)
+    }
+
+    /// Gets a reference to derivation rule by name.
+    pub fn derivation_by_name(&self, name: &str) -> Option<&Derivation> {
+        self.inner.iter().find(|der| der.name() == name)
+    }
+
+    /// Iterates over derivation rules.
+    pub fn iter_derivations(&self) -> impl Iterator<Item = &Derivation> {
+        self.inner.iter()
+    }
+
+    /// Iterates over derivation rules.
+    pub fn iter_derivations_mut(&mut self) -> impl Iterator<Item = &mut Derivation> {
+        self.inner.iter_mut()
+    }
+
+    /// Iterates over derivation rule ids.
+    pub fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.inner.ids()
+    }
+
+    /// Gets a reference to the inner store.
+    pub fn derivations(&self) -> &Store<Derivation> {
+        &self.inner
+    }
+}
+
+impl ReadXml for Derivations {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "derivations";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner
+            ._process_tag_start(Self::TAG, reader, state, name, attrs)
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Derivations {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner._serialize_xml("derivations", writer)
+    }
+}
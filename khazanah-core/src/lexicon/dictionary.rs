@@ -1,14 +1,18 @@
-use super::{Error, Word};
+use super::{Error, Word, WordStatus};
+use crate::phonology::{self, Categories, Inventory, Pattern};
 use crate::prelude::*;
 use crate::Store;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use crate::xml::{XmlError, XmlReader, XmlWriter};
 
 /// A lexicon. Stores dictionary of words.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Dictionary {
     words: Store<Word>,
 }
@@ -49,6 +53,11 @@ impl Dictionary {
         self.words.iter()
     }
 
+    /// Iterates over words of review status `status`.
+    pub fn iter_words_by_status(&self, status: WordStatus) -> impl Iterator<Item = &Word> {
+        self.iter_words().filter(move |w| w.status() == status)
+    }
+
     /// Iterates over words.
     pub fn iter_words_mut(&mut self) -> impl Iterator<Item = &mut Word> {
         self.words.iter_mut()
@@ -59,12 +68,51 @@ impl Dictionary {
         self.words.ids()
     }
 
+    /// Iterates over ids of words derived directly from the word of id `root_id`.
+    pub fn derivative_ids(&self, root_id: Uuid) -> impl Iterator<Item = Uuid> + '_ {
+        self.words.iter().filter_map(move |word| {
+            word.derivation()
+                .filter(|der| der.root_id == root_id)
+                .and(word.id())
+        })
+    }
+
+    /// Counts the words of each tag used in the dictionary.
+    pub fn tag_counts(&self) -> BTreeMap<String, usize> {
+        let mut ret = BTreeMap::new();
+        for tag in self.words.iter().flat_map(|word| word.tags()) {
+            *ret.entry(tag.to_string()).or_default() += 1;
+        }
+        ret
+    }
+
+    /// Gets the ids of words whose pronunciation matches `pattern`. See
+    /// [`Pattern::search_regex_pattern`].
+    pub fn search_pronunciation(
+        &self,
+        pattern: &Pattern,
+        categories: &Categories,
+        inventory: &Inventory,
+    ) -> Result<Vec<Uuid>, phonology::Error> {
+        let re = pattern.search_regex(categories, inventory)?;
+        Ok(self
+            .words
+            .iter()
+            .filter(|word| re.is_match(&phonology::normalize_syllables(word.pronunciation())))
+            .filter_map(|word| word.id())
+            .collect())
+    }
+
     /// Gets a reference to the inner store.
     pub fn words(&self) -> &Store<Word> {
         &self.words
     }
 }
 
+impl ReadJson for Dictionary {}
+
+impl WriteJson for Dictionary {}
+
 impl ReadXml for Dictionary {
     type Error = Error;
 
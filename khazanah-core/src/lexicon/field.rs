@@ -0,0 +1,311 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    prelude::*,
+    xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter},
+};
+
+use super::Error;
+
+/// The type of values of a custom field.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FieldKind {
+    #[default]
+    Text,
+    Number,
+    Enum,
+    Boolean,
+}
+
+impl FieldKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Text => "Text",
+            Self::Number => "Number",
+            Self::Enum => "Enum",
+            Self::Boolean => "Boolean",
+        }
+    }
+}
+
+impl From<&str> for FieldKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "Number" => Self::Number,
+            "Enum" => Self::Enum,
+            "Boolean" => Self::Boolean,
+            _ => Self::Text,
+        }
+    }
+}
+
+pub const ALL_FIELD_KINDS: &[FieldKind] = &[
+    FieldKind::Text,
+    FieldKind::Number,
+    FieldKind::Enum,
+    FieldKind::Boolean,
+];
+
+/// A typed value of a custom field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Number(f64),
+    Enum(String),
+    Boolean(bool),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text(s) | Self::Enum(s) => write!(f, "{}", s),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Boolean(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// The definition of a custom field of dictionary entries. Words store the value of the field
+/// as text, which is interpreted according to the definition.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct FieldDefinition {
+    /// The id of the field.
+    id: Option<Uuid>,
+    /// The name of the field.
+    name: String,
+    /// The type of the field.
+    kind: FieldKind,
+    /// The allowed values, if the field is an enum.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    options: Vec<String>,
+}
+
+impl IdAble for FieldDefinition {
+    /// Gets the id of the field.
+    fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// Generates new id for the field, and then returns it.
+    fn generate_id(&mut self) -> Uuid {
+        let id = Uuid::new_v4();
+        self.id = Some(id);
+        id
+    }
+}
+
+impl FieldDefinition {
+    /// Creates a new field.
+    pub fn new(name: String, kind: FieldKind) -> Self {
+        Self {
+            name,
+            kind,
+            ..Default::default()
+        }
+    }
+
+    /// Gets the name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the name of the field.
+    pub fn set_name(&mut self, value: String) {
+        self.name = value;
+    }
+
+    /// Gets the type of the field.
+    pub fn kind(&self) -> FieldKind {
+        self.kind
+    }
+
+    /// Sets the type of the field.
+    pub fn set_kind(&mut self, value: FieldKind) {
+        self.kind = value;
+    }
+
+    /// Gets the allowed values of an enum field.
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Sets the allowed values of an enum field.
+    pub fn set_options(&mut self, value: Vec<String>) {
+        self.options = value;
+    }
+
+    /// Interprets a stored value. Returns `None` if the value is not valid for the field.
+    pub fn parse(&self, value: &str) -> Option<FieldValue> {
+        match self.kind {
+            FieldKind::Text => Some(FieldValue::Text(value.to_string())),
+            FieldKind::Number => value.trim().parse().ok().map(FieldValue::Number),
+            FieldKind::Enum => self
+                .options
+                .iter()
+                .any(|o| o == value)
+                .then(|| FieldValue::Enum(value.to_string())),
+            FieldKind::Boolean => match value.trim() {
+                "true" => Some(FieldValue::Boolean(true)),
+                "false" => Some(FieldValue::Boolean(false)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns `true` if `value` is valid for the field.
+    pub fn is_valid(&self, value: &str) -> bool {
+        self.parse(value).is_some()
+    }
+}
+
+impl ReadXml for FieldDefinition {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "field-definition";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {
+                let id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "id")
+                    .map(|x| Uuid::parse_str(&x.1))
+                    .unwrap_or_else(|| Ok(Uuid::new_v4()))
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.id = Some(id);
+                self.kind = attrs
+                    .iter()
+                    .find(|&x| x.0 == "type")
+                    .map(|x| FieldKind::from(x.1.as_str()))
+                    .unwrap_or_default();
+            }
+            (Some(Self::TAG), Some("name")) => {
+                self.name.clear();
+            }
+            (Some(Self::TAG), Some("option")) => {
+                self.options.push(String::new());
+            }
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag() {
+            Some("name") => self.name += &text,
+            Some("option") => {
+                if let Some(option) = self.options.last_mut() {
+                    *option += &text;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for FieldDefinition {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        w: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        w.write_tag_start_with_attributes(
+            "field-definition",
+            [
+                ("id", self.id.unwrap_or_default().to_string().as_str()),
+                ("type", self.kind.name()),
+            ],
+        )?;
+
+        w.write_tag_start("name")?;
+        w.write_text(&self.name)?;
+        w.write_tag_end("name")?;
+
+        for option in self.options.iter() {
+            w.write_tag_start("option")?;
+            w.write_text(option)?;
+            w.write_tag_end("option")?;
+        }
+
+        w.write_tag_end("field-definition")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML1: &str = r#"
+    <field-definition id="3f1c9a52-7d8e-4b6a-9c1e-2a5b7d9e0f13" type="Enum">
+        <name>Register</name>
+        <option>formal</option>
+        <option>informal</option>
+    </field-definition>
+    "#;
+
+    #[test]
+    fn read_xml() {
+        let def = FieldDefinition::load_xml_str(XML1).unwrap();
+        assert_eq!(def.name(), "Register");
+        assert_eq!(def.kind(), FieldKind::Enum);
+        assert_eq!(def.options(), &["formal", "informal"]);
+    }
+
+    #[test]
+    fn write_xml() {
+        let def = FieldDefinition::load_xml_str(XML1).unwrap();
+        let xml2 = def.save_xml_string().unwrap();
+        let def2 = FieldDefinition::load_xml_str(&xml2).unwrap();
+        assert_eq!(&def, &def2);
+    }
+
+    #[test]
+    fn parse() {
+        let def = FieldDefinition::load_xml_str(XML1).unwrap();
+        assert_eq!(
+            def.parse("formal"),
+            Some(FieldValue::Enum("formal".to_string()))
+        );
+        assert_eq!(def.parse("slang"), None);
+
+        let def = FieldDefinition::new("Frequency".to_string(), FieldKind::Number);
+        assert_eq!(def.parse(" 12.5"), Some(FieldValue::Number(12.5)));
+        assert_eq!(def.parse("often"), None);
+
+        let def = FieldDefinition::new("Borrowed".to_string(), FieldKind::Boolean);
+        assert_eq!(def.parse("true"), Some(FieldValue::Boolean(true)));
+        assert!(!def.is_valid("yes"));
+    }
+}
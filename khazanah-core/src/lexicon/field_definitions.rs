@@ -0,0 +1,120 @@
+use crate::Store;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+
+use super::{Error, FieldDefinition};
+
+/// Collection of custom field definitions.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FieldDefinitions {
+    inner: Store<FieldDefinition>,
+}
+
+impl FieldDefinitions {
+    /// Creates a new collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a field definition.
+    pub fn add_field(&mut self, field: FieldDefinition) -> Uuid {
+        self.inner.add(field)
+    }
+
+    /// Removes a field definition by id.
+    pub fn remove_field_by_id(&mut self, id: Uuid) -> Option<FieldDefinition> {
+        self.inner.remove(id)
+    }
+
+    /// Gets the number of field definitions.
+    pub fn n_fields(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Gets a reference to field definition by id.
+    pub fn field_by_id(&self, id: Uuid) -> Option<&FieldDefinition> {
+        self.inner.get(id)
+    }
+
+    /// Gets a mutable reference to field definition by id.
+    pub fn field_by_id_mut(&mut self, id: Uuid) -> Option<&mut FieldDefinition> {
+        self.inner.get_mut(id)
+    }
+
+    /// Gets a reference to field definition by name.
+    pub fn field_by_name(&self, name: &str) -> Option<&FieldDefinition> {
+        self.inner.iter().find(|field| field.name() == name)
+    }
+
+    /// Iterates over field definitions.
+    pub fn iter_fields(&self) -> impl Iterator<Item = &FieldDefinition> {
+        self.inner.iter()
+    }
+
+    /// Iterates over field definitions.
+    pub fn iter_fields_mut(&mut self) -> impl Iterator<Item = &mut FieldDefinition> {
+        self.inner.iter_mut()
+    }
+
+    /// Iterates over field definition ids.
+    pub fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.inner.ids()
+    }
+
+    /// Gets a reference to the inner store.
+    pub fn fields(&self) -> &Store<FieldDefinition> {
+        &self.inner
+    }
+}
+
+impl ReadXml for FieldDefinitions {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "field-definitions";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner
+            ._process_tag_start(Self::TAG, reader, state, name, attrs)
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for FieldDefinitions {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner._serialize_xml("field-definitions", writer)
+    }
+}
@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+
+use super::{Error, Word, ALL_PARTS_OF_SPEECH};
+
+/// Consecutive changes of the same field within this many seconds are merged into one revision.
+pub const HISTORY_MERGE_SECONDS: u64 = 60;
+
+/// A field of a word tracked by the history.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WordField {
+    Romanization,
+    Translation,
+    Pronunciation,
+    PartOfSpeech,
+    Tags,
+    Status,
+    /// A custom field, by field id.
+    Field(Uuid),
+}
+
+/// Fields of a word tracked by the history, other than custom fields.
+pub const BUILTIN_WORD_FIELDS: &[WordField] = &[
+    WordField::Romanization,
+    WordField::Translation,
+    WordField::Pronunciation,
+    WordField::PartOfSpeech,
+    WordField::Tags,
+    WordField::Status,
+];
+
+/// Splits tags separated by unescaped commas, as given by [`WordField::value`].
+fn split_tags(value: &str) -> Vec<String> {
+    let mut ret = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => ret.last_mut().unwrap().extend(chars.next()),
+            ',' => ret.push(String::new()),
+            c => ret.last_mut().unwrap().push(c),
+        }
+    }
+    ret
+}
+
+impl WordField {
+    /// Gets the value of the field of `word` as a string. Tags are separated by commas, with
+    /// commas and backslashes in them escaped by a backslash.
+    pub fn value(&self, word: &Word) -> String {
+        match self {
+            Self::Romanization => word.romanization().to_string(),
+            Self::Translation => word.translation().to_string(),
+            Self::Pronunciation => word.pronunciation().to_string(),
+            Self::PartOfSpeech => word.part_of_speech().map(String::from).unwrap_or_default(),
+            Self::Tags => word
+                .tags()
+                .map(|t| t.replace('\\', "\\\\").replace(',', "\\,"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Self::Status => word.status().name().to_string(),
+            Self::Field(id) => word.field(*id).unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Sets the value of the field of `word` from a string given by [`WordField::value`].
+    pub fn set_value(&self, word: &mut Word, value: String) {
+        match self {
+            Self::Romanization => word.set_romanization(value),
+            Self::Translation => word.set_translation(value),
+            Self::Pronunciation => word.set_pronunciation(value),
+            Self::PartOfSpeech => word.set_part_of_speech(
+                ALL_PARTS_OF_SPEECH
+                    .iter()
+                    .flatten()
+                    .find(|pos| pos.name() == value)
+                    .copied(),
+            ),
+            Self::Tags => {
+                for tag in word.tags().map(str::to_string).collect::<Vec<_>>() {
+                    word.remove_tag(&tag);
+                }
+                for tag in split_tags(&value) {
+                    word.add_tag(tag);
+                }
+            }
+            Self::Status => word.set_status(value.as_str().into()),
+            Self::Field(id) => word.set_field(*id, (!value.is_empty()).then_some(value)),
+        }
+    }
+
+    /// Gets the name of the field in project files.
+    pub fn key(&self) -> String {
+        match self {
+            Self::Romanization => "romanization".to_string(),
+            Self::Translation => "translation".to_string(),
+            Self::Pronunciation => "pronunciation".to_string(),
+            Self::PartOfSpeech => "part-of-speech".to_string(),
+            Self::Tags => "tags".to_string(),
+            Self::Status => "status".to_string(),
+            Self::Field(id) => format!("field:{id}"),
+        }
+    }
+
+    /// Parses the name of the field in project files.
+    pub fn from_key(key: &str) -> Option<Self> {
+        let ret = match key {
+            "romanization" => Self::Romanization,
+            "translation" => Self::Translation,
+            "pronunciation" => Self::Pronunciation,
+            "part-of-speech" => Self::PartOfSpeech,
+            "tags" => Self::Tags,
+            "status" => Self::Status,
+            _ => Self::Field(Uuid::parse_str(key.strip_prefix("field:")?).ok()?),
+        };
+        Some(ret)
+    }
+}
+
+impl Display for WordField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Romanization => write!(f, "Romanization"),
+            Self::Translation => write!(f, "Translation"),
+            Self::Pronunciation => write!(f, "Pronunciation"),
+            Self::PartOfSpeech => write!(f, "Part of Speech"),
+            Self::Tags => write!(f, "Tags"),
+            Self::Status => write!(f, "Status"),
+            Self::Field(_) => write!(f, "Custom Field"),
+        }
+    }
+}
+
+/// A change of a field of a word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Revision {
+    /// The time of the change, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub field: WordField,
+    pub old_value: String,
+    pub new_value: String,
+    /// Why the change was made, if given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Gets the current time in seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Revision history of the words of a dictionary.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History {
+    /// Revisions by word id, oldest first.
+    revisions: BTreeMap<Uuid, Vec<Revision>>,
+}
+
+impl History {
+    /// Creates a new history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the revisions of the word of id `word_id`, oldest first.
+    pub fn revisions_of(&self, word_id: Uuid) -> &[Revision] {
+        self.revisions
+            .get(&word_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Gets the ids of the words that have revisions.
+    pub fn word_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.revisions.keys().copied()
+    }
+
+    /// Gets the number of revisions of all words.
+    pub fn n_revisions(&self) -> usize {
+        self.revisions.values().map(Vec::len).sum()
+    }
+
+    /// Records the changes between `before` and `after`, which are the same word before and
+    /// after an edit. A change without a reason is merged into the last revision if that
+    /// revision changed the same field without a reason less than [`HISTORY_MERGE_SECONDS`]
+    /// ago, so typing into a field makes one revision rather than one per keystroke.
+    pub fn record(&mut self, before: &Word, after: &Word, timestamp: u64, reason: Option<&str>) {
+        let Some(word_id) = after.id() else {
+            return;
+        };
+
+        let mut fields = BUILTIN_WORD_FIELDS.to_vec();
+        let mut custom = before
+            .fields()
+            .chain(after.fields())
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        custom.sort();
+        custom.dedup();
+        fields.extend(custom.into_iter().map(WordField::Field));
+
+        for field in fields {
+            let old_value = field.value(before);
+            let new_value = field.value(after);
+            if old_value != new_value {
+                self.push(word_id, field, old_value, new_value, timestamp, reason);
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        word_id: Uuid,
+        field: WordField,
+        old_value: String,
+        new_value: String,
+        timestamp: u64,
+        reason: Option<&str>,
+    ) {
+        let revisions = self.revisions.entry(word_id).or_default();
+        if let Some(last) = revisions.last_mut() {
+            if reason.is_none()
+                && last.reason.is_none()
+                && last.field == field
+                && last.new_value == old_value
+                && timestamp.saturating_sub(last.timestamp) < HISTORY_MERGE_SECONDS
+            {
+                last.new_value = new_value;
+                last.timestamp = timestamp;
+                if last.old_value == last.new_value {
+                    revisions.pop();
+                }
+                return;
+            }
+        }
+
+        revisions.push(Revision {
+            timestamp,
+            field,
+            old_value,
+            new_value,
+            reason: reason.map(str::to_string),
+        });
+    }
+
+    /// Restores `word` to the value before the revision at `index` of its history, and records
+    /// the restoration as a new revision. Returns `false` if there is no such revision.
+    pub fn restore(&mut self, word: &mut Word, index: usize, timestamp: u64) -> bool {
+        let Some(revision) = word
+            .id()
+            .and_then(|id| self.revisions_of(id).get(index))
+            .cloned()
+        else {
+            return false;
+        };
+
+        let before = word.clone();
+        revision.field.set_value(word, revision.old_value);
+        let reason = format!("Restored revision of {}", revision.timestamp);
+        self.record(&before, word, timestamp, Some(&reason));
+        true
+    }
+
+    /// Removes the history of the word of id `word_id`.
+    pub fn remove_word(&mut self, word_id: Uuid) {
+        self.revisions.remove(&word_id);
+    }
+}
+
+/// State of reading a history file.
+#[derive(Debug, Default)]
+pub struct HistoryReaderState {
+    word_id: Option<Uuid>,
+    revision: Option<Revision>,
+}
+
+impl ReadXml for History {
+    type Error = Error;
+
+    type ReaderState = HistoryReaderState;
+
+    const TAG: &'static str = "history";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        let attr = |key: &str| attrs.iter().find(|x| x.0 == key).map(|x| x.1.as_str());
+
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {}
+            (Some(Self::TAG), Some("word")) => {
+                let id = attr("id").ok_or(XmlError::Other(Error::NoId))?;
+                let id = Uuid::parse_str(id).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                state.word_id = Some(id);
+            }
+            (Some("word"), Some("revision")) => {
+                let field = attr("field")
+                    .and_then(WordField::from_key)
+                    .ok_or_else(|| XmlError::InvalidTag(name.clone()))?;
+                state.revision = Some(Revision {
+                    timestamp: attr("timestamp")
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or_default(),
+                    field,
+                    old_value: String::new(),
+                    new_value: String::new(),
+                    reason: None,
+                });
+            }
+            (Some("revision"), Some("old-value" | "new-value" | "reason")) => {}
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        let Some(revision) = state.revision.as_mut() else {
+            return Ok(());
+        };
+        match reader.last_tag() {
+            Some("old-value") => revision.old_value += &text,
+            Some("new-value") => revision.new_value += &text,
+            Some("reason") => *revision.reason.get_or_insert_with(String::new) += &text,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match name.as_str() {
+            "revision" => {
+                if let (Some(id), Some(revision)) = (state.word_id, state.revision.take()) {
+                    self.revisions.entry(id).or_default().push(revision);
+                }
+            }
+            "word" => state.word_id = None,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl WriteXml for History {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        writer.write_tag_start("history")?;
+
+        for (id, revisions) in self.revisions.iter() {
+            if revisions.is_empty() {
+                continue;
+            }
+            writer.write_tag_start_with_attributes("word", [("id", id.to_string().as_str())])?;
+            for revision in revisions.iter() {
+                writer.write_tag_start_with_attributes(
+                    "revision",
+                    [
+                        ("timestamp", revision.timestamp.to_string().as_str()),
+                        ("field", revision.field.key().as_str()),
+                    ],
+                )?;
+
+                writer.write_tag_start("old-value")?;
+                writer.write_text(&revision.old_value)?;
+                writer.write_tag_end("old-value")?;
+
+                writer.write_tag_start("new-value")?;
+                writer.write_text(&revision.new_value)?;
+                writer.write_tag_end("new-value")?;
+
+                if let Some(reason) = &revision.reason {
+                    writer.write_tag_start("reason")?;
+                    writer.write_text(reason)?;
+                    writer.write_tag_end("reason")?;
+                }
+
+                writer.write_tag_end("revision")?;
+            }
+            writer.write_tag_end("word")?;
+        }
+
+        writer.write_tag_end("history")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::{PartOfSpeech, WordBuilder};
+
+    use super::*;
+
+    #[test]
+    fn record_and_restore() {
+        let mut word = WordBuilder::new().romanization("kan".to_string()).build();
+        word.generate_id();
+        let id = word.id().unwrap();
+        let mut history = History::new();
+
+        let edit = |history: &mut History, word: &mut Word, t, f: &dyn Fn(&mut Word)| {
+            let before = word.clone();
+            f(word);
+            history.record(&before, word, t, None);
+        };
+
+        // Typing is merged into one revision.
+        edit(&mut history, &mut word, 100, &|w| {
+            w.set_romanization("kant".to_string())
+        });
+        edit(&mut history, &mut word, 101, &|w| {
+            w.set_romanization("kanta".to_string())
+        });
+        assert_eq!(history.revisions_of(id).len(), 1);
+        assert_eq!(history.revisions_of(id)[0].old_value, "kan");
+        assert_eq!(history.revisions_of(id)[0].new_value, "kanta");
+
+        edit(&mut history, &mut word, 500, &|w| {
+            w.set_part_of_speech(Some(PartOfSpeech::ProperNoun));
+            w.add_tag("old".to_string());
+        });
+        assert_eq!(history.n_revisions(), 3);
+
+        assert!(history.restore(&mut word, 0, 600));
+        assert_eq!(word.romanization(), "kan");
+        assert!(history.restore(&mut word, 1, 600));
+        assert_eq!(word.part_of_speech(), None);
+        assert!(!history.restore(&mut word, 10, 600));
+        assert_eq!(history.n_revisions(), 5);
+        assert!(history.revisions_of(id)[4].reason.is_some());
+
+        let xml = history.save_xml_string().unwrap();
+        assert_eq!(History::load_xml_str(&xml).unwrap(), history);
+    }
+
+    #[test]
+    fn restore_tags_with_commas() {
+        let mut word = WordBuilder::new().build();
+        word.generate_id();
+        word.add_tag("a, b".to_string());
+        word.add_tag("c\\".to_string());
+        let mut history = History::new();
+
+        let before = word.clone();
+        word.remove_tag("a, b");
+        word.add_tag("d".to_string());
+        history.record(&before, &word, 100, None);
+        assert_eq!(
+            history.revisions_of(word.id().unwrap())[0].old_value,
+            "a\\, b, c\\\\"
+        );
+
+        assert!(history.restore(&mut word, 0, 200));
+        assert_eq!(word.tags().collect::<Vec<_>>(), vec!["a, b", "c\\"]);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use uuid::Uuid;
+
+use crate::phonology::Inventory;
+use crate::prelude::*;
+use crate::utils;
+
+use super::{Dictionary, Word};
+
+/// The attribute shared by homonyms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HomonymKind {
+    /// Words sharing the same pronunciation, ignoring stress and syllable boundaries.
+    Pronunciation,
+    /// Words sharing the same romanization, ignoring case.
+    Romanization,
+}
+
+impl HomonymKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pronunciation => "Pronunciation",
+            Self::Romanization => "Romanization",
+        }
+    }
+}
+
+/// A group of words sharing a pronunciation or a romanization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomonymGroup {
+    pub kind: HomonymKind,
+    /// The shared pronunciation or romanization.
+    pub key: String,
+    pub word_ids: Vec<Uuid>,
+}
+
+/// A pair of words whose pronunciations differ by a few segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearPair {
+    pub word_ids: [Uuid; 2],
+    /// The edit distance between the pronunciations, in segments.
+    pub distance: usize,
+}
+
+/// Homonyms and near-duplicates found in a dictionary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HomonymReport {
+    pub groups: Vec<HomonymGroup>,
+    /// Near-duplicates, closest first.
+    pub near_pairs: Vec<NearPair>,
+}
+
+/// Gets the key of `word` by which homonyms are compared, or `None` if the attribute is empty.
+fn homonym_key(word: &Word, kind: HomonymKind, inventory: &Inventory) -> Option<String> {
+    let ret = match kind {
+        HomonymKind::Pronunciation => inventory
+            .segment_pronunciation(word.pronunciation())
+            .concat(),
+        HomonymKind::Romanization => word.romanization().trim().to_lowercase(),
+    };
+    (!ret.is_empty()).then_some(ret)
+}
+
+impl HomonymReport {
+    /// Analyzes `dictionary`. Pairs of words whose pronunciations are at most `max_distance`
+    /// segments apart are reported as near-duplicates; `0` disables the search.
+    pub fn new(dictionary: &Dictionary, inventory: &Inventory, max_distance: usize) -> Self {
+        let mut groups = Vec::new();
+        for kind in [HomonymKind::Pronunciation, HomonymKind::Romanization] {
+            let mut by_key: BTreeMap<String, Vec<Uuid>> = BTreeMap::new();
+            for word in dictionary.iter_words() {
+                if let (Some(key), Some(id)) = (homonym_key(word, kind, inventory), word.id()) {
+                    by_key.entry(key).or_default().push(id);
+                }
+            }
+            groups.extend(by_key.into_iter().filter(|(_, ids)| ids.len() > 1).map(
+                |(key, mut word_ids)| {
+                    word_ids.sort();
+                    HomonymGroup {
+                        kind,
+                        key,
+                        word_ids,
+                    }
+                },
+            ));
+        }
+
+        let mut near_pairs = Vec::new();
+        if max_distance > 0 {
+            let segmented = dictionary
+                .iter_words()
+                .filter_map(|w| Some((w.id()?, inventory.segment_pronunciation(w.pronunciation()))))
+                .filter(|(_, segs)| !segs.is_empty())
+                .collect::<Vec<_>>();
+
+            for (i, (a_id, a)) in segmented.iter().enumerate() {
+                for (b_id, b) in segmented[i + 1..].iter() {
+                    if a.len().abs_diff(b.len()) > max_distance {
+                        continue;
+                    }
+                    let distance = utils::levenshtein(a, b);
+                    if distance > 0 && distance <= max_distance {
+                        near_pairs.push(NearPair {
+                            word_ids: [*a_id.min(b_id), *a_id.max(b_id)],
+                            distance,
+                        });
+                    }
+                }
+            }
+            near_pairs.sort_by_key(|p| (p.distance, p.word_ids));
+        }
+
+        Self { groups, near_pairs }
+    }
+
+    /// Returns `true` if nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty() && self.near_pairs.is_empty()
+    }
+
+    /// Formats the report as plain text, naming words by their romanization.
+    pub fn to_text(&self, dictionary: &Dictionary) -> String {
+        let name = |id: &Uuid| {
+            dictionary
+                .word_by_id(*id)
+                .map(|w| format!("{} ‘{}’", w.romanization(), w.translation()))
+                .unwrap_or_default()
+        };
+
+        let mut ret = String::new();
+        for group in self.groups.iter() {
+            let _ = writeln!(ret, "{} homonyms: {}", group.kind.name(), group.key);
+            for id in group.word_ids.iter() {
+                let _ = writeln!(ret, "  {}", name(id));
+            }
+        }
+        if !self.near_pairs.is_empty() {
+            let _ = writeln!(ret, "Near-duplicates:");
+        }
+        for pair in self.near_pairs.iter() {
+            let _ = writeln!(
+                ret,
+                "  {} ~ {} ({})",
+                name(&pair.word_ids[0]),
+                name(&pair.word_ids[1]),
+                pair.distance
+            );
+        }
+        ret
+    }
+}
+
+/// Gets the ids of words in `dictionary`, other than `word` itself, sharing a pronunciation or
+/// a romanization with `word`.
+pub fn homonyms_of(word: &Word, dictionary: &Dictionary, inventory: &Inventory) -> Vec<Uuid> {
+    let keys = [HomonymKind::Pronunciation, HomonymKind::Romanization]
+        .map(|kind| homonym_key(word, kind, inventory));
+
+    dictionary
+        .iter_words()
+        .filter(|w| w.id() != word.id())
+        .filter(|w| {
+            [HomonymKind::Pronunciation, HomonymKind::Romanization]
+                .iter()
+                .zip(keys.iter())
+                .any(|(kind, key)| key.is_some() && homonym_key(w, *kind, inventory) == *key)
+        })
+        .filter_map(|w| w.id())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+    use crate::Phoneme;
+
+    use super::*;
+
+    #[test]
+    fn report() {
+        let mut inv = Inventory::new();
+        for s in ["t", "tʰ", "a", "m", "n", "i"] {
+            inv.add_phoneme(Phoneme::with_sound(s.to_string()));
+        }
+
+        let mut dict = Dictionary::new();
+        let mut add = |rom: &str, pron: &str| {
+            dict.add_word(
+                WordBuilder::new()
+                    .romanization(rom.to_string())
+                    .pronunciation(pron.to_string())
+                    .build(),
+            )
+        };
+        let tama = add("tama", "ˈta.ma");
+        let tama2 = add("Tama", "ta.ˈma");
+        let thama = add("thama", "ˈtʰa.ma");
+        let tana = add("tana", "ˈta.na");
+        let placeholder = add("", "");
+        add("", "");
+
+        let report = HomonymReport::new(&dict, &inv, 1);
+        let mut tamas = vec![tama, tama2];
+        tamas.sort();
+        assert_eq!(
+            report.groups,
+            vec![
+                HomonymGroup {
+                    kind: HomonymKind::Pronunciation,
+                    key: "tama".to_string(),
+                    word_ids: tamas.clone(),
+                },
+                HomonymGroup {
+                    kind: HomonymKind::Romanization,
+                    key: "tama".to_string(),
+                    word_ids: tamas.clone(),
+                },
+            ]
+        );
+        // thama ~ tama, thama ~ tama2, tana ~ tama, tana ~ tama2
+        assert_eq!(report.near_pairs.len(), 4);
+        assert!(report
+            .near_pairs
+            .iter()
+            .all(|p| p.distance == 1 && !p.word_ids.contains(&placeholder)));
+        assert!(!report
+            .near_pairs
+            .iter()
+            .any(|p| p.word_ids.contains(&thama) && p.word_ids.contains(&tana)));
+        assert!(HomonymReport::new(&dict, &inv, 0).near_pairs.is_empty());
+
+        let word = dict.word_by_id(tama).unwrap();
+        assert_eq!(homonyms_of(word, &dict, &inv), vec![tama2]);
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PartOfSpeech {
     Abbreviation,
     Adjective,
@@ -14,6 +16,7 @@ pub enum PartOfSpeech {
     Particle,
     Phrase,
     Pronoun,
+    #[serde(rename = "Proper Noun")]
     ProperNoun,
     Verb,
     // Custom(String),
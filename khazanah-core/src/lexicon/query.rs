@@ -0,0 +1,660 @@
+use regex::Regex;
+
+use crate::prelude::*;
+
+use super::{FieldDefinitions, Word};
+use crate::phonology::{self, normalize_syllables, Categories, Inventory, Pattern};
+
+/// Keys of query terms that search pronunciations by phonological pattern.
+pub const PATTERN_KEYS: &[&str] = &["pat", "pattern"];
+
+/// A word attribute that a query term looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryKey {
+    /// Romanization, translation, and tags.
+    Any,
+    Romanization,
+    Translation,
+    Pronunciation,
+    PartOfSpeech,
+    Tag,
+    Status,
+    /// A custom field, by name.
+    Field(String),
+}
+
+impl From<&str> for QueryKey {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "rom" | "romanization" | "word" => Self::Romanization,
+            "tr" | "translation" | "gloss" => Self::Translation,
+            "pron" | "pronunciation" | "ipa" => Self::Pronunciation,
+            "pos" => Self::PartOfSpeech,
+            "tag" => Self::Tag,
+            "status" => Self::Status,
+            _ => Self::Field(value.to_string()),
+        }
+    }
+}
+
+/// How a query term compares a value.
+#[derive(Debug, Clone)]
+pub enum QueryMatcher {
+    /// Case-insensitive equality, written as `key:value`.
+    Exact(String),
+    /// Case-insensitive substring, written as `key:~value`.
+    Contains(String),
+    /// Regular expression, written as `key:/regex/`.
+    Regex(Regex),
+    /// Phonological pattern over pronunciations, written as `pat:#CVN`. The regex is built by
+    /// [`Query::resolve_patterns`]; unresolved patterns match nothing.
+    Pattern(String, Option<Regex>),
+}
+
+impl QueryMatcher {
+    /// Returns `true` if `value` matches.
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            Self::Exact(s) => value.trim().to_lowercase() == s.to_lowercase(),
+            Self::Contains(s) => value.to_lowercase().contains(&s.to_lowercase()),
+            Self::Regex(re) => re.is_match(value),
+            Self::Pattern(_, Some(re)) => re.is_match(&normalize_syllables(value)),
+            Self::Pattern(_, None) => false,
+        }
+    }
+}
+
+/// A single condition of a query, e.g. `pos:verb`.
+#[derive(Debug, Clone)]
+pub struct QueryTerm {
+    pub key: QueryKey,
+    pub matcher: QueryMatcher,
+}
+
+impl QueryTerm {
+    /// Gets the values of the attribute of `word` that the term looks at.
+    fn values<'a>(&self, word: &'a Word, fields: &FieldDefinitions) -> Vec<&'a str> {
+        match &self.key {
+            QueryKey::Any => [word.romanization(), word.translation()]
+                .into_iter()
+                .chain(word.tags())
+                .collect(),
+            QueryKey::Romanization => vec![word.romanization()],
+            QueryKey::Translation => std::iter::once(word.translation())
+                .chain(word.glosses())
+                .collect(),
+            QueryKey::Pronunciation => vec![word.pronunciation()],
+            QueryKey::PartOfSpeech => word
+                .part_of_speech()
+                .map(|pos| vec![pos.name(), pos.label().trim_end_matches('.')])
+                .unwrap_or_default(),
+            QueryKey::Tag => word.tags().collect(),
+            QueryKey::Status => vec![word.status().name()],
+            QueryKey::Field(name) => fields
+                .iter_fields()
+                .find(|f| f.name().to_lowercase() == name.to_lowercase())
+                .and_then(|f| f.id())
+                .and_then(|id| word.field(id))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if `word` satisfies the term.
+    pub fn matches(&self, word: &Word, fields: &FieldDefinitions) -> bool {
+        self.values(word, fields)
+            .into_iter()
+            .any(|v| self.matcher.is_match(v))
+    }
+}
+
+/// A parsed dictionary search query.
+///
+/// Terms are written as `key:value` (exact), `key:~value` (substring), or `key:/regex/`.
+/// Values containing spaces can be quoted. Keys are `rom`, `tr`, `pron`, `pos`, `tag`, `status`,
+/// or the name of a custom field. `pat:pattern` searches pronunciations by phonological pattern.
+/// A term without key searches romanization, translation, and tags by substring. Terms are joined
+/// by `AND` (implicit), `OR`, and `NOT` (or `!`), and can be grouped by parentheses, e.g.
+/// `pos:verb translation:~"go" pron:/^ˈ?ta/ tag:archaic -tag:slang`. A leading `-` also negates
+/// a keyed term, a quoted term, or a group, but is part of a bare term, so `-tu` searches for the
+/// affix `-tu`.
+#[derive(Debug, Clone, Default)]
+pub enum Query {
+    /// Matches every word.
+    #[default]
+    All,
+    Term(QueryTerm),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+/// Error type that can be emitted by parsing a query.
+#[derive(Debug, thiserror::Error)]
+#[error("{kind} at position {position}")]
+pub struct QueryError {
+    /// The position of the error, in characters.
+    pub position: usize,
+    pub kind: QueryErrorKind,
+}
+
+/// The kind of a query parsing error.
+#[derive(Debug, thiserror::Error)]
+pub enum QueryErrorKind {
+    #[error("Unterminated quote")]
+    UnterminatedQuote,
+    #[error("Unterminated regular expression")]
+    UnterminatedRegex,
+    #[error("Invalid regular expression: {0}")]
+    Regex(#[from] regex::Error),
+    #[error("Missing value for `{0}`")]
+    MissingValue(String),
+    #[error("Unexpected `{0}`")]
+    UnexpectedToken(String),
+    #[error("Unclosed parenthesis")]
+    UnclosedParen,
+    #[error("Unexpected end of query")]
+    UnexpectedEnd,
+    #[error("Unknown key `{0}`")]
+    UnknownKey(String),
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(QueryTerm),
+}
+
+impl Token {
+    fn text(&self) -> &'static str {
+        match self {
+            Self::LParen => "(",
+            Self::RParen => ")",
+            Self::And => "AND",
+            Self::Or => "OR",
+            Self::Not => "NOT",
+            Self::Term(_) => "term",
+        }
+    }
+}
+
+/// Splits a query into tokens, along with their positions.
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    /// The names of custom fields, in lowercase.
+    field_names: Vec<String>,
+}
+
+impl Lexer {
+    fn error(&self, position: usize, kind: QueryErrorKind) -> QueryError {
+        QueryError { position, kind }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Reads a string delimited by `delim`, with the opening delimiter at the current
+    /// position. `\` escapes the delimiter.
+    fn read_delimited(&mut self, delim: char) -> Option<String> {
+        self.pos += 1;
+        let mut ret = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' if self.peek() == Some(delim) => {
+                    ret.push(delim);
+                    self.pos += 1;
+                }
+                c if c == delim => return Some(ret),
+                c => ret.push(c),
+            }
+        }
+        None
+    }
+
+    /// Returns `true` if a `-` at the current position negates what follows: a keyed term, a
+    /// quoted term, or a group.
+    fn is_negation(&self) -> bool {
+        let rest = &self.chars[self.pos + 1..];
+        match rest.first() {
+            Some('(' | '"') => true,
+            Some(_) => {
+                let n = rest
+                    .iter()
+                    .take_while(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | ':' | '"'))
+                    .count();
+                n > 0 && rest.get(n) == Some(&':')
+            }
+            None => false,
+        }
+    }
+
+    /// Reads a bare word, until whitespace, parenthesis, or one of `stops`.
+    fn read_word(&mut self, stops: &[char]) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '(' || c == ')' || stops.contains(&c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Reads a term value, which is a quoted string, a regex, or a bare word.
+    fn read_value(&mut self, contains: bool) -> Result<QueryMatcher, QueryError> {
+        let start = self.pos;
+        match self.peek() {
+            Some('"') => {
+                let s = self
+                    .read_delimited('"')
+                    .ok_or_else(|| self.error(start, QueryErrorKind::UnterminatedQuote))?;
+                Ok(match contains {
+                    true => QueryMatcher::Contains(s),
+                    false => QueryMatcher::Exact(s),
+                })
+            }
+            Some('/') if !contains => {
+                let s = self
+                    .read_delimited('/')
+                    .ok_or_else(|| self.error(start, QueryErrorKind::UnterminatedRegex))?;
+                Regex::new(&s)
+                    .map(QueryMatcher::Regex)
+                    .map_err(|e| self.error(start, e.into()))
+            }
+            _ => {
+                let s = self.read_word(&[]);
+                Ok(match contains {
+                    true => QueryMatcher::Contains(s),
+                    false => QueryMatcher::Exact(s),
+                })
+            }
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(usize, Token)>, QueryError> {
+        let mut ret = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            let token = match c {
+                c if c.is_whitespace() => {
+                    self.pos += 1;
+                    continue;
+                }
+                '(' => {
+                    self.pos += 1;
+                    Token::LParen
+                }
+                ')' => {
+                    self.pos += 1;
+                    Token::RParen
+                }
+                '!' => {
+                    self.pos += 1;
+                    Token::Not
+                }
+                '-' if self.is_negation() => {
+                    self.pos += 1;
+                    Token::Not
+                }
+                '"' | '/' => Token::Term(QueryTerm {
+                    key: QueryKey::Any,
+                    matcher: match self.read_value(false)? {
+                        QueryMatcher::Exact(s) => QueryMatcher::Contains(s),
+                        m => m,
+                    },
+                }),
+                _ => {
+                    let word = self.read_word(&[':', '"']);
+                    if word.is_empty() {
+                        return Err(
+                            self.error(start, QueryErrorKind::UnexpectedToken(c.to_string()))
+                        );
+                    }
+                    if self.peek() == Some(':') {
+                        self.pos += 1;
+                        let contains = self.peek() == Some('~');
+                        if contains {
+                            self.pos += 1;
+                        }
+                        let is_pattern = PATTERN_KEYS.contains(&word.to_lowercase().as_str());
+                        let matcher = match self.read_value(contains || is_pattern)? {
+                            QueryMatcher::Contains(s) if is_pattern => {
+                                QueryMatcher::Pattern(s, None)
+                            }
+                            m => m,
+                        };
+                        match &matcher {
+                            QueryMatcher::Exact(s)
+                            | QueryMatcher::Contains(s)
+                            | QueryMatcher::Pattern(s, _)
+                                if s.is_empty() =>
+                            {
+                                return Err(self.error(start, QueryErrorKind::MissingValue(word)))
+                            }
+                            _ => {}
+                        }
+                        let key = match is_pattern {
+                            true => QueryKey::Pronunciation,
+                            false => QueryKey::from(word.as_str()),
+                        };
+                        if let QueryKey::Field(name) = &key {
+                            if !self.field_names.contains(&name.to_lowercase()) {
+                                return Err(self.error(start, QueryErrorKind::UnknownKey(word)));
+                            }
+                        }
+                        Token::Term(QueryTerm { key, matcher })
+                    } else {
+                        match word.as_str() {
+                            "AND" => Token::And,
+                            "OR" => Token::Or,
+                            "NOT" => Token::Not,
+                            _ => Token::Term(QueryTerm {
+                                key: QueryKey::Any,
+                                matcher: QueryMatcher::Contains(word),
+                            }),
+                        }
+                    }
+                }
+            };
+            ret.push((start, token));
+        }
+
+        Ok(ret)
+    }
+}
+
+/// Builds a query from tokens by recursive descent.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(p, _)| *p)
+            .unwrap_or(self.end)
+    }
+
+    fn parse_or(&mut self) -> Result<Query, QueryError> {
+        let mut ret = vec![self.parse_and()?];
+        while let Some(Token::Or) = self.peek() {
+            self.pos += 1;
+            ret.push(self.parse_and()?);
+        }
+        Ok(match ret.len() {
+            1 => ret.remove(0),
+            _ => Query::Or(ret),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, QueryError> {
+        let mut ret = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    ret.push(self.parse_unary()?);
+                }
+                Some(Token::LParen | Token::Not | Token::Term(_)) => {
+                    ret.push(self.parse_unary()?);
+                }
+                _ => break,
+            }
+        }
+        Ok(match ret.len() {
+            1 => ret.remove(0),
+            _ => Query::And(ret),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, QueryError> {
+        let position = self.position();
+        let token = self.peek().cloned().ok_or(QueryError {
+            position,
+            kind: QueryErrorKind::UnexpectedEnd,
+        })?;
+        self.pos += 1;
+
+        match token {
+            Token::Not => Ok(Query::Not(Box::new(self.parse_unary()?))),
+            Token::Term(term) => Ok(Query::Term(term)),
+            Token::LParen => {
+                let ret = self.parse_or()?;
+                match self.peek() {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(ret)
+                    }
+                    _ => Err(QueryError {
+                        position,
+                        kind: QueryErrorKind::UnclosedParen,
+                    }),
+                }
+            }
+            t => Err(QueryError {
+                position,
+                kind: QueryErrorKind::UnexpectedToken(t.text().to_string()),
+            }),
+        }
+    }
+}
+
+impl Query {
+    /// Parses a query. An empty query matches every word. Keys that are neither built in nor the
+    /// name of a custom field in `fields` are an error.
+    pub fn parse(s: &str, fields: &FieldDefinitions) -> Result<Self, QueryError> {
+        let lexer = Lexer {
+            chars: s.chars().collect(),
+            pos: 0,
+            field_names: fields
+                .iter_fields()
+                .map(|f| f.name().to_lowercase())
+                .collect(),
+        };
+        let end = lexer.chars.len();
+        let tokens = lexer.tokenize()?;
+        if tokens.is_empty() {
+            return Ok(Self::All);
+        }
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end,
+        };
+        let ret = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(ret),
+            Some(t) => Err(QueryError {
+                position: parser.position(),
+                kind: QueryErrorKind::UnexpectedToken(t.text().to_string()),
+            }),
+        }
+    }
+
+    /// Builds the regular expressions of pattern terms, using data in `categories` and
+    /// `inventory`.
+    pub fn resolve_patterns(
+        &mut self,
+        categories: &Categories,
+        inventory: &Inventory,
+    ) -> Result<(), phonology::Error> {
+        match self {
+            Self::All => {}
+            Self::Term(QueryTerm {
+                matcher: QueryMatcher::Pattern(s, re),
+                ..
+            }) => {
+                *re = Some(Pattern::new(s.clone()).search_regex(categories, inventory)?);
+            }
+            Self::Term(_) => {}
+            Self::Not(q) => q.resolve_patterns(categories, inventory)?,
+            Self::And(qs) | Self::Or(qs) => {
+                for q in qs.iter_mut() {
+                    q.resolve_patterns(categories, inventory)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `word` satisfies the query. `fields` resolves custom field names.
+    pub fn matches(&self, word: &Word, fields: &FieldDefinitions) -> bool {
+        match self {
+            Self::All => true,
+            Self::Term(term) => term.matches(word, fields),
+            Self::Not(q) => !q.matches(word, fields),
+            Self::And(qs) => qs.iter().all(|q| q.matches(word, fields)),
+            Self::Or(qs) => qs.iter().any(|q| q.matches(word, fields)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::{FieldDefinition, FieldKind, WordBuilder};
+    use crate::PartOfSpeech;
+
+    use super::*;
+
+    fn words() -> (Vec<Word>, FieldDefinitions) {
+        let mut fields = FieldDefinitions::new();
+        let register = fields.add_field(FieldDefinition::new(
+            "Register".to_string(),
+            FieldKind::Text,
+        ));
+
+        let mut go = WordBuilder::new()
+            .romanization("tama".to_string())
+            .translation("go, walk".to_string())
+            .pronunciation("ˈta.ma".to_string())
+            .part_of_speech(PartOfSpeech::Verb)
+            .build();
+        go.add_tag("archaic".to_string());
+        go.set_field(register, Some("formal".to_string()));
+
+        let mut going = WordBuilder::new()
+            .romanization("tamani".to_string())
+            .translation("going away".to_string())
+            .pronunciation("ta.ˈma.ni".to_string())
+            .part_of_speech(PartOfSpeech::Noun)
+            .build();
+        going.add_tag("archaic".to_string());
+        going.add_tag("slang".to_string());
+
+        (vec![go, going], fields)
+    }
+
+    fn matching(q: &str) -> Vec<usize> {
+        let (words, fields) = words();
+        let q = Query::parse(q, &fields).unwrap();
+        (0..words.len())
+            .filter(|&i| q.matches(&words[i], &fields))
+            .collect()
+    }
+
+    #[test]
+    fn matches() {
+        assert_eq!(matching(""), vec![0, 1]);
+        assert_eq!(matching("pos:verb"), vec![0]);
+        assert_eq!(matching("pos:n"), vec![1]);
+        assert_eq!(matching("translation:go"), vec![0]);
+        assert_eq!(matching("translation:~\"go\""), vec![0, 1]);
+        assert_eq!(matching("pron:/^ˈ?ta/"), vec![0, 1]);
+        assert_eq!(matching("pron:/^ˈta/"), vec![0]);
+        assert_eq!(matching("tag:archaic -tag:slang"), vec![0]);
+        assert_eq!(matching("NOT tag:archaic"), Vec::<usize>::new());
+        assert_eq!(matching("pos:noun OR register:FORMAL"), vec![0, 1]);
+        assert_eq!(matching("(pos:noun OR pos:verb) AND away"), vec![1]);
+        assert_eq!(matching("!tag:slang"), vec![0]);
+        assert_eq!(matching("-(pos:verb)"), vec![1]);
+        assert_eq!(matching("-\"away\""), vec![0]);
+    }
+
+    #[test]
+    fn hyphenated_terms() {
+        let fields = FieldDefinitions::new();
+        let affix = WordBuilder::new()
+            .romanization("-tu".to_string())
+            .translation("PL".to_string())
+            .build();
+        let root = WordBuilder::new()
+            .romanization("nifu".to_string())
+            .translation("sun".to_string())
+            .build();
+
+        // A bare term starting with `-` is searched for, not negated.
+        let q = Query::parse("-tu", &fields).unwrap();
+        assert!(matches!(
+            &q,
+            Query::Term(QueryTerm { key: QueryKey::Any, matcher: QueryMatcher::Contains(s) })
+                if s == "-tu"
+        ));
+        assert!(q.matches(&affix, &fields));
+        assert!(!q.matches(&root, &fields));
+
+        let q = Query::parse("rom:-tu", &fields).unwrap();
+        assert!(q.matches(&affix, &fields));
+        let q = Query::parse("-rom:-tu", &fields).unwrap();
+        assert!(!q.matches(&affix, &fields));
+        assert!(q.matches(&root, &fields));
+    }
+
+    #[test]
+    fn patterns() {
+        let (words, fields) = words();
+        let mut cats = Categories::new();
+        let mut inv = Inventory::new();
+        let mut cat = crate::phonology::Category::new();
+        cat.set_name("N".to_string());
+        cat.add_phoneme_id(inv.add_phoneme(crate::Phoneme::with_sound("m".to_string())));
+        cat.add_phoneme_id(inv.add_phoneme(crate::Phoneme::with_sound("n".to_string())));
+        let _ = cats.add_category(cat);
+
+        let mut q = Query::parse("pat:N#", &fields).unwrap();
+        assert!(!q.matches(&words[0], &fields));
+        q.resolve_patterns(&cats, &inv).unwrap();
+        assert!(!q.matches(&words[0], &fields));
+
+        let mut q = Query::parse("pat:#taNa#", &fields).unwrap();
+        q.resolve_patterns(&cats, &inv).unwrap();
+        assert!(q.matches(&words[0], &fields));
+        assert!(!q.matches(&words[1], &fields));
+    }
+
+    #[test]
+    fn errors() {
+        let (_, fields) = words();
+        let err = |q: &str| Query::parse(q, &fields).unwrap_err();
+        assert!(matches!(
+            err("tr:\"go").kind,
+            QueryErrorKind::UnterminatedQuote
+        ));
+        assert!(matches!(err("pron:/(/").kind, QueryErrorKind::Regex(_)));
+        assert!(matches!(err("pos:").kind, QueryErrorKind::MissingValue(_)));
+        assert!(matches!(err("(a OR b").kind, QueryErrorKind::UnclosedParen));
+        assert!(matches!(err("a OR").kind, QueryErrorKind::UnexpectedEnd));
+        let e = err("a ) b");
+        assert!(matches!(e.kind, QueryErrorKind::UnexpectedToken(_)));
+        assert_eq!(e.position, 2);
+        let e = err("a foo:bar");
+        assert!(matches!(&e.kind, QueryErrorKind::UnknownKey(k) if k == "foo"));
+        assert_eq!(e.position, 2);
+        assert!(Query::parse("register:formal", &fields).is_ok());
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use uuid::Uuid;
+
+use crate::prelude::*;
+use crate::utils;
+
+use super::{normalize_gloss, Dictionary};
+
+/// English function words that are not indexed.
+pub const STOPWORDS: &[&str] = &[
+    "a",
+    "an",
+    "and",
+    "as",
+    "at",
+    "be",
+    "by",
+    "for",
+    "from",
+    "in",
+    "into",
+    "is",
+    "it",
+    "of",
+    "on",
+    "one",
+    "oneself",
+    "or",
+    "some",
+    "someone",
+    "something",
+    "the",
+    "to",
+    "with",
+];
+
+/// Reduces an English word to a crude stem by removing common inflectional suffixes, so
+/// `"running"`, `"runs"`, and `"run"` all become `"run"`.
+pub fn stem(word: &str) -> String {
+    let mut ret = word.to_lowercase();
+    let n = ret.chars().count();
+
+    if n > 4 && ret.ends_with("ies") {
+        ret.truncate(ret.len() - 3);
+        ret.push('y');
+        return ret;
+    }
+
+    if n > 5 && ret.ends_with("ing") {
+        ret.truncate(ret.len() - 3);
+        undouble(&mut ret);
+    } else if n > 4 && ret.ends_with("ed") {
+        ret.truncate(ret.len() - 2);
+        undouble(&mut ret);
+    } else if n > 3 && ret.ends_with('s') && !ret.ends_with("ss") {
+        ret.truncate(ret.len() - 1);
+    }
+
+    if ret.chars().count() > 2 && ret.ends_with('e') && !ret.ends_with("ee") {
+        ret.pop();
+    }
+    ret
+}
+
+/// Undoes consonant doubling at the end of a stem, as in "stopp-ed" or "runn-ing".
+fn undouble(s: &mut String) {
+    let mut chars = s.chars().rev();
+    if let (Some(a), Some(b)) = (chars.next(), chars.next()) {
+        if a == b && !"aeioulsz".contains(a) {
+            s.pop();
+        }
+    }
+}
+
+/// Splits a gloss into lowercase keywords. If `english` is `true`, stopwords are removed.
+pub fn keywords(gloss: &str, english: bool) -> Vec<String> {
+    normalize_gloss(gloss)
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|s| !s.is_empty())
+        .filter(|s| !english || !STOPWORDS.contains(s))
+        .map(str::to_string)
+        .collect()
+}
+
+/// A word found by a reverse lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseMatch {
+    /// The id of the word.
+    pub word_id: Uuid,
+    /// The number of keywords of the lookup that the word matches.
+    pub n_matched: usize,
+    /// The sum of edit distances of the matched keywords. `0` means every keyword matched
+    /// exactly, after stemming.
+    pub distance: usize,
+}
+
+/// An inverted index from keywords of word translations to words, for looking up words by
+/// their meaning in the local language.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReverseIndex {
+    /// Word ids, by keyword stem.
+    index: BTreeMap<String, BTreeSet<Uuid>>,
+    /// Whether the local language is English, enabling stopwords and stemming.
+    english: bool,
+}
+
+impl ReverseIndex {
+    /// Builds the index of `dictionary`. Translations are split on commas and semicolons.
+    /// If `english` is `true`, stopwords are removed and keywords are stemmed.
+    pub fn new(dictionary: &Dictionary, english: bool) -> Self {
+        let mut ret = Self {
+            index: BTreeMap::new(),
+            english,
+        };
+
+        for word in dictionary.iter_words() {
+            let Some(id) = word.id() else {
+                continue;
+            };
+            for gloss in word.glosses() {
+                for keyword in keywords(gloss, english) {
+                    ret.index.entry(ret.key(&keyword)).or_default().insert(id);
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Gets the index key of a keyword.
+    fn key(&self, keyword: &str) -> String {
+        match self.english {
+            true => stem(keyword),
+            false => keyword.to_lowercase(),
+        }
+    }
+
+    /// Gets the number of distinct keywords.
+    pub fn n_keywords(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Looks up words whose translation contains the keywords of `text`. A keyword also matches
+    /// indexed keywords within `max_distance` edits, if it is longer than `2 * max_distance + 1`
+    /// characters. Results are ranked by the number of matched keywords, and then by distance.
+    pub fn lookup(&self, text: &str, max_distance: usize) -> Vec<ReverseMatch> {
+        let mut found: BTreeMap<Uuid, (usize, usize)> = BTreeMap::new();
+
+        for keyword in keywords(text, self.english) {
+            let key = self.key(&keyword);
+            let key_chars = key.chars().collect::<Vec<_>>();
+
+            // Best distance of this keyword for each word.
+            let mut best: BTreeMap<Uuid, usize> = BTreeMap::new();
+            for (k, ids) in self.index.iter() {
+                let distance = if *k == key {
+                    0
+                } else if key_chars.len() > 2 * max_distance + 1 {
+                    utils::levenshtein(&key_chars, &k.chars().collect::<Vec<_>>())
+                } else {
+                    continue;
+                };
+                if distance > max_distance {
+                    continue;
+                }
+                for id in ids {
+                    let d = best.entry(*id).or_insert(distance);
+                    *d = std::cmp::min(*d, distance);
+                }
+            }
+
+            for (id, distance) in best {
+                let entry = found.entry(id).or_default();
+                entry.0 += 1;
+                entry.1 += distance;
+            }
+        }
+
+        let mut ret = found
+            .into_iter()
+            .map(|(word_id, (n_matched, distance))| ReverseMatch {
+                word_id,
+                n_matched,
+                distance,
+            })
+            .collect::<Vec<_>>();
+        ret.sort_by(|a, b| {
+            b.n_matched
+                .cmp(&a.n_matched)
+                .then(a.distance.cmp(&b.distance))
+        });
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexicon::WordBuilder;
+
+    use super::*;
+
+    #[test]
+    fn stems() {
+        assert_eq!(stem("running"), "run");
+        assert_eq!(stem("runs"), "run");
+        assert_eq!(stem("stopped"), "stop");
+        assert_eq!(stem("making"), stem("make"));
+        assert_eq!(stem("berries"), "berry");
+        assert_eq!(stem("grass"), "grass");
+        assert_eq!(keywords("to go (away); the sun", true), vec!["go", "sun"]);
+        assert_eq!(
+            utils::levenshtein(&['k', 'a', 't'], &['k', 'a', 's', 't']),
+            1
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let mut dict = Dictionary::new();
+        let run = dict.add_word(
+            WordBuilder::new()
+                .translation("run, flee".to_string())
+                .build(),
+        );
+        let sun = dict.add_word(
+            WordBuilder::new()
+                .translation("the sun".to_string())
+                .build(),
+        );
+        let sunrise = dict.add_word(
+            WordBuilder::new()
+                .translation("rising of the sun".to_string())
+                .build(),
+        );
+
+        let index = ReverseIndex::new(&dict, true);
+        let ids = |v: Vec<ReverseMatch>| v.into_iter().map(|m| m.word_id).collect::<Vec<_>>();
+
+        assert_eq!(ids(index.lookup("running", 0)), vec![run]);
+        assert_eq!(ids(index.lookup("the", 1)), vec![]);
+        assert_eq!(ids(index.lookup("sun rises", 0)), vec![sunrise, sun]);
+        assert_eq!(ids(index.lookup("flea", 1)), vec![run]);
+        assert_eq!(ids(index.lookup("flea", 0)), vec![]);
+    }
+
+    #[test]
+    fn stemming_by_default() {
+        use crate::Language;
+
+        let mut language = Language::new();
+        let run = language
+            .dictionary_mut()
+            .add_word(WordBuilder::new().translation("to run".to_string()).build());
+        let ids = |v: Vec<ReverseMatch>| v.into_iter().map(|m| m.word_id).collect::<Vec<_>>();
+
+        let index = language.dictionary_reverse_index();
+        assert_eq!(ids(index.lookup("running", 0)), vec![run]);
+
+        language.meta_mut().local_lang = "Indonesian".to_string();
+        let index = language.dictionary_reverse_index();
+        assert_eq!(ids(index.lookup("running", 0)), vec![]);
+    }
+}
@@ -0,0 +1,397 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+use uuid::Uuid;
+
+use crate::{
+    phonology::{Categories, Inventory},
+    prelude::*,
+};
+
+use super::{AffixKind, Dictionary, PartOfSpeech};
+
+/// A morpheme of a segmented word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Morpheme {
+    /// The romanization of the morpheme as it appears in the word.
+    pub form: String,
+    /// The id of the dictionary entry of the morpheme.
+    pub word_id: Uuid,
+    /// How the morpheme attaches, or `None` for roots.
+    pub kind: Option<AffixKind>,
+}
+
+/// A word split into its morphemes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Segmentation {
+    pub morphemes: Vec<Morpheme>,
+}
+
+impl Segmentation {
+    /// Gets the Leipzig-style separator between morphemes `prev` and `next`: `<` and `>` around
+    /// infixes, and `-` otherwise.
+    pub fn separator(prev: &Morpheme, next: &Morpheme) -> &'static str {
+        match (prev.kind, next.kind) {
+            (_, Some(AffixKind::Infix)) => "<",
+            (Some(AffixKind::Infix), _) => ">",
+            _ => "-",
+        }
+    }
+}
+
+impl Display for Segmentation {
+    /// Formats the segmentation with Leipzig-style separators, e.g. `nifu-tu` or `s<um>ulat`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut prev: Option<&Morpheme> = None;
+        for m in self.morphemes.iter() {
+            if let Some(p) = prev {
+                write!(f, "{}", Self::separator(p, m))?;
+            }
+            write!(f, "{}", m.form)?;
+            prev = Some(m);
+        }
+        Ok(())
+    }
+}
+
+/// A morpheme that may be matched during segmentation.
+struct Candidate<'a> {
+    form: &'a str,
+    word_id: Uuid,
+    kind: Option<AffixKind>,
+    allomorph: Option<&'a super::Allomorph>,
+}
+
+/// The state of a search: position, whether the root is found, and the pending circumfix part.
+type SearchState<'a> = (usize, bool, Option<(Uuid, &'a str)>);
+
+struct Segmenter<'a> {
+    s: &'a str,
+    roots: Vec<Candidate<'a>>,
+    prefixes: Vec<Candidate<'a>>,
+    suffixes: Vec<Candidate<'a>>,
+    infixes: Vec<Candidate<'a>>,
+    categories: &'a Categories,
+    inventory: &'a Inventory,
+    /// Results of searches already done, as the same state is reached through many paths.
+    memo: RefCell<HashMap<SearchState<'a>, Option<Vec<Morpheme>>>>,
+}
+
+impl<'a> Segmenter<'a> {
+    fn fits(&self, c: &Candidate, start: usize, end: usize) -> bool {
+        c.allomorph
+            .map(|a| {
+                a.matches_environment(
+                    &self.s[..start],
+                    &self.s[end..],
+                    self.categories,
+                    self.inventory,
+                )
+            })
+            .unwrap_or(true)
+    }
+
+    /// Finds the segmentation of `s[pos..]` with the fewest morphemes.
+    fn search(
+        &self,
+        pos: usize,
+        after_root: bool,
+        pending: Option<(Uuid, &'a str)>,
+    ) -> Option<Vec<Morpheme>> {
+        let state = (pos, after_root, pending);
+        if let Some(ret) = self.memo.borrow().get(&state) {
+            return ret.clone();
+        }
+        let ret = self.search_uncached(pos, after_root, pending);
+        self.memo.borrow_mut().insert(state, ret.clone());
+        ret
+    }
+
+    fn search_uncached(
+        &self,
+        pos: usize,
+        after_root: bool,
+        pending: Option<(Uuid, &'a str)>,
+    ) -> Option<Vec<Morpheme>> {
+        let rest = &self.s[pos..];
+        let mut best: Option<Vec<Morpheme>> = None;
+        let mut consider = |head: Vec<Morpheme>, tail: Option<Vec<Morpheme>>| {
+            if let Some(tail) = tail {
+                let len = head.len() + tail.len();
+                if best.as_ref().map(|b| len < b.len()).unwrap_or(true) {
+                    best = Some(head.into_iter().chain(tail).collect());
+                }
+            }
+        };
+
+        if !after_root {
+            for c in self.prefixes.iter() {
+                let end = pos + c.form.len();
+                if !rest.starts_with(c.form) || !self.fits(c, pos, end) {
+                    continue;
+                }
+                let pending = if c.kind == Some(AffixKind::Circumfix) {
+                    if pending.is_some() {
+                        continue;
+                    }
+                    let (_, tail) = c.allomorph.unwrap().circumfix_parts();
+                    Some((c.word_id, tail))
+                } else {
+                    pending
+                };
+                consider(vec![morpheme(c.form, c)], self.search(end, false, pending));
+            }
+
+            for c in self.roots.iter() {
+                if rest.starts_with(c.form) {
+                    let end = pos + c.form.len();
+                    consider(vec![morpheme(c.form, c)], self.search(end, true, pending));
+                }
+
+                for (k, _) in c.form.char_indices().skip(1) {
+                    let (left, right) = c.form.split_at(k);
+                    if !rest.starts_with(left) {
+                        break;
+                    }
+                    for i in self.infixes.iter() {
+                        let i_start = pos + left.len();
+                        let i_end = i_start + i.form.len();
+                        if self.s[i_start..].starts_with(i.form)
+                            && self.s[i_end..].starts_with(right)
+                            && self.fits(i, i_start, i_end)
+                        {
+                            consider(
+                                vec![morpheme(left, c), morpheme(i.form, i), morpheme(right, c)],
+                                self.search(i_end + right.len(), true, pending),
+                            );
+                        }
+                    }
+                }
+            }
+        } else {
+            if rest.is_empty() && pending.is_none() {
+                return Some(Vec::new());
+            }
+
+            for c in self.suffixes.iter() {
+                let end = pos + c.form.len();
+                if !rest.starts_with(c.form) || !self.fits(c, pos, end) {
+                    continue;
+                }
+                if c.kind == Some(AffixKind::Circumfix) {
+                    if pending == Some((c.word_id, c.form)) {
+                        consider(vec![morpheme(c.form, c)], self.search(end, true, None));
+                    }
+                } else {
+                    consider(vec![morpheme(c.form, c)], self.search(end, true, pending));
+                }
+            }
+        }
+
+        best
+    }
+}
+
+fn morpheme(form: &str, c: &Candidate) -> Morpheme {
+    Morpheme {
+        form: form.to_string(),
+        word_id: c.word_id,
+        kind: c.kind,
+    }
+}
+
+/// Splits `romanization` into morphemes known by `dictionary`. Words that are not affixes are
+/// used as roots. Returns the segmentation with the fewest morphemes, preferring longer leading
+/// morphemes among equals, or `None` if the string can't be fully segmented. The word of id
+/// `exclude`, if any, is not used as a morpheme.
+pub fn segment(
+    romanization: &str,
+    exclude: Option<Uuid>,
+    dictionary: &Dictionary,
+    categories: &Categories,
+    inventory: &Inventory,
+) -> Option<Segmentation> {
+    let mut segmenter = Segmenter {
+        s: romanization,
+        roots: Vec::new(),
+        prefixes: Vec::new(),
+        suffixes: Vec::new(),
+        infixes: Vec::new(),
+        categories,
+        inventory,
+        memo: RefCell::new(HashMap::new()),
+    };
+
+    for word in dictionary.iter_words() {
+        let Some(word_id) = word.id() else { continue };
+        if exclude == Some(word_id) {
+            continue;
+        }
+
+        match word.affix() {
+            Some(affix) => {
+                for allo in affix.allomorphs.iter() {
+                    let c = |form| Candidate {
+                        form,
+                        word_id,
+                        kind: Some(affix.kind),
+                        allomorph: Some(allo),
+                    };
+                    match affix.kind {
+                        AffixKind::Prefix => segmenter.prefixes.push(c(&allo.form)),
+                        AffixKind::Suffix => segmenter.suffixes.push(c(&allo.form)),
+                        AffixKind::Infix => segmenter.infixes.push(c(&allo.form)),
+                        AffixKind::Circumfix => {
+                            let (head, tail) = allo.circumfix_parts();
+                            segmenter.prefixes.push(c(head));
+                            segmenter.suffixes.push(c(tail));
+                        }
+                    }
+                }
+            }
+            None if word.part_of_speech() != Some(PartOfSpeech::Affix) => {
+                segmenter.roots.push(Candidate {
+                    form: word.romanization(),
+                    word_id,
+                    kind: None,
+                    allomorph: None,
+                });
+            }
+            None => {}
+        }
+    }
+
+    for list in [
+        &mut segmenter.roots,
+        &mut segmenter.prefixes,
+        &mut segmenter.suffixes,
+        &mut segmenter.infixes,
+    ] {
+        list.retain(|c| !c.form.is_empty());
+        // Among segmentations with as many morphemes, the one with the longest morphemes first
+        // wins, regardless of the order of the dictionary.
+        list.sort_by(|a, b| {
+            b.form
+                .len()
+                .cmp(&a.form.len())
+                .then(a.word_id.cmp(&b.word_id))
+        });
+    }
+
+    segmenter
+        .search(0, false, None)
+        .map(|morphemes| Segmentation { morphemes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lexicon::{Affix, Allomorph, WordBuilder},
+        phonology::Category,
+        Phoneme, Word,
+    };
+
+    fn affix(romanization: &str, kind: AffixKind, allomorphs: Vec<Allomorph>) -> Word {
+        let mut word = WordBuilder::new()
+            .romanization(romanization.to_string())
+            .part_of_speech(PartOfSpeech::Affix)
+            .build();
+        word.set_affix(Some(Affix { kind, allomorphs }));
+        word
+    }
+
+    fn root(romanization: &str) -> Word {
+        WordBuilder::new()
+            .romanization(romanization.to_string())
+            .part_of_speech(PartOfSpeech::Noun)
+            .build()
+    }
+
+    fn test_data() -> (Dictionary, Categories, Inventory) {
+        let mut cats = Categories::new();
+        let mut inv = Inventory::new();
+        let mut cat = Category::new();
+        cat.set_name("V".to_string());
+        for s in ["a", "i", "u"] {
+            cat.add_phoneme_id(inv.add_phoneme(Phoneme::with_sound(s.to_string())));
+        }
+        let _ = cats.add_category(cat);
+
+        let mut dict = Dictionary::new();
+        dict.add_word(root("nifu"));
+        dict.add_word(root("sulat"));
+        dict.add_word(affix(
+            "-tu",
+            AffixKind::Suffix,
+            vec![
+                Allomorph {
+                    form: "tu".to_string(),
+                    left: Some("V".to_string()),
+                    right: None,
+                },
+                Allomorph {
+                    form: "u".to_string(),
+                    left: None,
+                    right: None,
+                },
+            ],
+        ));
+        dict.add_word(affix(
+            "ka-",
+            AffixKind::Prefix,
+            vec![Allomorph::new("ka".to_string())],
+        ));
+        dict.add_word(affix(
+            "-um-",
+            AffixKind::Infix,
+            vec![Allomorph::new("um".to_string())],
+        ));
+        dict.add_word(affix(
+            "ge-...-t",
+            AffixKind::Circumfix,
+            vec![Allomorph::new("ge...t".to_string())],
+        ));
+
+        (dict, cats, inv)
+    }
+
+    fn seg(s: &str) -> Option<String> {
+        let (dict, cats, inv) = test_data();
+        segment(s, None, &dict, &cats, &inv).map(|s| s.to_string())
+    }
+
+    #[test]
+    fn segment_words() {
+        assert_eq!(seg("nifutu").as_deref(), Some("nifu-tu"));
+        assert_eq!(seg("kanifu").as_deref(), Some("ka-nifu"));
+        assert_eq!(seg("sumulat").as_deref(), Some("s<um>ulat"));
+        assert_eq!(seg("genifut").as_deref(), Some("ge-nifu-t"));
+        assert_eq!(seg("sulatu").as_deref(), Some("sulat-u"));
+        // `tu` requires a preceding vowel.
+        assert_eq!(seg("sulattu"), None);
+        // Circumfix needs both parts.
+        assert_eq!(seg("genifu"), None);
+        assert_eq!(seg("xyz"), None);
+    }
+
+    #[test]
+    fn segment_long_word() {
+        let mut dict = Dictionary::new();
+        dict.add_word(root("a"));
+        for form in ["a", "aa", "aaa"] {
+            dict.add_word(affix(
+                form,
+                AffixKind::Suffix,
+                vec![Allomorph::new(form.to_string())],
+            ));
+        }
+        let (cats, inv) = (Categories::new(), Inventory::new());
+
+        // Every split of the tail is tried, which is only feasible with memoization.
+        let s = "a".repeat(80) + "x";
+        assert_eq!(segment(&s, None, &dict, &cats, &inv), None);
+        let s = "a".repeat(80);
+        let seg = segment(&s, None, &dict, &cats, &inv).unwrap();
+        assert_eq!(seg.morphemes.len(), 28);
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+/// The review status of a word.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WordStatus {
+    /// A word still being worked on.
+    Draft,
+    /// A word submitted for review.
+    Proposed,
+    /// An accepted word. Words without a status are approved.
+    #[default]
+    Approved,
+    /// A word that should no longer be used, usually in favor of a replacement.
+    Deprecated,
+    /// A word that is no longer in use.
+    Obsolete,
+}
+
+impl WordStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::Proposed => "Proposed",
+            Self::Approved => "Approved",
+            Self::Deprecated => "Deprecated",
+            Self::Obsolete => "Obsolete",
+        }
+    }
+
+    /// Returns `true` if the word may have a replacement.
+    pub fn is_retired(&self) -> bool {
+        matches!(self, Self::Deprecated | Self::Obsolete)
+    }
+}
+
+impl From<&str> for WordStatus {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "draft" => Self::Draft,
+            "proposed" => Self::Proposed,
+            "deprecated" => Self::Deprecated,
+            "obsolete" => Self::Obsolete,
+            _ => Self::Approved,
+        }
+    }
+}
+
+pub const ALL_WORD_STATUSES: &[WordStatus] = &[
+    WordStatus::Draft,
+    WordStatus::Proposed,
+    WordStatus::Approved,
+    WordStatus::Deprecated,
+    WordStatus::Obsolete,
+];
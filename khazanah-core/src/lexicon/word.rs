@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
@@ -6,11 +7,16 @@ use crate::{
     IdAble,
 };
 
-use super::{Error, PartOfSpeech};
-use std::{fmt::Debug, io::Write};
+use super::{Affix, AffixKind, Allomorph, DerivationLink, Error, PartOfSpeech, WordStatus};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    io::Write,
+};
 
 /// Word entry in the lexicon.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Word {
     /// The id of the word.
     id: Option<Uuid>,
@@ -21,9 +27,31 @@ pub struct Word {
     /// The pronunciation of word in IPA.
     pronunciation: String,
     /// Which part of speech this word belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
     part_of_speech: Option<PartOfSpeech>,
     /// The X-SAMPA pronunciation of the word, if exists.
+    #[serde(skip_serializing_if = "Option::is_none")]
     xsampa_pronunciation: Option<String>,
+    /// The root and rule this word is derived from, if any.
+    #[serde(rename = "derived-from", skip_serializing_if = "Option::is_none")]
+    derivation: Option<DerivationLink>,
+    /// Attachment information, if this word is an affix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    affix: Option<Affix>,
+    /// Free-form tags of the word.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<String>,
+    /// Values of custom fields, by field id.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<Uuid, String>,
+    /// The review status of the word.
+    status: WordStatus,
+    /// The word replacing this word, if it is deprecated or obsolete.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replaced_by: Option<Uuid>,
+    /// Ids of media assets attached to the word, such as recordings and illustrations.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    media: Vec<Uuid>,
 }
 
 impl IdAble for Word {
@@ -74,6 +102,15 @@ impl Word {
         self.translation = value;
     }
 
+    /// Iterates over the senses of the translation, which are separated by commas or
+    /// semicolons.
+    pub fn glosses(&self) -> impl Iterator<Item = &str> {
+        self.translation
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+
     /// Gets the IPA pronunciation of the word.
     pub fn pronunciation(&self) -> &str {
         self.pronunciation.as_str()
@@ -108,19 +145,133 @@ impl Word {
     pub fn set_part_of_speech(&mut self, value: Option<PartOfSpeech>) {
         self.part_of_speech = value;
     }
+
+    /// Gets the root and rule this word is derived from.
+    pub fn derivation(&self) -> Option<DerivationLink> {
+        self.derivation
+    }
+
+    /// Sets the root and rule this word is derived from.
+    pub fn set_derivation(&mut self, value: Option<DerivationLink>) {
+        self.derivation = value;
+    }
+
+    /// Gets the attachment information of the word, if it is an affix.
+    pub fn affix(&self) -> Option<&Affix> {
+        self.affix.as_ref()
+    }
+
+    /// Gets a mutable reference to the attachment information of the word.
+    pub fn affix_mut(&mut self) -> Option<&mut Affix> {
+        self.affix.as_mut()
+    }
+
+    /// Iterates over the tags of the word, in alphabetical order.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|s| s.as_str())
+    }
+
+    /// Returns `true` if the word has tag `tag`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Adds a tag to the word. Returns `false` if the word already has the tag.
+    pub fn add_tag(&mut self, tag: String) -> bool {
+        let tag = tag.trim();
+        !tag.is_empty() && self.tags.insert(tag.to_string())
+    }
+
+    /// Removes a tag from the word. Returns `false` if the word doesn't have the tag.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.tags.remove(tag)
+    }
+
+    /// Gets the value of custom field of id `field_id`.
+    pub fn field(&self, field_id: Uuid) -> Option<&str> {
+        self.fields.get(&field_id).map(|s| s.as_str())
+    }
+
+    /// Sets the value of custom field of id `field_id`, or clears it if `value` is `None`.
+    pub fn set_field(&mut self, field_id: Uuid, value: Option<String>) {
+        match value {
+            Some(v) => self.fields.insert(field_id, v),
+            None => self.fields.remove(&field_id),
+        };
+    }
+
+    /// Iterates over the values of custom fields, with their field ids.
+    pub fn fields(&self) -> impl Iterator<Item = (Uuid, &str)> {
+        self.fields.iter().map(|(k, v)| (*k, v.as_str()))
+    }
+
+    /// Gets the review status of the word.
+    pub fn status(&self) -> WordStatus {
+        self.status
+    }
+
+    /// Sets the review status of the word.
+    pub fn set_status(&mut self, value: WordStatus) {
+        self.status = value;
+    }
+
+    /// Gets the id of the word replacing this word.
+    pub fn replaced_by(&self) -> Option<Uuid> {
+        self.replaced_by
+    }
+
+    /// Sets the id of the word replacing this word.
+    pub fn set_replaced_by(&mut self, value: Option<Uuid>) {
+        self.replaced_by = value;
+    }
+
+    /// Sets the attachment information of the word.
+    pub fn set_affix(&mut self, value: Option<Affix>) {
+        self.affix = value;
+    }
+
+    /// Gets the ids of media assets attached to the word, in order of attachment.
+    pub fn media(&self) -> &[Uuid] {
+        &self.media
+    }
+
+    /// Attaches the media asset of id `id`. Returns `false` if it is already attached.
+    pub fn add_media(&mut self, id: Uuid) -> bool {
+        if self.media.contains(&id) {
+            return false;
+        }
+        self.media.push(id);
+        true
+    }
+
+    /// Detaches the media asset of id `id`. Returns `false` if it is not attached.
+    pub fn remove_media(&mut self, id: Uuid) -> bool {
+        let len = self.media.len();
+        self.media.retain(|&x| x != id);
+        self.media.len() != len
+    }
+}
+
+/// State of reading a word.
+#[derive(Debug, Default)]
+pub struct WordReaderState {
+    /// The derivation rule of the `derived-from` element being read.
+    rule_id: Option<Uuid>,
+    /// The field definition of the `field` element being read.
+    field_id: Option<Uuid>,
 }
 
 impl ReadXml for Word {
     type Error = Error;
 
-    type ReaderState = ();
+    type ReaderState = WordReaderState;
 
     const TAG: &'static str = "word";
 
     fn process_tag_start<R: std::io::BufRead>(
         &mut self,
         reader: &mut crate::xml::XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        state: &mut Self::ReaderState,
         _name: String,
         attrs: Vec<(String, String)>,
     ) -> Result<(), XmlError<Self::Error>> {
@@ -152,6 +303,51 @@ impl ReadXml for Word {
             Some("part-of-speech") => {
                 self.part_of_speech = None;
             }
+            Some("derived-from") => {
+                self.derivation = None;
+                state.rule_id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "rule")
+                    .map(|x| Uuid::parse_str(&x.1))
+                    .transpose()
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?;
+            }
+            Some("affix") => {
+                let kind = attrs
+                    .iter()
+                    .find(|&x| x.0 == "type")
+                    .map(|x| AffixKind::from(x.1.as_str()))
+                    .unwrap_or(AffixKind::Suffix);
+                self.affix = Some(Affix::new(kind));
+            }
+            Some("status") => {
+                self.status = WordStatus::default();
+            }
+            Some("replaced-by") => {
+                self.replaced_by = None;
+            }
+            Some("tags") | Some("fields") | Some("tag") | Some("media") | Some("asset") => {}
+            Some("field") => {
+                let field_id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "id")
+                    .map(|x| Uuid::parse_str(&x.1))
+                    .transpose()
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?
+                    .ok_or(XmlError::Other(Error::NoId))?;
+                self.fields.insert(field_id, String::new());
+                state.field_id = Some(field_id);
+            }
+            Some("allomorph") => {
+                let attr = |key: &str| attrs.iter().find(|&x| x.0 == key).map(|x| x.1.to_owned());
+                if let Some(affix) = self.affix.as_mut() {
+                    affix.allomorphs.push(Allomorph {
+                        form: String::new(),
+                        left: attr("left"),
+                        right: attr("right"),
+                    });
+                }
+            }
             _ => return Err(XmlError::InvalidTag(tag.unwrap_or_default().to_string())),
         }
         Ok(())
@@ -160,7 +356,7 @@ impl ReadXml for Word {
     fn process_text<R: std::io::BufRead>(
         &mut self,
         reader: &mut crate::xml::XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        state: &mut Self::ReaderState,
         text: String,
     ) -> Result<(), XmlError<Self::Error>> {
         let tag = reader.context.last().map(|s| s.as_str());
@@ -173,6 +369,36 @@ impl ReadXml for Word {
             Some("part-of-speech") => {
                 self.part_of_speech = Some(text.as_str().into());
             }
+            Some("derived-from") => {
+                let root_id = Uuid::parse_str(&text).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.derivation = state
+                    .rule_id
+                    .map(|rule_id| DerivationLink { root_id, rule_id });
+            }
+            Some("status") => {
+                self.status = text.trim().into();
+            }
+            Some("replaced-by") => {
+                let id = Uuid::parse_str(text.trim()).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.replaced_by = Some(id);
+            }
+            Some("tag") => {
+                self.add_tag(text);
+            }
+            Some("asset") => {
+                let id = Uuid::parse_str(text.trim()).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.add_media(id);
+            }
+            Some("field") => {
+                if let Some(value) = state.field_id.and_then(|id| self.fields.get_mut(&id)) {
+                    *value += &text;
+                }
+            }
+            Some("allomorph") => {
+                if let Some(allo) = self.affix.as_mut().and_then(|a| a.allomorphs.last_mut()) {
+                    allo.form += &text;
+                }
+            }
             _ => return Err(XmlError::InvalidTag(tag.unwrap_or_default().to_string())),
         }
         Ok(())
@@ -181,9 +407,14 @@ impl ReadXml for Word {
     fn process_tag_end<R: std::io::BufRead>(
         &mut self,
         _reader: &mut crate::xml::XmlReader<R>,
-        _state: &mut Self::ReaderState,
-        _name: String,
+        state: &mut Self::ReaderState,
+        name: String,
     ) -> Result<(), XmlError<Self::Error>> {
+        match name.as_str() {
+            "derived-from" => state.rule_id = None,
+            "field" => state.field_id = None,
+            _ => {}
+        }
         Ok(())
     }
 }
@@ -219,6 +450,74 @@ impl WriteXml for Word {
             w.write_tag_end("part-of-speech")?;
         }
 
+        if let Some(der) = &self.derivation {
+            w.write_tag_start_with_attributes(
+                "derived-from",
+                [("rule", der.rule_id.to_string().as_str())],
+            )?;
+            w.write_text(der.root_id.to_string().as_str())?;
+            w.write_tag_end("derived-from")?;
+        }
+
+        if let Some(affix) = &self.affix {
+            w.write_tag_start_with_attributes("affix", [("type", affix.kind.name())])?;
+            for allo in affix.allomorphs.iter() {
+                let mut attrs = Vec::new();
+                if let Some(left) = &allo.left {
+                    attrs.push(("left", left.as_str()));
+                }
+                if let Some(right) = &allo.right {
+                    attrs.push(("right", right.as_str()));
+                }
+                w.write_tag_start_with_attributes("allomorph", attrs)?;
+                w.write_text(&allo.form)?;
+                w.write_tag_end("allomorph")?;
+            }
+            w.write_tag_end("affix")?;
+        }
+
+        if self.status != WordStatus::default() {
+            w.write_tag_start("status")?;
+            w.write_text(self.status.name())?;
+            w.write_tag_end("status")?;
+        }
+
+        if let Some(id) = &self.replaced_by {
+            w.write_tag_start("replaced-by")?;
+            w.write_text(id.to_string().as_str())?;
+            w.write_tag_end("replaced-by")?;
+        }
+
+        if !self.tags.is_empty() {
+            w.write_tag_start("tags")?;
+            for tag in self.tags.iter() {
+                w.write_tag_start("tag")?;
+                w.write_text(tag)?;
+                w.write_tag_end("tag")?;
+            }
+            w.write_tag_end("tags")?;
+        }
+
+        if !self.fields.is_empty() {
+            w.write_tag_start("fields")?;
+            for (id, value) in self.fields.iter() {
+                w.write_tag_start_with_attributes("field", [("id", id.to_string().as_str())])?;
+                w.write_text(value)?;
+                w.write_tag_end("field")?;
+            }
+            w.write_tag_end("fields")?;
+        }
+
+        if !self.media.is_empty() {
+            w.write_tag_start("media")?;
+            for id in self.media.iter() {
+                w.write_tag_start("asset")?;
+                w.write_text(id.to_string().as_str())?;
+                w.write_tag_end("asset")?;
+            }
+            w.write_tag_end("media")?;
+        }
+
         w.write_tag_end("word")?;
 
         Ok(())
@@ -261,6 +560,11 @@ impl WordBuilder {
         self
     }
 
+    pub fn status(mut self, value: WordStatus) -> Self {
+        self.inner.set_status(value);
+        self
+    }
+
     pub fn build(self) -> Word {
         self.inner
     }
@@ -292,6 +596,13 @@ mod tests {
             pronunciation: IPA.to_string(),
             xsampa_pronunciation: Some(XSAMPA.to_string()),
             part_of_speech: Some(POS),
+            derivation: None,
+            affix: None,
+            tags: BTreeSet::new(),
+            fields: BTreeMap::new(),
+            status: WordStatus::default(),
+            replaced_by: None,
+            media: Vec::new(),
         }
     }
 
@@ -368,4 +679,121 @@ mod tests {
         let xml = word.save_xml_string().unwrap();
         assert_eq!(Word::load_xml_str(&xml).unwrap(), word);
     }
+
+    #[test]
+    fn affix_xml() {
+        let xml = format!(
+            r#"
+            <word id="{UUID}">
+                <romanization>-tu</romanization>
+                <pronunciation>tu</pronunciation>
+                <translation>PL</translation>
+                <part-of-speech>Affix</part-of-speech>
+                <affix type="Suffix">
+                    <allomorph left="V">tu</allomorph>
+                    <allomorph>u</allomorph>
+                </affix>
+            </word>
+            "#,
+        );
+        let word = Word::load_xml_str(&xml).unwrap();
+        let affix = word.affix().unwrap();
+        assert_eq!(affix.kind, AffixKind::Suffix);
+        assert_eq!(
+            affix.allomorphs,
+            vec![
+                Allomorph {
+                    form: "tu".to_string(),
+                    left: Some("V".to_string()),
+                    right: None,
+                },
+                Allomorph::new("u".to_string()),
+            ]
+        );
+
+        let xml2 = word.save_xml_string().unwrap();
+        assert_eq!(Word::load_xml_str(&xml2).unwrap(), word);
+    }
+
+    #[test]
+    fn tags_fields_xml() {
+        let field_id = Uuid::parse_str("3f1c9a52-7d8e-4b6a-9c1e-2a5b7d9e0f13").unwrap();
+        let xml = format!(
+            r#"
+            <word id="{UUID}">
+                <romanization>nifu</romanization>
+                <pronunciation>nifu</pronunciation>
+                <translation>sun</translation>
+                <tags>
+                    <tag>needs review</tag>
+                    <tag>archaic</tag>
+                </tags>
+                <fields>
+                    <field id="{field_id}">formal</field>
+                </fields>
+                <media>
+                    <asset>{field_id}</asset>
+                </media>
+            </word>
+            "#,
+        );
+        let word = Word::load_xml_str(&xml).unwrap();
+        assert_eq!(
+            word.tags().collect::<Vec<_>>(),
+            vec!["archaic", "needs review"]
+        );
+        assert!(word.has_tag("archaic"));
+        assert_eq!(word.field(field_id), Some("formal"));
+        assert_eq!(word.media(), [field_id]);
+
+        let xml2 = word.save_xml_string().unwrap();
+        assert_eq!(Word::load_xml_str(&xml2).unwrap(), word);
+    }
+
+    #[test]
+    fn fields_before_derivation_xml() {
+        let field_id = Uuid::parse_str("3f1c9a52-7d8e-4b6a-9c1e-2a5b7d9e0f13").unwrap();
+        let rule_id = Uuid::parse_str("7a0c4e1b-5d2f-4c8a-9b3e-6f1d2a4c8e05").unwrap();
+        let xml = format!(
+            r#"
+            <word id="{UUID}">
+                <fields>
+                    <field id="{field_id}">formal</field>
+                </fields>
+                <derived-from rule="{rule_id}">{UUID}</derived-from>
+                <fields>
+                    <field id="{rule_id}">archaic</field>
+                </fields>
+            </word>
+            "#,
+        );
+        let word = Word::load_xml_str(&xml).unwrap();
+        assert_eq!(word.field(field_id), Some("formal"));
+        assert_eq!(word.field(rule_id), Some("archaic"));
+        assert_eq!(word.derivation().map(|d| d.rule_id), Some(rule_id));
+    }
+
+    #[test]
+    fn status_xml() {
+        let replacement = Uuid::parse_str("3f1c9a52-7d8e-4b6a-9c1e-2a5b7d9e0f13").unwrap();
+        let xml = format!(
+            r#"
+            <word id="{UUID}">
+                <romanization>nifu</romanization>
+                <status>Deprecated</status>
+                <replaced-by>{replacement}</replaced-by>
+            </word>
+            "#,
+        );
+        let word = Word::load_xml_str(&xml).unwrap();
+        assert_eq!(word.status(), WordStatus::Deprecated);
+        assert_eq!(word.replaced_by(), Some(replacement));
+
+        let xml2 = word.save_xml_string().unwrap();
+        assert_eq!(Word::load_xml_str(&xml2).unwrap(), word);
+
+        let word = Word::load_xml_str(&format!(r#"<word id="{UUID}"></word>"#)).unwrap();
+        assert_eq!(word.status(), WordStatus::Approved);
+        assert!(!word.save_xml_string().unwrap().contains("status"));
+    }
 }
@@ -1,15 +1,21 @@
 //! A Library for constructed language management.
 
+pub use corpus::Corpus;
 pub use ipa::Ipa;
 pub use language::Language;
 pub use lexicon::{Dictionary, PartOfSpeech, Word, ALL_PARTS_OF_SPEECH};
 pub use phonology::Phoneme;
-pub use project::{Project, PROJECT_FILE_EXT, PROJECT_MIME_TYPE};
+pub use project::{Project, ProjectFormat, PROJECT_FILE_EXT, PROJECT_MIME_TYPE};
 pub use store::{IdAble, Store};
 
+pub mod analysis;
+pub mod corpus;
+pub mod interchange;
 pub mod ipa;
+pub mod json;
 pub mod language;
 pub mod lexicon;
+pub mod media;
 pub mod phonology;
 pub mod project;
 
@@ -18,6 +24,7 @@ pub mod utils;
 pub mod xml;
 
 pub mod prelude {
+    pub use crate::json::{ReadJson, WriteJson};
     pub use crate::xml::{ReadXml, WriteXml};
     pub use crate::IdAble;
     pub use std::io::{BufRead, Write};
@@ -0,0 +1,9 @@
+//! Module for media assets, such as recorded pronunciations and illustrations of words.
+
+pub use asset::{Asset, AssetKind};
+pub use assets::Media;
+pub use error::Error;
+
+mod asset;
+mod assets;
+mod error;
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    prelude::*,
+    xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter},
+};
+
+use super::Error;
+
+/// What an asset holds.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AssetKind {
+    /// A recording, such as of the pronunciation of a word.
+    Audio,
+    /// A picture, such as an illustration of a word.
+    Image,
+    #[default]
+    Other,
+}
+
+impl AssetKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Audio => "audio",
+            Self::Image => "image",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl From<&str> for AssetKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "audio" => Self::Audio,
+            "image" => Self::Image,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Known file extensions, with their kind and MIME type.
+const FILE_TYPES: &[(&str, AssetKind, &str)] = &[
+    ("flac", AssetKind::Audio, "audio/flac"),
+    ("m4a", AssetKind::Audio, "audio/mp4"),
+    ("mp3", AssetKind::Audio, "audio/mpeg"),
+    ("oga", AssetKind::Audio, "audio/ogg"),
+    ("ogg", AssetKind::Audio, "audio/ogg"),
+    ("opus", AssetKind::Audio, "audio/ogg"),
+    ("wav", AssetKind::Audio, "audio/wav"),
+    ("gif", AssetKind::Image, "image/gif"),
+    ("jpeg", AssetKind::Image, "image/jpeg"),
+    ("jpg", AssetKind::Image, "image/jpeg"),
+    ("png", AssetKind::Image, "image/png"),
+    ("svg", AssetKind::Image, "image/svg+xml"),
+    ("webp", AssetKind::Image, "image/webp"),
+];
+
+/// A media file stored in the project.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Asset {
+    /// The id of the asset.
+    id: Option<Uuid>,
+    /// The original file name, such as `"nifu.ogg"`.
+    name: String,
+    /// What the asset holds.
+    kind: AssetKind,
+    /// The MIME type of the data.
+    mime_type: String,
+    /// The contents of the file. Not part of the manifest.
+    #[serde(skip)]
+    data: Vec<u8>,
+}
+
+impl IdAble for Asset {
+    /// Gets the id of the asset.
+    fn id(&self) -> Option<Uuid> {
+        self.id
+    }
+
+    /// Generates new id for the asset, and then returns it.
+    fn generate_id(&mut self) -> Uuid {
+        let id = Uuid::new_v4();
+        self.id = Some(id);
+        id
+    }
+}
+
+impl Asset {
+    /// Creates a new asset from the contents of a file named `name`. The kind and the MIME type
+    /// are guessed from the extension of the name.
+    pub fn new(name: String, data: Vec<u8>) -> Self {
+        let (kind, mime_type) = FILE_TYPES
+            .iter()
+            .find(|(ext, _, _)| Some(*ext) == Self::extension_of(&name).as_deref())
+            .map(|(_, kind, mime)| (*kind, mime.to_string()))
+            .unwrap_or((AssetKind::Other, "application/octet-stream".to_string()));
+        Self {
+            id: None,
+            name,
+            kind,
+            mime_type,
+            data,
+        }
+    }
+
+    fn extension_of(name: &str) -> Option<String> {
+        let (_, ext) = name.rsplit_once('.')?;
+        Some(ext.to_lowercase())
+    }
+
+    /// Gets the original file name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets what the asset holds.
+    pub fn kind(&self) -> AssetKind {
+        self.kind
+    }
+
+    /// Gets the MIME type of the data.
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    /// Gets the contents of the file.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Sets the contents of the file.
+    pub fn set_data(&mut self, value: Vec<u8>) {
+        self.data = value;
+    }
+
+    /// Gets the path of the file in the project archive, such as `"media/<id>.ogg"`.
+    pub fn path(&self) -> String {
+        let mut ret = format!("media/{}", self.id.unwrap_or_default());
+        if let Some(ext) = Self::extension_of(&self.name) {
+            ret.push('.');
+            ret.push_str(&ext);
+        }
+        ret
+    }
+}
+
+impl ReadXml for Asset {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "asset";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        match reader.last_tag_pair() {
+            (_, Some(Self::TAG)) => {
+                let attr = |key: &str| attrs.iter().find(|&x| x.0 == key).map(|x| x.1.as_str());
+                let id = attr("id")
+                    .map(Uuid::parse_str)
+                    .unwrap_or_else(|| Ok(Uuid::new_v4()))
+                    .map_err(|e| XmlError::Other(Error::Id(e)))?;
+                self.id = Some(id);
+                self.kind = attr("kind").unwrap_or_default().into();
+                self.mime_type = attr("type").unwrap_or_default().to_string();
+            }
+            (Some(Self::TAG), Some("name")) => {
+                self.name.clear();
+            }
+            _ => return Err(XmlError::InvalidTag(name)),
+        }
+
+        Ok(())
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        if let Some("name") = reader.last_tag() {
+            self.name += &text;
+        }
+
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Asset {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        w: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        w.write_tag_start_with_attributes(
+            "asset",
+            [
+                ("id", self.id.unwrap_or_default().to_string().as_str()),
+                ("kind", self.kind.name()),
+                ("type", self.mime_type.as_str()),
+            ],
+        )?;
+
+        w.write_tag_start("name")?;
+        w.write_text(&self.name)?;
+        w.write_tag_end("name")?;
+
+        w.write_tag_end("asset")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guess_kind() {
+        let asset = Asset::new("Nifu.OGG".to_string(), vec![1, 2, 3]);
+        assert_eq!(asset.kind(), AssetKind::Audio);
+        assert_eq!(asset.mime_type(), "audio/ogg");
+        assert!(asset.path().ends_with(".ogg"));
+
+        let asset = Asset::new("sun.png".to_string(), Vec::new());
+        assert_eq!(asset.kind(), AssetKind::Image);
+
+        let asset = Asset::new("notes".to_string(), Vec::new());
+        assert_eq!(asset.kind(), AssetKind::Other);
+        assert_eq!(asset.path(), format!("media/{}", Uuid::nil()));
+    }
+
+    #[test]
+    fn read_write_xml() {
+        let mut asset = Asset::new("sun.png".to_string(), vec![0; 4]);
+        asset.generate_id();
+        let asset2 = Asset::load_xml_str(&asset.save_xml_string().unwrap()).unwrap();
+        assert_eq!(asset2.id(), asset.id());
+        assert_eq!(asset2.name(), "sun.png");
+        assert_eq!(asset2.kind(), AssetKind::Image);
+        assert_eq!(asset2.mime_type(), "image/png");
+        // The data is stored in its own file.
+        assert!(asset2.data().is_empty());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::Store;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
+
+use super::{Asset, Error};
+
+/// Collection of media assets. Written as the manifest of the `media/` directory of a project.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Media {
+    inner: Store<Asset>,
+}
+
+impl Media {
+    /// Creates a new collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an asset.
+    pub fn add_asset(&mut self, asset: Asset) -> Uuid {
+        self.inner.add(asset)
+    }
+
+    /// Removes an asset by id.
+    pub fn remove_asset_by_id(&mut self, id: Uuid) -> Option<Asset> {
+        self.inner.remove(id)
+    }
+
+    /// Gets the number of assets.
+    pub fn n_assets(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there is no asset.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Gets a reference to asset by id.
+    pub fn asset_by_id(&self, id: Uuid) -> Option<&Asset> {
+        self.inner.get(id)
+    }
+
+    /// Gets a mutable reference to asset by id.
+    pub fn asset_by_id_mut(&mut self, id: Uuid) -> Option<&mut Asset> {
+        self.inner.get_mut(id)
+    }
+
+    /// Iterates over assets.
+    pub fn iter_assets(&self) -> impl Iterator<Item = &Asset> {
+        self.inner.iter()
+    }
+
+    /// Iterates over assets.
+    pub fn iter_assets_mut(&mut self) -> impl Iterator<Item = &mut Asset> {
+        self.inner.iter_mut()
+    }
+
+    /// Iterates over asset ids.
+    pub fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.inner.ids()
+    }
+}
+
+impl ReadXml for Media {
+    type Error = Error;
+
+    type ReaderState = ();
+
+    const TAG: &'static str = "media";
+
+    fn process_tag_start<R: std::io::BufRead>(
+        &mut self,
+        reader: &mut XmlReader<R>,
+        state: &mut Self::ReaderState,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner
+            ._process_tag_start(Self::TAG, reader, state, name, attrs)
+    }
+
+    fn process_text<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _text: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+
+    fn process_tag_end<R: std::io::BufRead>(
+        &mut self,
+        _reader: &mut XmlReader<R>,
+        _state: &mut Self::ReaderState,
+        _name: String,
+    ) -> Result<(), XmlError<Self::Error>> {
+        Ok(())
+    }
+}
+
+impl WriteXml for Media {
+    type Error = Error;
+
+    fn serialize_xml<W: std::io::Write>(
+        &self,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError<Self::Error>> {
+        self.inner._serialize_xml("media", writer)
+    }
+}
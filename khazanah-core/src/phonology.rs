@@ -10,6 +10,9 @@ pub use categories::Categories;
 pub use category::Category;
 pub use error::Error;
 pub use inventory::Inventory;
-pub use pattern::{Pattern, PatternElement, PatternElements};
+pub use pattern::{
+    normalize_syllables, LearnedWeights, Pattern, PatternElement, PatternElements,
+    PATTERN_SYLLABLE_BOUNDARY, PATTERN_WORD_BOUNDARY, STRESS_MARKS,
+};
 pub use phoneme::{Phoneme, PhonemeBuilder};
 pub use phonotactic::{Phonotactic, StressIndexing, StressRule};
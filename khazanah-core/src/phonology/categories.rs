@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use crate::Store;
+use serde::{Deserialize, Serialize};
 
 use uuid::Uuid;
 
@@ -8,7 +9,8 @@ use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 use super::{Category, Error};
 
 /// Collections of categories
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Categories {
     inner: Store<Category>,
 }
@@ -102,6 +104,10 @@ impl Categories {
     }
 }
 
+impl ReadJson for Categories {}
+
+impl WriteJson for Categories {}
+
 impl ReadXml for Categories {
     type Error = Error;
 
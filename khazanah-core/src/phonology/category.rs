@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use crate::{prelude::*, Phoneme};
 use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
@@ -7,13 +10,15 @@ use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 use super::{Error, Inventory};
 
 /// A category of phonemes. Used in phonotactics and word generator.
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Category {
     /// The id of the category.
     id: Option<Uuid>,
     /// The name of the category.
     name: String,
     /// The id of the phonemes in the category.
+    #[serde(rename = "phonemes")]
     phonemes_id: Vec<Uuid>,
 }
 
@@ -124,6 +129,22 @@ impl Category {
             .choose(rng)
             .and_then(|&id| inventory.phoneme_by_id(id))
     }
+
+    /// Randomly chooses phoneme from the category, weighted by `weights` by phoneme id.
+    /// Phonemes without a weight are never chosen, unless none of the phonemes has a weight,
+    /// in which case the phoneme is chosen uniformly.
+    pub fn choose_phoneme_weighted<'a, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        inventory: &'a Inventory,
+        weights: &BTreeMap<Uuid, usize>,
+    ) -> Option<&'a Phoneme> {
+        self.phonemes_id
+            .choose_weighted(rng, |id| weights.get(id).copied().unwrap_or_default())
+            .or_else(|_| self.phonemes_id.choose(rng).ok_or(()))
+            .ok()
+            .and_then(|&id| inventory.phoneme_by_id(id))
+    }
 }
 
 impl ReadXml for Category {
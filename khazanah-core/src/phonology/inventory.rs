@@ -1,20 +1,25 @@
 use bimap::BiHashMap;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use uuid::Uuid;
 
+use crate::json::{ReadJson, WriteJson};
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 use crate::{utils, Phoneme, Store};
 
-use super::{Categories, Error};
+use super::{Categories, Error, PATTERN_SYLLABLE_BOUNDARY, STRESS_MARKS};
 
 /// An inventory of phonemes.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Inventory {
     // phonemes: HashMap<Uuid, Phoneme>,
     phonemes: Store<Phoneme>,
 
+    #[serde(skip)]
     romanization_pronunciation_map: RefCell<Option<BiHashMap<String, String>>>,
 
+    #[serde(skip)]
     pub(crate) is_inner: bool,
 }
 
@@ -132,6 +137,46 @@ impl Inventory {
         }
     }
 
+    /// Splits an IPA pronunciation into segments, preferring the longest phoneme of the
+    /// inventory at each position. Unknown characters become segments of their own, except
+    /// modifier letters and combining diacritics, which attach to the previous segment.
+    /// Whitespace, stress marks, and syllable boundaries are skipped.
+    pub fn segment_pronunciation(&self, pronunciation: &str) -> Vec<String> {
+        let sounds = self
+            .iter_phonemes()
+            .map(Phoneme::sound)
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut ret: Vec<String> = Vec::new();
+        let mut rest = pronunciation;
+        while let Some(c) = rest.chars().next() {
+            if c.is_whitespace() || STRESS_MARKS.contains(&c) || c == PATTERN_SYLLABLE_BOUNDARY {
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            if let Some(sound) = sounds
+                .iter()
+                .filter(|s| rest.starts_with(**s))
+                .max_by_key(|s| s.len())
+            {
+                ret.push(sound.to_string());
+                rest = &rest[sound.len()..];
+                continue;
+            }
+
+            let is_modifier = matches!(c, '\u{02b0}'..='\u{02ff}' | '\u{0300}'..='\u{036f}');
+            match ret.last_mut() {
+                Some(last) if is_modifier => last.push(c),
+                _ => ret.push(c.to_string()),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+
+        ret
+    }
+
     fn populate_romanization_pronunciation_map(&self) {
         let mut map = BiHashMap::new();
         for phoneme in self.iter_phonemes() {
@@ -153,6 +198,10 @@ impl Inventory {
     }
 }
 
+impl ReadJson for Inventory {}
+
+impl WriteJson for Inventory {}
+
 impl ReadXml for Inventory {
     type Error = Error;
 
@@ -304,4 +353,13 @@ mod tests {
             pronunciation_no_delimiter
         );
     }
+
+    #[test]
+    fn segment_pronunciation() {
+        let inv = Inventory::load_xml_str(XML1).unwrap();
+        assert_eq!(
+            inv.segment_pronunciation("ˈtʰə.maːɹ.t͡sɪx̃"),
+            vec!["tʰ", "ə", "m", "aː", "ɹ", "t͡s", "ɪ", "x̃"]
+        );
+    }
 }
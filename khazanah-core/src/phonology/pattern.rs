@@ -1,12 +1,57 @@
+use std::collections::BTreeMap;
 use std::str::Chars;
 
 use rand::Rng;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::{Categories, Category, Error, Inventory, Phoneme};
 
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 
+/// The word boundary symbol in search patterns.
+pub const PATTERN_WORD_BOUNDARY: char = '#';
+/// The syllable boundary symbol in search patterns and pronunciations.
+pub const PATTERN_SYLLABLE_BOUNDARY: char = '.';
+/// Stress marks, which are treated as syllable boundaries when searching pronunciations.
+pub const STRESS_MARKS: &[char] = &['ˈ', 'ˌ'];
+
+/// Normalizes a pronunciation for pattern search. Stress marks become syllable boundaries,
+/// and boundaries at the edges of the word are removed, so `"ˈta.ma"` becomes `"ta.ma"`.
+pub fn normalize_syllables(pronunciation: &str) -> String {
+    let mut ret = String::new();
+    for c in pronunciation.trim().chars() {
+        let c = match STRESS_MARKS.contains(&c) {
+            true => PATTERN_SYLLABLE_BOUNDARY,
+            false => c,
+        };
+        if c == PATTERN_SYLLABLE_BOUNDARY
+            && (ret.is_empty() || ret.ends_with(PATTERN_SYLLABLE_BOUNDARY))
+        {
+            continue;
+        }
+        ret.push(c);
+    }
+    ret.trim_end_matches(PATTERN_SYLLABLE_BOUNDARY).to_string()
+}
+
+/// Gets a regex string matching any phoneme of `category`.
+fn category_regex_pattern(category: &Category, inventory: &Inventory) -> String {
+    let mut ret = "(".to_string();
+    let mut first = true;
+    for p in category.iter_phonemes(inventory) {
+        if first {
+            first = false;
+        } else {
+            ret += "|"
+        }
+        ret += &regex::escape(p.sound());
+    }
+    ret += ")";
+    ret
+}
+
 /// An element of a pattern.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PatternElement<'a> {
@@ -80,18 +125,33 @@ impl<'a> Iterator for PatternElements<'a> {
     }
 }
 
+/// Weights of a pattern learned from the pronunciations of existing words.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearnedWeights {
+    /// The number of syllables using the pattern.
+    pub likeliness: usize,
+    /// The number of occurences of each phoneme in the categories of the pattern, by phoneme id.
+    pub phonemes: BTreeMap<Uuid, usize>,
+}
+
 /// A syllable pattern. The pattern string can contains IPA symbols and category names.
 ///
 /// For example: `"CrV"` means that the pattern consists of a consonant, followed by /r/,
 /// followed by a vowel, forming something like /kra/ or /tri/.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Pattern {
     /// The pattern string.
+    #[serde(rename = "pattern")]
     pub pattern_str: String,
     /// The mora or weight of the pattern.
     pub mora: u32,
-    /// The likeliness of this pattern to occur.
+    /// The likeliness of this pattern to occur. It is ignored while `learned` is set.
     pub likeliness: usize,
+    /// Weights learned from existing words. If set, they take precedence over `likeliness`.
+    #[serde(rename = "learned-weights", skip_serializing_if = "Option::is_none")]
+    pub learned: Option<LearnedWeights>,
 }
 
 impl Pattern {
@@ -101,6 +161,16 @@ impl Pattern {
             pattern_str: s,
             mora: 1,
             likeliness: 1,
+            learned: None,
+        }
+    }
+
+    /// Gets the weight of the pattern in generation, which is the learned likeliness if there is
+    /// any, or `likeliness` otherwise.
+    pub fn weight(&self) -> usize {
+        match &self.learned {
+            Some(learned) => learned.likeliness,
+            None => self.likeliness,
         }
     }
 
@@ -119,24 +189,64 @@ impl Pattern {
                     ret += s;
                 }
                 PatternElement::Category(c) => {
-                    ret += "(";
-                    let mut first = true;
-                    for p in c.iter_phonemes(inventory) {
-                        if first {
-                            first = false;
-                        } else {
-                            ret += "|"
-                        }
-                        ret += &regex::escape(p.sound());
+                    ret += &category_regex_pattern(c, inventory);
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Gets a regex string for searching pronunciations normalized by [`normalize_syllables`].
+    ///
+    /// Unlike [`Pattern::regex_pattern`], the pattern can match anywhere in the pronunciation.
+    /// `#` matches a word boundary and `.` matches a syllable boundary, including the edges of
+    /// the word. Other syllable boundaries in the pronunciation are ignored, so `"VNC"` matches
+    /// both /anta/ and /an.ta/.
+    pub fn search_regex_pattern(&self, categories: &Categories, inventory: &Inventory) -> String {
+        let mut units = Vec::new();
+        for elem in self.parse_elements(categories) {
+            match elem {
+                PatternElement::Str(s) => units.extend(s.chars().map(|c| match c {
+                    PATTERN_WORD_BOUNDARY | PATTERN_SYLLABLE_BOUNDARY => (c, None),
+                    c => (c, Some(regex::escape(&c.to_string()))),
+                })),
+                PatternElement::Category(c) => {
+                    units.push(('\0', Some(category_regex_pattern(c, inventory))));
+                }
+            }
+        }
+
+        let mut ret = String::new();
+        let n = units.len();
+        for (i, (c, unit)) in units.iter().enumerate() {
+            match unit {
+                Some(r) => {
+                    if i > 0 && units[i - 1].1.is_some() {
+                        ret += r"\.?";
                     }
-                    ret += ")";
+                    ret += r;
                 }
+                None if *c == PATTERN_SYLLABLE_BOUNDARY => ret += r"(?:^|\.|$)",
+                None if i == 0 => ret += "^",
+                None if i + 1 == n => ret += "$",
+                None => ret += r"\s",
             }
         }
 
         ret
     }
 
+    /// Gets a regular expression for searching pronunciations normalized by
+    /// [`normalize_syllables`]. See [`Pattern::search_regex_pattern`].
+    pub fn search_regex(
+        &self,
+        categories: &Categories,
+        inventory: &Inventory,
+    ) -> Result<Regex, Error> {
+        Regex::new(&self.search_regex_pattern(categories, inventory)).map_err(Error::from)
+    }
+
     /// Gets a regular expression for the pattern, using data in `categories` and `inventory`.
     pub fn regex(&self, categories: &Categories, inventory: &Inventory) -> Result<Regex, Error> {
         let r = format!("^{}$", self.regex_pattern(categories, inventory));
@@ -158,10 +268,13 @@ impl Pattern {
                     ret += s;
                 }
                 PatternElement::Category(c) => {
-                    ret += c
-                        .choose_phoneme(rng, inventory)
-                        .map(Phoneme::sound)
-                        .unwrap_or_default();
+                    let phoneme = match &self.learned {
+                        Some(learned) => {
+                            c.choose_phoneme_weighted(rng, inventory, &learned.phonemes)
+                        }
+                        None => c.choose_phoneme(rng, inventory),
+                    };
+                    ret += phoneme.map(Phoneme::sound).unwrap_or_default();
                 }
             }
         }
@@ -176,6 +289,7 @@ impl Default for Pattern {
             pattern_str: Default::default(),
             mora: 1,
             likeliness: 1,
+            learned: None,
         }
     }
 }
@@ -183,16 +297,17 @@ impl Default for Pattern {
 impl ReadXml for Pattern {
     type Error = Error;
 
-    type ReaderState = ();
+    /// The id of the phoneme whose learned weight is being read.
+    type ReaderState = Option<Uuid>;
 
     const TAG: &'static str = "pattern";
 
     fn process_tag_start<R: std::io::BufRead>(
         &mut self,
         reader: &mut XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        state: &mut Self::ReaderState,
         name: String,
-        _attrs: Vec<(String, String)>,
+        attrs: Vec<(String, String)>,
     ) -> Result<(), XmlError<Self::Error>> {
         match reader.last_tag_pair() {
             (_, Some(Self::TAG)) => {}
@@ -205,6 +320,25 @@ impl ReadXml for Pattern {
             (Some(Self::TAG), Some("likeliness")) => {
                 self.likeliness = 1;
             }
+            (Some(Self::TAG), Some("learned-weights")) => {
+                let likeliness = attrs
+                    .iter()
+                    .find(|&x| x.0 == "likeliness")
+                    .and_then(|x| x.1.parse().ok())
+                    .unwrap_or_default();
+                self.learned = Some(LearnedWeights {
+                    likeliness,
+                    ..Default::default()
+                });
+            }
+            (Some("learned-weights"), Some("phoneme")) => {
+                let id = attrs
+                    .iter()
+                    .find(|&x| x.0 == "id")
+                    .ok_or(XmlError::Other(Error::NoId))?;
+                let id = Uuid::parse_str(&id.1).map_err(|e| XmlError::Other(Error::Id(e)))?;
+                *state = Some(id);
+            }
             _ => return Err(XmlError::InvalidTag(name)),
         }
         Ok(())
@@ -213,7 +347,7 @@ impl ReadXml for Pattern {
     fn process_text<R: std::io::BufRead>(
         &mut self,
         reader: &mut XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        state: &mut Self::ReaderState,
         text: String,
     ) -> Result<(), XmlError<Self::Error>> {
         match reader.last_tag() {
@@ -226,6 +360,13 @@ impl ReadXml for Pattern {
             Some("likeliness") => {
                 self.likeliness = text.parse().unwrap_or(1);
             }
+            Some("phoneme") => {
+                if let (Some(id), Some(learned)) = (state.take(), self.learned.as_mut()) {
+                    learned
+                        .phonemes
+                        .insert(id, text.trim().parse().unwrap_or_default());
+                }
+            }
             _ => {}
         }
 
@@ -263,6 +404,22 @@ impl WriteXml for Pattern {
         writer.write_text(self.likeliness.to_string().as_str())?;
         writer.write_tag_end("likeliness")?;
 
+        if let Some(learned) = &self.learned {
+            writer.write_tag_start_with_attributes(
+                "learned-weights",
+                [("likeliness", learned.likeliness.to_string().as_str())],
+            )?;
+            for (id, n) in learned.phonemes.iter() {
+                writer.write_tag_start_with_attributes(
+                    "phoneme",
+                    [("id", id.to_string().as_str())],
+                )?;
+                writer.write_text(n.to_string().as_str())?;
+                writer.write_tag_end("phoneme")?;
+            }
+            writer.write_tag_end("learned-weights")?;
+        }
+
         writer.write_tag_end("pattern")?;
 
         Ok(())
@@ -300,6 +457,22 @@ mod tests {
         assert_eq!(&pat, &pat2);
     }
 
+    #[test]
+    fn learned_weights_xml() {
+        let mut pat = Pattern::load_xml_str(XML1).unwrap();
+        let id = Uuid::new_v4();
+        pat.learned = Some(LearnedWeights {
+            likeliness: 7,
+            phonemes: BTreeMap::from([(id, 4)]),
+        });
+        assert_eq!(pat.weight(), 7);
+
+        let xml2 = pat.save_xml_string().unwrap();
+        let pat2 = Pattern::load_xml_str(&xml2).unwrap();
+        assert_eq!(&pat, &pat2);
+        assert_eq!(pat2.learned.unwrap().phonemes.get(&id), Some(&4));
+    }
+
     #[test]
     fn parse_patterns() {
         let mut cats = Categories::new();
@@ -418,6 +591,28 @@ mod tests {
         assert!(!re.is_match("truna"));
     }
 
+    #[test]
+    fn search_regex() {
+        let (cats, inv) = test_data();
+        let search = |pat: &str, pron: &str| {
+            Pattern::new(pat.to_string())
+                .search_regex(&cats, &inv)
+                .unwrap()
+                .is_match(&normalize_syllables(pron))
+        };
+
+        assert_eq!(normalize_syllables("ˈta.ˌma..ni."), "ta.ma.ni");
+
+        assert!(search("#CVC", "ˈkan.ta"));
+        assert!(search("VnC", "ˈkan.ta"));
+        assert!(search("n.", "ˈkan.ta"));
+        assert!(!search("n.", "ˈka.na"));
+        assert!(search("n#", "ˈta.man"));
+        assert!(!search("#n", "ˈta.man"));
+        assert!(search("V.tV", "ˈa.ta.i"));
+        assert!(!search("V.tV", "ˈat.ta"));
+    }
+
     #[test]
     fn generate() {
         let (cats, inv) = test_data();
@@ -1,5 +1,6 @@
 use std::io::{BufRead, Write};
 
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
@@ -11,15 +12,18 @@ use crate::{
 use super::Error;
 
 /// A Phoneme.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
 pub struct Phoneme {
     /// The id of the phoneme.
     id: Option<Uuid>,
     /// The IPA sound of the phoneme.
     sound: String,
     /// The sound of the phoneme in X-SAMPA.
+    #[serde(skip_serializing_if = "Option::is_none")]
     xsampa_sound: Option<String>,
     /// The romanization of the phoneme.
+    #[serde(skip_serializing_if = "Option::is_none")]
     romanization: Option<String>,
     /// The mora length of the phoneme.
     mora: u32,
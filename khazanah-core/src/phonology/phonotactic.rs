@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
 use rand::{seq::SliceRandom, Rng};
 use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
 
-use super::{Categories, Error, Inventory, Pattern};
+use super::{Categories, Error, Inventory, LearnedWeights, Pattern};
 
+use crate::prelude::*;
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
 
 pub enum StressIndexing {
@@ -16,10 +20,14 @@ pub enum StressRule {
 }
 
 /// Rules that governs the formation of syllables.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Phonotactic {
+    #[serde(rename = "onset")]
     onset_patterns: Vec<Pattern>,
+    #[serde(rename = "nucleus")]
     nucleus_patterns: Vec<Pattern>,
+    #[serde(rename = "coda")]
     coda_patterns: Vec<Pattern>,
     // stress_rule: Option<StressRule>
 }
@@ -134,35 +142,152 @@ impl Phonotactic {
         RegexSet::new(ret).map_err(Error::from)
     }
 
-    /// Generates a random syllable following the rule.
+    /// Generates a random syllable following the rule. Patterns are chosen by their
+    /// [`Pattern::weight`].
     pub fn generate<R: Rng + ?Sized>(
         &self,
         rng: &mut R,
         categories: &Categories,
         inventory: &Inventory,
     ) -> String {
-        let onset = self
-            .onset_patterns
-            .choose(rng)
-            .map(|p| p.generate(rng, categories, inventory))
-            .unwrap_or_default();
-
-        let nucleus = self
-            .nucleus_patterns
-            .choose(rng)
-            .map(|p| p.generate(rng, categories, inventory))
-            .unwrap_or_default();
-
-        let coda = self
-            .coda_patterns
-            .choose(rng)
-            .map(|p| p.generate(rng, categories, inventory))
-            .unwrap_or_default();
+        let mut generate = |patterns: &[Pattern]| {
+            patterns
+                .choose_weighted(rng, Pattern::weight)
+                .or_else(|_| patterns.choose(rng).ok_or(()))
+                .map(|p| p.generate(rng, categories, inventory))
+                .unwrap_or_default()
+        };
+
+        let onset = generate(&self.onset_patterns);
+        let nucleus = generate(&self.nucleus_patterns);
+        let coda = generate(&self.coda_patterns);
 
         format!("{onset}{nucleus}{coda}")
     }
+
+    /// Learns the weights of the patterns from `syllables`, so generated syllables follow the
+    /// frequencies of patterns and phonemes in them. Each syllable is counted for the first
+    /// combination of onset, nucleus, and coda patterns matching it. Returns the number of
+    /// syllables matching the phonotactic.
+    pub fn learn_weights<'a>(
+        &mut self,
+        syllables: impl IntoIterator<Item = &'a str>,
+        categories: &Categories,
+        inventory: &Inventory,
+    ) -> Result<usize, Error> {
+        let compile = |patterns: &[Pattern]| {
+            patterns
+                .iter()
+                .map(|p| p.regex(categories, inventory))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let regexes = [
+            compile(&self.onset_patterns)?,
+            compile(&self.nucleus_patterns)?,
+            compile(&self.coda_patterns)?,
+        ];
+        let sounds = inventory
+            .iter_phonemes()
+            .filter_map(|p| Some((p.sound(), p.id()?)))
+            .collect::<HashMap<_, _>>();
+
+        let mut learned = regexes
+            .each_ref()
+            .map(|r| vec![LearnedWeights::default(); r.len()]);
+        let mut n_matched = 0;
+        for syllable in syllables {
+            let Some(matched) = Self::match_syllable(syllable, &regexes) else {
+                continue;
+            };
+            n_matched += 1;
+            for (i, (idx, part)) in matched.into_iter().enumerate() {
+                let weights = &mut learned[i][idx];
+                weights.likeliness += 1;
+                let Some(captures) = regexes[i][idx].captures(part) else {
+                    continue;
+                };
+                for c in captures.iter().skip(1).flatten() {
+                    if let Some(id) = sounds.get(c.as_str()) {
+                        *weights.phonemes.entry(*id).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        let [onset, nucleus, coda] = learned;
+        for (patterns, learned) in [
+            (&mut self.onset_patterns, onset),
+            (&mut self.nucleus_patterns, nucleus),
+            (&mut self.coda_patterns, coda),
+        ] {
+            for (pattern, weights) in patterns.iter_mut().zip(learned) {
+                pattern.learned = Some(weights);
+            }
+        }
+
+        Ok(n_matched)
+    }
+
+    /// Splits `syllable` into onset, nucleus, and coda matching the first possible combination
+    /// of patterns, and returns the index of the pattern matching each part, along with the part.
+    fn match_syllable<'a>(
+        syllable: &'a str,
+        regexes: &[Vec<Regex>; 3],
+    ) -> Option<[(usize, &'a str); 3]> {
+        let find = |i: usize, part: &str| regexes[i].iter().position(|r| r.is_match(part));
+        let bounds = syllable
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([syllable.len()])
+            .collect::<Vec<_>>();
+
+        for (a, &i) in bounds.iter().enumerate() {
+            for &j in bounds[a..].iter() {
+                let parts = [&syllable[..i], &syllable[i..j], &syllable[j..]];
+                if let (Some(o), Some(n), Some(c)) =
+                    (find(0, parts[0]), find(1, parts[1]), find(2, parts[2]))
+                {
+                    return Some([(o, parts[0]), (n, parts[1]), (c, parts[2])]);
+                }
+            }
+        }
+        None
+    }
+
+    /// Resets the weights to uniform, so all patterns are equally likely to be chosen, and so
+    /// are phonemes. The weights take precedence over the likeliness of patterns until they are
+    /// cleared with [`Phonotactic::clear_weights`].
+    pub fn reset_weights(&mut self) {
+        for pattern in self
+            .onset_patterns
+            .iter_mut()
+            .chain(self.nucleus_patterns.iter_mut())
+            .chain(self.coda_patterns.iter_mut())
+        {
+            pattern.learned = Some(LearnedWeights {
+                likeliness: 1,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Clears the learned or reset weights, so patterns are chosen by their likeliness again.
+    pub fn clear_weights(&mut self) {
+        for pattern in self
+            .onset_patterns
+            .iter_mut()
+            .chain(self.nucleus_patterns.iter_mut())
+            .chain(self.coda_patterns.iter_mut())
+        {
+            pattern.learned = None;
+        }
+    }
 }
 
+impl ReadJson for Phonotactic {}
+
+impl WriteJson for Phonotactic {}
+
 impl ReadXml for Phonotactic {
     type Error = Error;
 
@@ -411,4 +536,63 @@ mod tests {
             assert!(re.is_match(&s));
         }
     }
+
+    #[test]
+    fn learn_weights() {
+        let (cats, inv) = test_data();
+        let mut rng = rand::thread_rng();
+
+        let mut pt = Phonotactic {
+            onset_patterns: vec![Pattern::new("".to_string()), Pattern::new("C".to_string())],
+            nucleus_patterns: vec![Pattern::new("V".to_string())],
+            coda_patterns: vec![Pattern::new("".to_string()), Pattern::new("C".to_string())],
+        };
+
+        let n = pt
+            .learn_weights(["ta", "ka", "tat", "a", "xyz"], &cats, &inv)
+            .unwrap();
+        assert_eq!(n, 4);
+        assert_eq!(
+            pt.onset_patterns()
+                .iter()
+                .map(Pattern::weight)
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            pt.coda_patterns()
+                .iter()
+                .map(Pattern::weight)
+                .collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+
+        let re = Regex::new("^[tk]?a[t]?$").unwrap();
+        for _i in 0..20 {
+            assert!(re.is_match(&pt.generate(&mut rng, &cats, &inv)));
+        }
+
+        pt.onset_patterns_mut()[0].likeliness = 5;
+        pt.reset_weights();
+        assert!(pt
+            .onset_patterns()
+            .iter()
+            .chain(pt.coda_patterns())
+            .all(|p| p.weight() == 1));
+        assert!(pt.onset_patterns().iter().all(|p| p
+            .learned
+            .as_ref()
+            .unwrap()
+            .phonemes
+            .is_empty()));
+
+        pt.clear_weights();
+        assert!(pt
+            .onset_patterns()
+            .iter()
+            .chain(pt.nucleus_patterns())
+            .chain(pt.coda_patterns())
+            .all(|p| p.learned.is_none()));
+        assert_eq!(pt.onset_patterns()[0].weight(), 5);
+    }
 }
@@ -7,24 +7,56 @@
 //! ```txt
 //! *.zip
 //! |-mimetype
-//! |-lexicon.xml
+//! |-khazanah.xml
+//! |-corpus.xml
+//! |-history.xml
+//! |-media/
+//!   |-manifest.xml
+//!   |-<asset id>.<extension>
 //! ```
+//!
+//! `mimetype` holds [`PROJECT_MIME_TYPE`]. Like in OpenDocument files, it is the first entry and
+//! stored uncompressed, so that the type of the file can be told from its first bytes. Every
+//! entry is read whole when loading, so that a corrupted archive fails its checksums instead of
+//! being loaded partially. See [`Project::check`] to validate a project file.
+//!
+//! `corpus.xml` holds the example texts and `history.xml` holds the revision history of words.
+//! Both may be absent in older projects. `media/` holds the media assets attached to words,
+//! listed in `manifest.xml`, and is absent if there is none.
+//!
+//! `khazanah.xml` has the version of the format in the `version` attribute of its root element.
+//! Documents of older versions are upgraded when loaded, skipping elements that are no longer
+//! known, and documents of newer versions are refused. Unknown elements directly below the root
+//! of a current document are kept and written back when saved.
+//!
+//! A project stored as JSON has a single `khazanah.json` holding the language, the corpus, and
+//! the history instead of the XML files, including the media manifest. The media files are stored
+//! the same way. See [`crate::json`] for its schema.
 
+pub use check::Problem;
 pub use error::{ArchiveError, Error};
+pub use file::backup_path;
 
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     fs::File,
     io::{BufReader, Read, Seek, Write},
     path::Path,
 };
-use zip::{write::FileOptions, ZipArchive, ZipWriter};
+use zip::{result::ZipError, write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
 
 use crate::{
-    xml::{self, ReadXml, WriteXml},
-    Language,
+    json::{ReadJson, WriteJson},
+    lexicon::{self, History},
+    media::{Asset, Media},
+    xml::{self, ReadXml, WriteXml, XmlDiagnostic, XmlElement},
+    Corpus, Language, Word,
 };
 
+mod check;
 mod error;
+mod file;
+mod migration;
 
 // The MIME type of a project file.
 pub const PROJECT_MIME_TYPE: &str = "application/khz";
@@ -32,10 +64,48 @@ pub const PROJECT_MIME_TYPE: &str = "application/khz";
 // The file extension of a project file.
 pub const PROJECT_FILE_EXT: &str = "khz";
 
+/// The version of the project format.
+pub const PROJECT_VERSION: u32 = 3;
+
+/// The name of the entry holding the MIME type, which must be the first of the archive.
+const MIME_TYPE_FILE: &str = "mimetype";
+
+/// The name of the manifest of media assets.
+const MEDIA_MANIFEST_FILE: &str = "media/manifest.xml";
+
+/// The name of the JSON file in a project stored as JSON.
+pub const PROJECT_JSON_FILE: &str = "khazanah.json";
+
+/// How the data of a project is stored in the project file.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProjectFormat {
+    #[default]
+    Xml,
+    Json,
+}
+
+impl ProjectFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Xml => "XML",
+            Self::Json => "JSON",
+        }
+    }
+}
+
+pub const ALL_PROJECT_FORMATS: &[ProjectFormat] = &[ProjectFormat::Xml, ProjectFormat::Json];
+
 /// A project.
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct Project {
     language: Language,
+    corpus: Corpus,
+    history: History,
+    media: Media,
+    /// How the project is stored when saved.
+    format: ProjectFormat,
+    /// Unknown elements in `khazanah.xml`, kept to be written back.
+    extensions: Vec<XmlElement>,
 }
 
 impl Project {
@@ -52,23 +122,260 @@ impl Project {
         &mut self.language
     }
 
+    pub fn corpus(&self) -> &Corpus {
+        &self.corpus
+    }
+
+    pub fn corpus_mut(&mut self) -> &mut Corpus {
+        &mut self.corpus
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.history
+    }
+
+    pub fn media(&self) -> &Media {
+        &self.media
+    }
+
+    /// Adds the contents of a file named `name` as a media asset attached to the word of id
+    /// `word_id`. Returns the id of the asset, or `None` if there is no such word.
+    pub fn add_asset(
+        &mut self,
+        word_id: uuid::Uuid,
+        name: String,
+        data: Vec<u8>,
+    ) -> Option<uuid::Uuid> {
+        let word = self.language.dictionary_mut().word_by_id_mut(word_id)?;
+        let id = self.media.add_asset(Asset::new(name, data));
+        word.add_media(id);
+        Some(id)
+    }
+
+    /// Removes the media asset of id `id`, detaching it from every word.
+    pub fn remove_asset(&mut self, id: uuid::Uuid) -> Option<Asset> {
+        let asset = self.media.remove_asset_by_id(id)?;
+        for word in self.language.dictionary_mut().iter_words_mut() {
+            word.remove_media(id);
+        }
+        Some(asset)
+    }
+
+    /// Removes the word of id `id` and every word derived from it, directly or not, with their
+    /// corpus links, history and media assets. Returns the ids of the removed words, the word
+    /// of id `id` first, or nothing if there is no such word.
+    pub fn remove_word(&mut self, id: uuid::Uuid) -> Vec<uuid::Uuid> {
+        let dictionary = self.language.dictionary();
+        if dictionary.word_by_id(id).is_none() {
+            return Vec::new();
+        }
+        let mut ids = vec![id];
+        let mut i = 0;
+        while i < ids.len() {
+            for did in dictionary.derivative_ids(ids[i]) {
+                if !ids.contains(&did) {
+                    ids.push(did);
+                }
+            }
+            i += 1;
+        }
+
+        // Derivatives first, so that no word is left pointing to a removed root.
+        for &id in ids.iter().rev() {
+            let media = self
+                .language
+                .dictionary()
+                .word_by_id(id)
+                .map(|word| word.media().to_vec())
+                .unwrap_or_default();
+            for asset_id in media {
+                self.media.remove_asset_by_id(asset_id);
+            }
+            self.corpus.unlink_word(id);
+            self.history.remove_word(id);
+            self.language.dictionary_remove_word_by_id(id, true);
+        }
+        ids
+    }
+
+    /// Gets the contents of the media asset of id `id`.
+    pub fn asset_data(&self, id: uuid::Uuid) -> Option<&[u8]> {
+        self.media.asset_by_id(id).map(Asset::data)
+    }
+
+    /// Iterates over the media assets attached to the word of id `word_id`.
+    pub fn word_assets(&self, word_id: uuid::Uuid) -> impl Iterator<Item = &Asset> {
+        self.language
+            .dictionary()
+            .word_by_id(word_id)
+            .map(|word| word.media())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&id| self.media.asset_by_id(id))
+    }
+
+    /// Gets how the project is stored when saved.
+    pub fn format(&self) -> ProjectFormat {
+        self.format
+    }
+
+    /// Sets how the project is stored when saved.
+    pub fn set_format(&mut self, value: ProjectFormat) {
+        self.format = value;
+    }
+
+    /// Applies `f` to the word of id `id`, recording the changes in the history.
+    pub fn update_word<F, O>(&mut self, id: uuid::Uuid, f: F) -> Option<O>
+    where
+        F: FnOnce(&mut Word) -> O,
+    {
+        let word = self.language.dictionary_mut().word_by_id_mut(id)?;
+        let before = word.clone();
+        let ret = f(word);
+        self.history.record(&before, word, lexicon::now(), None);
+        Some(ret)
+    }
+
+    /// Restores the word of id `id` to the value before the revision at `index` of its history.
+    /// Returns `false` if there is no such word or revision.
+    pub fn restore_word_revision(&mut self, id: uuid::Uuid, index: usize) -> bool {
+        match self.language.dictionary_mut().word_by_id_mut(id) {
+            Some(word) => self.history.restore(word, index, lexicon::now()),
+            None => false,
+        }
+    }
+
     /// Loads project from ZIP archive.
     pub fn load<R: Read + Seek>(reader: R) -> Result<Self, ArchiveError> {
+        Self::load_archive(reader, false).map(|(proj, _)| proj)
+    }
+
+    /// Loads project from ZIP archive in recovery mode. Broken entries in the XML files are
+    /// skipped, and the problems found are returned with the partially loaded project.
+    pub fn recover<R: Read + Seek>(reader: R) -> Result<(Self, Vec<XmlDiagnostic>), ArchiveError> {
+        Self::load_archive(reader, true)
+    }
+
+    fn load_archive<R: Read + Seek>(
+        reader: R,
+        recover: bool,
+    ) -> Result<(Self, Vec<XmlDiagnostic>), ArchiveError> {
+        let mut diagnostics = Vec::new();
+
         // Initialize ZIP Archive
         let mut archive = ZipArchive::new(reader)?;
 
         // Check MIME type
-        {
-            let mut mimetype_file = archive.by_name("mimetype")?;
-            let mut mimetype = String::new();
-            mimetype_file.read_to_string(&mut mimetype)?;
-            if mimetype.trim() != PROJECT_MIME_TYPE {
-                return Err(ArchiveError::WrongMimeType);
+        let mimetype = read_entry(&mut archive, MIME_TYPE_FILE)?
+            .ok_or_else(|| ArchiveError::MissingEntry(MIME_TYPE_FILE.to_string()))?;
+        if !is_project_mime_type(&mimetype) {
+            return Err(ArchiveError::WrongMimeType);
+        }
+
+        // Load JSON file, if the project is stored as JSON
+        let json = match read_entry(&mut archive, PROJECT_JSON_FILE)? {
+            Some(data) => Some(Self::read_json(data.as_slice())?),
+            None => None,
+        };
+        let mut proj = match json {
+            Some(mut proj) => {
+                proj.format = ProjectFormat::Json;
+                proj
+            }
+            None => Self::load_xml_parts(&mut archive, recover, &mut diagnostics)?,
+        };
+
+        // Load media files
+        let ids = proj.media.ids().copied().collect::<Vec<_>>();
+        for id in ids {
+            let path = proj
+                .media
+                .asset_by_id(id)
+                .map(Asset::path)
+                .unwrap_or_default();
+            match read_entry(&mut archive, &path)? {
+                Some(data) => {
+                    if let Some(asset) = proj.media.asset_by_id_mut(id) {
+                        asset.set_data(data);
+                    }
+                }
+                None if recover => {
+                    diagnostics.push(XmlDiagnostic {
+                        file: MEDIA_MANIFEST_FILE.to_string(),
+                        message: format!("Missing media file {path}"),
+                        ..Default::default()
+                    });
+                    proj.remove_asset(id);
+                }
+                None => return Err(ArchiveError::MissingEntry(path)),
             }
         }
 
+        Ok((proj, diagnostics))
+    }
+
+    /// Loads the XML files of a project stored as XML.
+    fn load_xml_parts<R: Read + Seek>(
+        archive: &mut ZipArchive<R>,
+        recover: bool,
+        diagnostics: &mut Vec<XmlDiagnostic>,
+    ) -> Result<Self, ArchiveError> {
         // Loas XML file
-        let proj = Self::read_xml(BufReader::new(archive.by_name("khazanah.xml")?))?.0;
+        let data = read_entry(archive, "khazanah.xml")?
+            .ok_or_else(|| ArchiveError::MissingEntry("khazanah.xml".to_string()))?;
+        let version = document_version(data.as_slice())?;
+        // Elements unknown to older versions are skipped, in every file.
+        let skip_unknown = version < PROJECT_VERSION;
+        let mut proj: Self = read_part(
+            data.as_slice(),
+            "khazanah.xml",
+            recover,
+            skip_unknown,
+            diagnostics,
+        )?;
+
+        // Load corpus, if any
+        if let Some(data) = read_entry(archive, "corpus.xml")? {
+            proj.corpus = read_part(
+                data.as_slice(),
+                "corpus.xml",
+                recover,
+                skip_unknown,
+                diagnostics,
+            )
+            .map_err(|xe| xe.map_into())?;
+        }
+
+        // Load word history, if any
+        if let Some(data) = read_entry(archive, "history.xml")? {
+            proj.history = read_part(
+                data.as_slice(),
+                "history.xml",
+                recover,
+                skip_unknown,
+                diagnostics,
+            )
+            .map_err(|xe| xe.map_into())?;
+        }
+
+        // Load media manifest, if any
+        if let Some(data) = read_entry(archive, MEDIA_MANIFEST_FILE)? {
+            proj.media = read_part(
+                data.as_slice(),
+                MEDIA_MANIFEST_FILE,
+                recover,
+                skip_unknown,
+                diagnostics,
+            )
+            .map_err(|xe| xe.map_into())?;
+        }
+
+        // Migrate once every part is loaded, as migrations may touch any of them.
+        migration::migrate(&mut proj, version).map_err(xml::XmlError::Other)?;
 
         Ok(proj)
     }
@@ -80,42 +387,176 @@ impl Project {
         Ok(proj)
     }
 
+    /// Loads project from ZIP file in filesystem in recovery mode.
+    pub fn recover_file<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Self, Vec<XmlDiagnostic>), ArchiveError> {
+        let file = File::open(&path)?;
+        Self::recover(file)
+    }
+
     /// Saves project to ZIP archive.
     pub fn save<W: Write + Seek>(&self, writer: W) -> Result<W, ArchiveError> {
         let mut archive = ZipWriter::new(writer);
 
         let options = FileOptions::default();
 
-        // Save MIME type
-        archive.start_file("mimetype", options)?;
+        // Save MIME type first and uncompressed, so that it can be found at a fixed offset.
+        archive.start_file(
+            MIME_TYPE_FILE,
+            FileOptions::default().compression_method(CompressionMethod::Stored),
+        )?;
         archive.write_all(PROJECT_MIME_TYPE.as_bytes())?;
 
+        if self.format == ProjectFormat::Json {
+            archive.start_file(PROJECT_JSON_FILE, options)?;
+            archive = self.write_json(archive)?;
+            return self.save_media(archive, false);
+        }
+
         // Save XML file
         archive.start_file("khazanah.xml", options)?;
         archive = self.write_xml(archive)?;
 
+        // Save corpus
+        archive.start_file("corpus.xml", options)?;
+        archive = self
+            .corpus
+            .write_xml(archive)
+            .map_err(|xe| xe.map_into::<Error>())?;
+
+        // Save word history
+        archive.start_file("history.xml", options)?;
+        archive = self
+            .history
+            .write_xml(archive)
+            .map_err(|xe| xe.map_into::<Error>())?;
+
+        self.save_media(archive, true)
+    }
+
+    /// Saves media files, and their manifest if `manifest` is `true`, then finishes `archive`.
+    fn save_media<W: Write + Seek>(
+        &self,
+        mut archive: ZipWriter<W>,
+        manifest: bool,
+    ) -> Result<W, ArchiveError> {
+        if self.media.is_empty() {
+            return Ok(archive.finish()?);
+        }
+
+        if manifest {
+            archive.start_file(MEDIA_MANIFEST_FILE, FileOptions::default())?;
+            archive = self
+                .media
+                .write_xml(archive)
+                .map_err(|xe| xe.map_into::<Error>())?;
+        }
+
+        // Media files are compressed already.
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        for asset in self.media.iter_assets() {
+            archive.start_file(asset.path(), options)?;
+            archive.write_all(asset.data())?;
+        }
+
         Ok(archive.finish()?)
     }
 
-    /// Saves project to ZIP archive in filesystem.
+    /// Saves project to ZIP archive in filesystem. The archive is written to a temporary file
+    /// first, and then moved over `path`, so a crash never leaves a partially written project.
     pub fn save_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ArchiveError> {
-        let file = File::create(&path)?;
-        self.save(file)?;
-        Ok(())
+        self.save_file_with_backups(path, 0)
+    }
+
+    /// Saves project to ZIP archive in filesystem like [`Project::save_file`], keeping up to
+    /// `backups` previous versions of the file. See [`backup_path`] for their names.
+    pub fn save_file_with_backups<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        backups: usize,
+    ) -> Result<(), ArchiveError> {
+        file::write_atomic(path.as_ref(), backups, |file| self.save(file))
     }
 }
 
+/// Checks that `data`, the contents of the `mimetype` entry, is the MIME type of projects.
+/// Surrounding whitespace, such as a trailing newline written by other tools, is ignored.
+fn is_project_mime_type(data: &[u8]) -> bool {
+    String::from_utf8_lossy(data).trim() == PROJECT_MIME_TYPE
+}
+
+/// Reads the whole entry of the archive named `name`, which verifies its checksum. Returns `None`
+/// if there is no such entry.
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, ArchiveError> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|e| ArchiveError::Corrupted(name.to_string(), e))?;
+    Ok(Some(data))
+}
+
+/// Reads a file of the archive named `name`, collecting diagnostics if `recover` is `true`.
+/// Unknown elements are skipped if `skip_unknown` is `true`.
+fn read_part<T: ReadXml, R: Read>(
+    file: R,
+    name: &str,
+    recover: bool,
+    skip_unknown: bool,
+    diagnostics: &mut Vec<XmlDiagnostic>,
+) -> Result<T, xml::XmlError<T::Error>> {
+    let mut reader = xml::XmlReader::new(BufReader::new(file));
+    reader.recover = recover;
+    reader.skip_unknown = skip_unknown;
+    let ret = T::deserialize_xml(&mut reader, None)?;
+    diagnostics.extend(reader.diagnostics.into_iter().map(|d| XmlDiagnostic {
+        file: name.to_string(),
+        ..d
+    }));
+    Ok(ret)
+}
+
+/// Gets the format version from the attributes of the root element of a project document.
+/// Documents before versioning are version 1.
+fn parse_version(attrs: &[(String, String)]) -> Result<u32, Error> {
+    match attrs.iter().find(|(k, _)| k == "version") {
+        Some((_, v)) => v
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidVersion(v.clone())),
+        None => Ok(1),
+    }
+}
+
+/// Gets the format version of the project document `file`.
+fn document_version<R: Read>(file: R) -> Result<u32, xml::XmlError<Error>> {
+    let mut reader = xml::XmlReader::new(BufReader::new(file));
+    let attrs = reader
+        .read_root_start()?
+        .map(|(_, attrs)| attrs)
+        .unwrap_or_default();
+    parse_version(&attrs).map_err(xml::XmlError::Other)
+}
+
 impl ReadXml for Project {
     type Error = Error;
 
-    type ReaderState = ();
+    /// The version of the document.
+    type ReaderState = u32;
 
     const TAG: &'static str = "khazanah";
 
     fn process_tag_start<R: std::io::BufRead>(
         &mut self,
         reader: &mut xml::XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        version: &mut Self::ReaderState,
         name: String,
         attrs: Vec<(String, String)>,
     ) -> Result<(), xml::XmlError<Self::Error>> {
@@ -126,11 +567,18 @@ impl ReadXml for Project {
         };
 
         match (ptag, name.as_str()) {
-            (_, "khazanah") => {}
+            (None, "khazanah") => {
+                *version = parse_version(&attrs).map_err(xml::XmlError::Other)?;
+                migration::check_version(*version).map_err(xml::XmlError::Other)?;
+                reader.skip_unknown = *version < PROJECT_VERSION;
+            }
             (Some("khazanah"), "language") => {
                 self.language = Language::deserialize_xml(reader, Some((name, attrs)))
                     .map_err(|xe| xe.map_into())?;
             }
+            (Some("khazanah"), _) if *version >= PROJECT_VERSION => {
+                self.extensions.push(reader.read_element(name, attrs)?);
+            }
             _ => return Err(xml::XmlError::InvalidTag(name)),
         }
 
@@ -149,9 +597,10 @@ impl ReadXml for Project {
     fn process_tag_end<R: std::io::BufRead>(
         &mut self,
         _reader: &mut xml::XmlReader<R>,
-        _state: &mut Self::ReaderState,
+        _version: &mut Self::ReaderState,
         _name: String,
     ) -> Result<(), xml::XmlError<Self::Error>> {
+        // Migrations run once the whole archive is loaded.
         Ok(())
     }
 }
@@ -163,12 +612,421 @@ impl WriteXml for Project {
         &self,
         writer: &mut xml::XmlWriter<W>,
     ) -> Result<(), xml::XmlError<Self::Error>> {
-        writer.write_tag_start_with_attributes("khazanah", [("version", "1")])?;
+        writer.write_tag_start_with_attributes(
+            "khazanah",
+            [("version", PROJECT_VERSION.to_string().as_str())],
+        )?;
         self.language
             .serialize_xml(writer)
             .map_err(|xe| xe.map_into())?;
+        for element in self.extensions.iter() {
+            element.write(writer)?;
+        }
         writer.write_tag_end("khazanah")?;
 
         Ok(())
     }
 }
+
+/// Serializes the project as a JSON object with its format version.
+impl Serialize for Project {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Project", 5)?;
+        state.serialize_field("version", &PROJECT_VERSION)?;
+        state.serialize_field("language", &self.language)?;
+        state.serialize_field("corpus", &self.corpus)?;
+        state.serialize_field("history", &self.history)?;
+        if self.media.is_empty() {
+            state.skip_field("media")?;
+        } else {
+            state.serialize_field("media", &self.media)?;
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Project {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(default)]
+        struct ProjectJson {
+            version: u32,
+            language: Language,
+            corpus: Corpus,
+            history: History,
+            media: Media,
+        }
+
+        impl Default for ProjectJson {
+            fn default() -> Self {
+                Self {
+                    version: PROJECT_VERSION,
+                    language: Language::default(),
+                    corpus: Corpus::default(),
+                    history: History::default(),
+                    media: Media::default(),
+                }
+            }
+        }
+
+        let json = ProjectJson::deserialize(deserializer)?;
+        let mut project = Self {
+            language: json.language,
+            corpus: json.corpus,
+            history: json.history,
+            media: json.media,
+            format: ProjectFormat::Json,
+            extensions: Vec::new(),
+        };
+        migration::migrate(&mut project, json.version).map_err(serde::de::Error::custom)?;
+        Ok(project)
+    }
+}
+
+impl ReadJson for Project {}
+
+impl WriteJson for Project {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuse_newer_version() {
+        let res = Project::load_xml_str(r#"<khazanah version="99"><language/></khazanah>"#);
+        assert!(matches!(
+            res,
+            Err(xml::XmlError::Other(Error::UnsupportedVersion(99)))
+        ));
+        let res = Project::load_xml_str(r#"<khazanah version="two"/>"#);
+        assert!(matches!(
+            res,
+            Err(xml::XmlError::Other(Error::InvalidVersion(_)))
+        ));
+    }
+
+    #[test]
+    fn skip_unknown_in_older_version() {
+        let s = r#"
+            <khazanah version="1">
+              <language>
+                <meta><name>Nifuan</name><obsolete>x</obsolete></meta>
+              </language>
+              <settings><theme>dark</theme></settings>
+            </khazanah>
+        "#;
+        let project = Project::load_xml_str(s).unwrap();
+        assert_eq!(project.language().meta().name, "Nifuan");
+        assert!(project.extensions.is_empty());
+
+        // Without a version, the document is as old as version 1.
+        assert!(Project::load_xml_str(&s.replace(r#" version="1""#, "")).is_ok());
+    }
+
+    #[test]
+    fn keep_unknown_in_current_version() {
+        let s = format!(
+            r#"<khazanah version="{PROJECT_VERSION}"><language/><plugin id="a"><data>x &amp; y</data></plugin></khazanah>"#
+        );
+        let project = Project::load_xml_str(&s).unwrap();
+        assert_eq!(project.extensions.len(), 1);
+        assert_eq!(project.extensions[0].name, "plugin");
+
+        let project2 = Project::load_xml_str(&project.save_xml_string().unwrap()).unwrap();
+        assert_eq!(project2.extensions, project.extensions);
+
+        // Unknown elements deeper in a current document are still errors.
+        let s = format!(
+            r#"<khazanah version="{PROJECT_VERSION}"><language><foo/></language></khazanah>"#
+        );
+        assert!(Project::load_xml_str(&s).is_err());
+    }
+
+    #[test]
+    fn recover_broken_entries() {
+        let s = format!(
+            r#"<khazanah version="{PROJECT_VERSION}">
+              <language>
+                <meta><name>Nifuan</name></meta>
+                <lexicon><dictionary>
+                  <word id="not-a-uuid"><romanization>bad</romanization></word>
+                  <word id="5ce2f1b7-527f-4779-9c96-71939cb397af">
+                    <romanization>good</romanization>
+                    <foo>bar</foo>
+                  </word>
+                </dictionary></lexicon>
+              </language>
+            </khazanah>"#
+        );
+        assert!(Project::load_xml_str(&s).is_err());
+
+        let (project, diagnostics) = Project::recover_xml(s.as_bytes()).unwrap();
+        assert_eq!(project.language().meta().name, "Nifuan");
+        let words = project
+            .language()
+            .dictionary()
+            .iter_words()
+            .collect::<Vec<_>>();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].romanization(), "good");
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, 5);
+        assert_eq!(
+            diagnostics[0].path,
+            "khazanah/language/lexicon/dictionary/word"
+        );
+        assert_eq!(diagnostics[1].line, 8);
+        assert_eq!(
+            diagnostics[1].path,
+            "khazanah/language/lexicon/dictionary/word/foo"
+        );
+
+        // Reading stops at a syntax error, keeping what was read before.
+        let (project, diagnostics) = Project::recover_xml(
+            r#"<khazanah version="2"><language><meta><name>Nifuan</name></meta><lexicon><dictionary><word"#
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(project.language().meta().name, "Nifuan");
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    const KEPT_ID: &str = "5ce2f1b7-527f-4779-9c96-71939cb397af";
+    const DELETED_ID: &str = "0f9e7e3c-3b5d-4a54-8cf5-2f4c1b0d9a11";
+
+    /// Builds an archive of version `version` with unknown elements in every file, and a
+    /// corpus link and a history of a word no longer in the dictionary.
+    fn fixture(version: u32) -> Vec<u8> {
+        let files = [
+            (
+                "khazanah.xml",
+                format!(
+                    r#"<khazanah version="{version}">
+                      <language>
+                        <meta><name>Nifuan</name></meta>
+                        <lexicon><dictionary>
+                          <word id="{KEPT_ID}"><romanization>nifu</romanization></word>
+                        </dictionary></lexicon>
+                      </language>
+                      <settings/>
+                    </khazanah>"#
+                ),
+            ),
+            (
+                "corpus.xml",
+                format!(
+                    r#"<corpus>
+                      <text id="6f1d3c52-9a0e-4d7b-8b8e-3c1f2a4d5e60">
+                        <title>The Sun</title>
+                        <sentence>
+                          <source>nifu tu</source>
+                          <links><link>{KEPT_ID}</link><link>{DELETED_ID}</link></links>
+                          <note>old</note>
+                        </sentence>
+                      </text>
+                    </corpus>"#
+                ),
+            ),
+            (
+                "history.xml",
+                [KEPT_ID, DELETED_ID]
+                    .map(|id| {
+                        format!(
+                            r#"<word id="{id}"><revision timestamp="1" field="romanization"><old-value>nif</old-value><new-value>nifu</new-value><author>x</author></revision></word>"#
+                        )
+                    })
+                    .concat(),
+            ),
+        ];
+
+        let mut archive = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        archive
+            .start_file(
+                MIME_TYPE_FILE,
+                FileOptions::default().compression_method(CompressionMethod::Stored),
+            )
+            .unwrap();
+        archive.write_all(PROJECT_MIME_TYPE.as_bytes()).unwrap();
+        for (name, content) in files {
+            archive.start_file(name, FileOptions::default()).unwrap();
+            let content = match name {
+                "history.xml" => format!("<history>{content}</history>"),
+                _ => content,
+            };
+            archive.write_all(content.as_bytes()).unwrap();
+        }
+        archive.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn migrate_older_archives() {
+        use std::io::Cursor;
+
+        let kept_id = uuid::Uuid::parse_str(KEPT_ID).unwrap();
+        let deleted_id = uuid::Uuid::parse_str(DELETED_ID).unwrap();
+        for version in 1..PROJECT_VERSION {
+            let project = Project::load(Cursor::new(fixture(version))).unwrap();
+            assert_eq!(project.language().meta().name, "Nifuan");
+            assert!(project.extensions.is_empty());
+
+            let text = project.corpus().iter_texts().next().unwrap();
+            assert_eq!(text.title(), "The Sun");
+            assert_eq!(
+                text.sentences()[0].token_links(),
+                [Some(kept_id), None],
+                "version {version}"
+            );
+            assert_eq!(project.history().revisions_of(kept_id).len(), 1);
+            assert!(project.history().revisions_of(deleted_id).is_empty());
+
+            // Saved with the current version, the project reads back the same.
+            let data = project.save(Cursor::new(Vec::new())).unwrap().into_inner();
+            let loaded = Project::load(Cursor::new(data)).unwrap();
+            assert_eq!(loaded.corpus(), project.corpus());
+            assert_eq!(loaded.history(), project.history());
+        }
+
+        // Unknown elements are errors in a current archive.
+        assert!(Project::load(Cursor::new(fixture(PROJECT_VERSION))).is_err());
+    }
+
+    #[test]
+    fn media_round_trip() {
+        use std::io::Cursor;
+
+        let mut project = Project::new();
+        let word_id = project
+            .language_mut()
+            .dictionary_mut()
+            .add_word(Word::new());
+        let audio_id = project
+            .add_asset(word_id, "nifu.ogg".to_string(), vec![1, 2, 3])
+            .unwrap();
+        let image_id = project
+            .add_asset(word_id, "sun.png".to_string(), vec![4, 5])
+            .unwrap();
+        assert!(project
+            .add_asset(uuid::Uuid::new_v4(), "x.wav".to_string(), Vec::new())
+            .is_none());
+
+        for format in ALL_PROJECT_FORMATS {
+            project.set_format(*format);
+            let data = project.save(Cursor::new(Vec::new())).unwrap().into_inner();
+            let loaded = Project::load(Cursor::new(data)).unwrap();
+            assert_eq!(loaded.media(), project.media());
+            assert_eq!(loaded.asset_data(audio_id), Some([1, 2, 3].as_slice()));
+            let names = loaded
+                .word_assets(word_id)
+                .map(|a| a.name())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["nifu.ogg", "sun.png"]);
+        }
+
+        assert!(project.remove_asset(image_id).is_some());
+        assert_eq!(
+            project
+                .language()
+                .dictionary()
+                .word_by_id(word_id)
+                .unwrap()
+                .media(),
+            [audio_id]
+        );
+    }
+
+    #[test]
+    fn remove_word_and_derivatives() {
+        use crate::corpus::{Sentence, Text};
+        use crate::lexicon::DerivationLink;
+
+        let mut project = Project::new();
+        let mut word_ids = Vec::new();
+        for romanization in ["nifu", "nifutu", "nifututu", "xahla"] {
+            let id = project
+                .language_mut()
+                .dictionary_mut()
+                .add_word(Word::new());
+            project.update_word(id, |word| word.set_romanization(romanization.to_string()));
+            project
+                .add_asset(id, format!("{romanization}.ogg"), vec![1])
+                .unwrap();
+            word_ids.push(id);
+        }
+        let [root_id, derived_id, derived2_id, other_id] = word_ids[..] else {
+            unreachable!();
+        };
+        let rule_id = uuid::Uuid::new_v4();
+        for (id, root_id) in [(derived_id, root_id), (derived2_id, derived_id)] {
+            project
+                .language_mut()
+                .dictionary_mut()
+                .word_by_id_mut(id)
+                .unwrap()
+                .set_derivation(Some(DerivationLink { root_id, rule_id }));
+        }
+
+        let mut sentence = Sentence::new();
+        sentence.set_source("nifu nifutu nifututu xahla".to_string());
+        sentence.set_links(word_ids.iter().map(|&id| Some(id)));
+        let mut text = Text::new();
+        text.add_sentence(sentence);
+        project.corpus_mut().add_text(text);
+
+        assert_eq!(
+            project.remove_word(root_id),
+            [root_id, derived_id, derived2_id]
+        );
+        assert!(project.remove_word(root_id).is_empty());
+
+        let dictionary = project.language().dictionary();
+        assert_eq!(dictionary.iter_words().count(), 1);
+        assert!(dictionary.word_by_id(other_id).is_some());
+        let text = project.corpus().iter_texts().next().unwrap();
+        assert_eq!(
+            text.sentences()[0].token_links(),
+            [None, None, None, Some(other_id)]
+        );
+        assert_eq!(project.history().word_ids().collect::<Vec<_>>(), [other_id]);
+        let names = project
+            .media()
+            .ids()
+            .filter_map(|&id| project.media().asset_by_id(id))
+            .map(|asset| asset.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["xahla.ogg"]);
+    }
+
+    #[test]
+    fn save_file_with_backups() {
+        let dir = std::env::temp_dir().join(format!("khazanah-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let path = dir.join("test.khz");
+
+        let mut project = Project::new();
+        for name in ["a", "b", "c", "d"] {
+            project.language_mut().meta_mut().name = name.to_string();
+            project.save_file_with_backups(&path, 2).unwrap();
+        }
+        let name = |path| {
+            Project::load_file(path)
+                .unwrap()
+                .language()
+                .meta()
+                .name
+                .clone()
+        };
+        assert_eq!(name(path.clone()), "d");
+        assert_eq!(name(backup_path(&path, 1)), "c");
+        assert_eq!(name(backup_path(&path, 2)), "b");
+
+        let mut files = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["test.khz", "test.khz~", "test.khz~2"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
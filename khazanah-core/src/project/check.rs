@@ -0,0 +1,205 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek},
+    path::Path,
+};
+
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::xml::XmlDiagnostic;
+
+use super::{
+    is_project_mime_type, ArchiveError, Project, MIME_TYPE_FILE, PROJECT_JSON_FILE,
+    PROJECT_MIME_TYPE,
+};
+
+/// A problem found when checking a project file with [`Project::check`].
+#[derive(Debug, thiserror::Error)]
+pub enum Problem {
+    /// The archive has no `mimetype` entry.
+    #[error("The archive has no mimetype entry")]
+    MissingMimeType,
+    /// The `mimetype` entry is not the first entry of the archive.
+    #[error("The mimetype entry is not the first entry of the archive")]
+    MimeTypeNotFirst,
+    /// The `mimetype` entry is compressed.
+    #[error("The mimetype entry is compressed")]
+    MimeTypeCompressed,
+    /// The `mimetype` entry holds another MIME type.
+    #[error("Wrong MIME type \"{0}\", expected \"{PROJECT_MIME_TYPE}\"")]
+    WrongMimeType(String),
+    /// The archive has no language data.
+    #[error("The archive has neither khazanah.xml nor {PROJECT_JSON_FILE}")]
+    MissingProject,
+    /// An entry cannot be read, or does not match its checksum.
+    #[error("Entry {0} is corrupted: {1}")]
+    Corrupted(String, io::Error),
+    /// A part of the project is broken, and would be skipped by [`Project::recover`].
+    #[error("{0}")]
+    Damaged(XmlDiagnostic),
+    /// The project cannot be loaded.
+    #[error("Unable to load the project: {0}")]
+    Unreadable(ArchiveError),
+}
+
+impl Project {
+    /// Checks that a project archive is well-formed: `mimetype` is the first entry, stored
+    /// uncompressed, and holds the right MIME type, the required entries are present, every entry
+    /// matches its checksum, and the project loads without problems. Returns the problems found,
+    /// or an error if the data is not a ZIP archive at all.
+    pub fn check<R: Read + Seek>(reader: R) -> Result<Vec<Problem>, ArchiveError> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut problems = Vec::new();
+
+        // Check layout of MIME type
+        let names = archive.file_names().map(String::from).collect::<Vec<_>>();
+        if !names.iter().any(|name| name == MIME_TYPE_FILE) {
+            problems.push(Problem::MissingMimeType);
+        } else {
+            if archive.by_index(0)?.name() != MIME_TYPE_FILE {
+                problems.push(Problem::MimeTypeNotFirst);
+            }
+            if archive.by_name(MIME_TYPE_FILE)?.compression() != CompressionMethod::Stored {
+                problems.push(Problem::MimeTypeCompressed);
+            }
+        }
+
+        // Check required entries
+        if !names
+            .iter()
+            .any(|name| name == "khazanah.xml" || name == PROJECT_JSON_FILE)
+        {
+            problems.push(Problem::MissingProject);
+        }
+
+        // Check entries against their checksums
+        let mut corrupted = false;
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let mut data = Vec::new();
+            match file.read_to_end(&mut data) {
+                Ok(_) if file.name() == MIME_TYPE_FILE => {
+                    if !is_project_mime_type(&data) {
+                        let mimetype = String::from_utf8_lossy(&data).to_string();
+                        problems.push(Problem::WrongMimeType(mimetype));
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    corrupted = true;
+                    problems.push(Problem::Corrupted(file.name().to_string(), e));
+                }
+            }
+        }
+
+        // Check contents, unless loading would only fail on the problems above
+        if !corrupted
+            && !problems
+                .iter()
+                .any(|p| matches!(p, Problem::MissingProject))
+        {
+            match Self::load_archive(archive.into_inner(), true) {
+                Ok((_, diagnostics)) => {
+                    problems.extend(diagnostics.into_iter().map(Problem::Damaged))
+                }
+                Err(e) => problems.push(Problem::Unreadable(e)),
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// Checks a project file in filesystem. See [`Project::check`].
+    pub fn check_file<P: AsRef<Path>>(path: P) -> Result<Vec<Problem>, ArchiveError> {
+        let file = File::open(&path)?;
+        Self::check(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::*;
+
+    #[test]
+    fn mimetype_at_fixed_offset() {
+        let data = Project::new()
+            .save(Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner();
+        assert_eq!(&data[30..38], MIME_TYPE_FILE.as_bytes());
+        assert_eq!(
+            &data[38..38 + PROJECT_MIME_TYPE.len()],
+            PROJECT_MIME_TYPE.as_bytes()
+        );
+        assert!(Project::check(Cursor::new(data)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn check_layout() {
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("khazanah.xml", FileOptions::default())
+            .unwrap();
+        archive.write_all(b"<khazanah version=\"3\"/>").unwrap();
+        archive
+            .start_file(MIME_TYPE_FILE, FileOptions::default())
+            .unwrap();
+        archive.write_all(b"application/zip").unwrap();
+        let data = archive.finish().unwrap().into_inner();
+
+        let problems = Project::check(Cursor::new(data)).unwrap();
+        assert!(matches!(problems[0], Problem::MimeTypeNotFirst));
+        assert!(matches!(problems[1], Problem::MimeTypeCompressed));
+        assert!(matches!(&problems[2], Problem::WrongMimeType(s) if s == "application/zip"));
+        assert!(matches!(
+            problems[3],
+            Problem::Unreadable(ArchiveError::WrongMimeType)
+        ));
+    }
+
+    #[test]
+    fn mimetype_with_newline() {
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file(
+                MIME_TYPE_FILE,
+                FileOptions::default().compression_method(CompressionMethod::Stored),
+            )
+            .unwrap();
+        archive
+            .write_all(format!("{PROJECT_MIME_TYPE}\n").as_bytes())
+            .unwrap();
+        archive
+            .start_file("khazanah.xml", FileOptions::default())
+            .unwrap();
+        archive.write_all(b"<khazanah version=\"3\"/>").unwrap();
+        let data = archive.finish().unwrap().into_inner();
+
+        // Checking accepts what loading accepts.
+        assert!(Project::load(Cursor::new(data.clone())).is_ok());
+        assert!(Project::check(Cursor::new(data)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn check_checksum() {
+        let mut project = Project::new();
+        project.language_mut().meta_mut().name = "Checksum".to_string();
+        let mut data = project.save(Cursor::new(Vec::new())).unwrap().into_inner();
+
+        // Corrupt khazanah.xml, the second entry.
+        let i = data.windows(12).position(|w| w == b"khazanah.xml").unwrap();
+        let start = i + 12 + 5;
+        data[start] ^= 0xff;
+
+        let problems = Project::check(Cursor::new(data.clone())).unwrap();
+        assert!(matches!(&problems[..], [Problem::Corrupted(name, _)] if name == "khazanah.xml"));
+        assert!(matches!(
+            Project::load(Cursor::new(data)),
+            Err(ArchiveError::Corrupted(name, _)) if name == "khazanah.xml"
+        ));
+    }
+}
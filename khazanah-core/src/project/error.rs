@@ -1,7 +1,10 @@
 use zip::result::ZipError;
 
-use crate::language;
+use crate::{corpus, language, lexicon, media};
 
+use super::PROJECT_VERSION;
+
+use crate::json::JsonError;
 use crate::xml::XmlError;
 
 /// Error type for `Project`.
@@ -10,6 +13,21 @@ pub enum Error {
     /// Error at language domain.
     #[error("Language error: {0}")]
     Language(#[from] language::Error),
+    /// Error at corpus domain.
+    #[error("Corpus error: {0}")]
+    Corpus(#[from] corpus::Error),
+    /// Error at word history.
+    #[error("History error: {0}")]
+    History(#[from] lexicon::Error),
+    /// Error at media manifest.
+    #[error("Media error: {0}")]
+    Media(#[from] media::Error),
+    /// The project format version is not supported by this version of the program.
+    #[error("Unsupported project format version {0}, the latest supported is {PROJECT_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The project format version is not a number.
+    #[error("Invalid project format version \"{0}\"")]
+    InvalidVersion(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -23,7 +41,16 @@ pub enum ArchiveError {
     /// Error at Xml parsing.
     #[error("Error at XML parsing: {0}")]
     Xml(#[from] XmlError<Error>),
+    /// Error at JSON parsing.
+    #[error("Error at JSON parsing: {0}")]
+    Json(#[from] JsonError),
     /// Wrong MIME type.
     #[error("This file has wrong MIME type")]
     WrongMimeType,
+    /// A required entry is missing from the archive.
+    #[error("The archive has no entry {0}")]
+    MissingEntry(String),
+    /// An entry of the archive cannot be read, or does not match its checksum.
+    #[error("Entry {0} is corrupted: {1}")]
+    Corrupted(String, std::io::Error),
 }
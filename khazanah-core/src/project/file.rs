@@ -0,0 +1,132 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::ArchiveError;
+
+/// Gets the path of the backup of `path` at `index`, starting from 1 for the newest:
+/// `project.khz~`, `project.khz~2`, `project.khz~3`, and so on.
+pub fn backup_path<P: AsRef<Path>>(path: P, index: usize) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push("~");
+    if index > 1 {
+        name.push(index.to_string());
+    }
+    PathBuf::from(name)
+}
+
+/// Gets the path of the temporary file written before replacing `path`, in the same directory
+/// so that it can be renamed over `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Shifts the backups of `path` by one, dropping the oldest, and backs up `path` as the newest.
+fn rotate_backups(path: &Path, backups: usize) -> io::Result<()> {
+    match fs::remove_file(backup_path(path, backups)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    for i in (1..backups).rev() {
+        let from = backup_path(path, i);
+        if from.exists() {
+            fs::rename(from, backup_path(path, i + 1))?;
+        }
+    }
+    // `path` stays in place until it is replaced, so link it instead of moving it.
+    let newest = backup_path(path, 1);
+    if fs::hard_link(path, &newest).is_err() {
+        fs::copy(path, &newest)?;
+    }
+    Ok(())
+}
+
+/// Flushes the directory holding `path`, so that a rename into it survives a crash.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+/// Directories cannot be opened as files on other systems, where renames are not flushed.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Writes a file at `path` with `write` so that `path` is either the old or the new file, even if
+/// the program crashes. Keeps up to `backups` previous versions of the file.
+pub(super) fn write_atomic<F>(path: &Path, backups: usize, write: F) -> Result<(), ArchiveError>
+where
+    F: FnOnce(File) -> Result<File, ArchiveError>,
+{
+    let temp = temp_path(path);
+    let result = File::create(&temp)
+        .map_err(ArchiveError::from)
+        .and_then(write)
+        .and_then(|file| Ok(file.sync_all()?))
+        .and_then(|_| {
+            // Keep the permissions of the file being replaced.
+            if let Ok(metadata) = fs::metadata(path) {
+                fs::set_permissions(&temp, metadata.permissions())?;
+            }
+            if backups > 0 && path.exists() {
+                rotate_backups(path, backups)?;
+            }
+            fs::rename(&temp, path)?;
+            Ok(sync_parent(path)?)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_paths() {
+        assert_eq!(backup_path("a/b.khz", 1), PathBuf::from("a/b.khz~"));
+        assert_eq!(backup_path("a/b.khz", 3), PathBuf::from("a/b.khz~3"));
+        assert_eq!(
+            temp_path(Path::new("a/b.khz")),
+            PathBuf::from("a/.b.khz.tmp")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keep_permissions() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("khazanah-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        let path = dir.join("test.khz");
+        let write = |contents: &'static [u8]| {
+            move |mut file: File| {
+                file.write_all(contents)?;
+                Ok(file)
+            }
+        };
+
+        write_atomic(&path, 0, write(b"a")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        write_atomic(&path, 1, write(b"b")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"b");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
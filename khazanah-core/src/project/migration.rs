@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use uuid::Uuid;
+
+use super::{Error, Project, PROJECT_VERSION};
+
+/// A step that upgrades a project from version `from` of the format to the next.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Project),
+}
+
+/// Migrations, in order of version.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "Keep unknown elements instead of rejecting them",
+        apply: from_v1,
+    },
+    Migration {
+        from: 2,
+        description: "Attach media assets to words, and drop references to deleted words",
+        apply: from_v2,
+    },
+];
+
+/// Version 2 adds nothing that needs converting: word status, affixes, derivations, custom
+/// fields, the corpus, and the history all have defaults. It marks that unknown elements are
+/// kept from then on, while version 1 documents have theirs skipped.
+fn from_v1(_project: &mut Project) {}
+
+/// Version 3 adds media assets, which older projects don't have. Older projects may still link
+/// corpus tokens to, and keep the history of, words since deleted from the dictionary, which
+/// are dropped here.
+fn from_v2(project: &mut Project) {
+    let dictionary = project.language.dictionary();
+    let dangling = project
+        .corpus
+        .iter_texts()
+        .flat_map(|text| text.sentences())
+        .flat_map(|sentence| sentence.token_links())
+        .flatten()
+        .chain(project.history.word_ids())
+        .filter(|id| dictionary.word_by_id(*id).is_none())
+        .collect::<BTreeSet<Uuid>>();
+    for id in dangling {
+        project.corpus.unlink_word(id);
+        project.history.remove_word(id);
+    }
+}
+
+/// Checks that a document of version `version` can be read.
+pub(crate) fn check_version(version: u32) -> Result<(), Error> {
+    if version == 0 || version > PROJECT_VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+/// Upgrades `project`, read from a document of version `version`, to the current version one
+/// step at a time. Returns the descriptions of the applied migrations.
+pub(crate) fn migrate(project: &mut Project, version: u32) -> Result<Vec<&'static str>, Error> {
+    check_version(version)?;
+    let mut ret = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        (migration.apply)(project);
+        ret.push(migration.description);
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_consecutive() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from, i as u32 + 1);
+        }
+        assert_eq!(MIGRATIONS.len() as u32 + 1, PROJECT_VERSION);
+    }
+
+    #[test]
+    fn migrate_from_each_version() {
+        let mut project = Project::new();
+        assert_eq!(migrate(&mut project, 1).unwrap().len(), MIGRATIONS.len());
+        assert!(migrate(&mut project, PROJECT_VERSION).unwrap().is_empty());
+        assert!(matches!(
+            migrate(&mut project, PROJECT_VERSION + 1),
+            Err(Error::UnsupportedVersion(_))
+        ));
+        assert!(migrate(&mut project, 0).is_err());
+    }
+}
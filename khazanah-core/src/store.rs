@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::Uuid;
 
 use crate::xml::{ReadXml, WriteXml, XmlError, XmlReader, XmlWriter};
@@ -72,6 +73,31 @@ where
     }
 }
 
+/// Stores are written to JSON as arrays sorted by id, so the output is stable.
+impl<T> Serialize for Store<T>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items = self.inner.iter().collect::<Vec<_>>();
+        items.sort_by_key(|(id, _)| *id);
+        serializer.collect_seq(items.into_iter().map(|(_, v)| v))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Store<T>
+where
+    T: IdAble + Default + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut store = Self::new();
+        for v in Vec::<T>::deserialize(deserializer)? {
+            store.add(v);
+        }
+        Ok(store)
+    }
+}
+
 impl<T> Store<T>
 where
     T: IdAble + Default + ReadXml,
@@ -206,7 +232,7 @@ mod tests {
     }
 
     impl ReadXml for Item {
-        type Error = ();
+        type Error = uuid::Error;
 
         type ReaderState = ();
 
@@ -226,7 +252,7 @@ mod tests {
                         .find(|&x| x.0 == "id")
                         .map(|x| Uuid::parse_str(&x.1))
                         .unwrap_or_else(|| Ok(Uuid::new_v4()))
-                        .map_err(|_| XmlError::Other(()))?;
+                        .map_err(XmlError::Other)?;
                     self.id = Some(id);
                 }
                 (Some(Self::TAG), Some("value")) => {
@@ -2,8 +2,9 @@
 
 use std::{
     borrow::Cow,
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader, Cursor, Write},
+    io::{BufRead, BufReader, Cursor, Read, Write},
     path::Path,
     str::Utf8Error,
     string::FromUtf8Error,
@@ -110,12 +111,105 @@ pub trait XmlReaderProcess {
     }
 }
 
+/// A problem found while reading XML in recovery mode.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct XmlDiagnostic {
+    /// The name of the file read, if known.
+    pub file: String,
+    /// The line of the problem, starting from 1.
+    pub line: usize,
+    /// The column of the problem in bytes, starting from 1.
+    pub column: usize,
+    /// The path of the element where the problem is, such as `"khazanah/language/meta"`.
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for XmlDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.file.is_empty() {
+            write!(f, "{}:", self.file)?;
+        }
+        write!(
+            f,
+            "{}:{}: {} (in <{}>)",
+            self.line, self.column, self.message, self.path
+        )
+    }
+}
+
+/// A reader that remembers where lines start, to locate positions in the input.
+struct LineReader<R> {
+    inner: R,
+    /// Bytes consumed so far.
+    pos: usize,
+    /// Positions of the newlines consumed so far.
+    newlines: Vec<usize>,
+}
+
+impl<R> LineReader<R> {
+    fn count(&mut self, buf: &[u8]) {
+        for (i, &b) in buf.iter().enumerate() {
+            if b == b'\n' {
+                self.newlines.push(self.pos + i);
+            }
+        }
+        self.pos += buf.len();
+    }
+
+    /// Gets the line and column of byte position `pos`, both starting from 1.
+    fn line_column(&self, pos: usize) -> (usize, usize) {
+        let line = self.newlines.partition_point(|&n| n < pos);
+        let line_start = match line {
+            0 => 0,
+            _ => self.newlines[line - 1] + 1,
+        };
+        (line + 1, pos - line_start + 1)
+    }
+}
+
+impl<R: Read> Read for LineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for LineReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if amt > 0 {
+            // The data is already buffered, so this doesn't read.
+            if let Ok(buf) = self.inner.fill_buf() {
+                let buf = buf[..amt.min(buf.len())].to_vec();
+                self.count(&buf);
+            }
+        }
+        self.inner.consume(amt)
+    }
+}
+
 /// Generic XML reader.
 #[allow(clippy::type_complexity)]
 pub struct XmlReader<R> {
-    reader: Reader<R>,
+    reader: Reader<LineReader<R>>,
     buf: Vec<u8>,
     pub context: Vec<String>,
+    /// If `true`, elements rejected with `XmlError::InvalidTag` are skipped instead.
+    pub skip_unknown: bool,
+    /// Paths of the elements skipped so far, such as `"khazanah/language/foo"`.
+    pub skipped: Vec<String>,
+    /// If `true`, errors are collected in `diagnostics` and the elements where they occur are
+    /// skipped, instead of stopping at the first error.
+    pub recover: bool,
+    /// Problems found so far in recovery mode.
+    pub diagnostics: Vec<XmlDiagnostic>,
+    /// Whether the input is so broken that reading cannot continue.
+    failed: bool,
 }
 
 impl<R> XmlReader<R>
@@ -124,13 +218,24 @@ where
 {
     /// Creates a new reader.
     pub fn new(reader: R) -> Self {
-        let mut reader = Reader::from_reader(reader);
+        let mut reader = Reader::from_reader(LineReader {
+            inner: reader,
+            pos: 0,
+            newlines: Vec::new(),
+        });
         reader.trim_text(true);
+        // Read `<tag/>` as an opening and a closing tag, as written by other tools.
+        reader.expand_empty_elements(true);
 
         Self {
             reader,
             buf: Vec::new(),
             context: Vec::new(),
+            skip_unknown: false,
+            skipped: Vec::new(),
+            recover: false,
+            diagnostics: Vec::new(),
+            failed: false,
         }
     }
 
@@ -170,9 +275,127 @@ where
         (parent_tag, last_tag)
     }
 
+    /// Gets the line and column of the last read event, both starting from 1.
+    pub fn position(&self) -> (usize, usize) {
+        self.reader
+            .get_ref()
+            .line_column(self.reader.buffer_position())
+    }
+
+    /// Records `error` as a diagnostic at the current position.
+    pub fn report<E: Display>(&mut self, error: &XmlError<E>) {
+        let (line, column) = self.position();
+        self.diagnostics.push(XmlDiagnostic {
+            file: String::new(),
+            line,
+            column,
+            path: self.context.join("/"),
+            message: error.to_string(),
+        });
+    }
+
+    /// Reads up to the opening tag of the root element, and gets its name and attributes.
+    /// Returns `None` if the document has no element.
+    #[allow(clippy::type_complexity)]
+    pub fn read_root_start<E>(
+        &mut self,
+    ) -> Result<Option<(String, Vec<(String, String)>)>, XmlError<E>> {
+        loop {
+            match self.read_event()? {
+                Event::Start(e) => return read_tag_start(&e).map(Some),
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips the rest of the element last opened, up to and including its closing tag.
+    pub fn skip_element(&mut self) -> Result<(), quick_xml::Error> {
+        let depth = self.context.len();
+        while self.context.len() >= depth {
+            if let Event::Eof = self.read_event()? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the rest of the element last opened, named `name` with attributes `attrs`, up to
+    /// and including its closing tag.
+    pub fn read_element<E>(
+        &mut self,
+        name: String,
+        attrs: Vec<(String, String)>,
+    ) -> Result<XmlElement, XmlError<E>> {
+        let mut stack = vec![XmlElement {
+            name,
+            attrs,
+            children: Vec::new(),
+        }];
+        loop {
+            match self.read_event()? {
+                Event::Start(e) => {
+                    let (name, attrs) = read_tag_start(&e)?;
+                    stack.push(XmlElement {
+                        name,
+                        attrs,
+                        children: Vec::new(),
+                    });
+                }
+                Event::Text(e) => {
+                    let text = e.unescape()?.to_string();
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(XmlNode::Text(text));
+                    }
+                }
+                Event::End(_) => {
+                    let element = stack.pop().unwrap_or_default();
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(XmlNode::Element(element)),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Eof => return Ok(stack.swap_remove(0)),
+                _ => {}
+            }
+        }
+    }
+
     /// Finishes reading and returns the underlying reader.
     pub fn finish(self) -> R {
-        self.reader.into_inner()
+        self.reader.into_inner().inner
+    }
+}
+
+/// An element kept as it is, such as one not known by this version of the program.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+}
+
+/// A child of an `XmlElement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+impl XmlElement {
+    /// Writes the element and its children.
+    pub fn write<W: Write, E>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError<E>> {
+        writer.write_tag_start_with_attributes(
+            &self.name,
+            self.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+        )?;
+        for child in self.children.iter() {
+            match child {
+                XmlNode::Element(element) => element.write(writer)?,
+                XmlNode::Text(text) => writer.write_text(text)?,
+            }
+        }
+        writer.write_tag_end(&self.name)
     }
 }
 
@@ -244,12 +467,27 @@ where
     }
 }
 
+/// Gets the name and the attributes of an opening tag.
+#[allow(clippy::type_complexity)]
+fn read_tag_start<E>(e: &BytesStart) -> Result<(String, Vec<(String, String)>), XmlError<E>> {
+    let name = std::str::from_utf8(e.name().into_inner())?.to_string();
+    let mut attrs = Vec::new();
+    for a in e.attributes() {
+        let attr = a?;
+        attrs.push((
+            std::str::from_utf8(attr.key.into_inner())?.to_string(),
+            attr.unescape_value()?.to_string(),
+        ));
+    }
+    Ok((name, attrs))
+}
+
 /// A trait for object that can be read from XML.
 pub trait ReadXml
 where
     Self: Sized + Default,
 {
-    type Error;
+    type Error: Display;
 
     type ReaderState: Default;
 
@@ -293,41 +531,84 @@ where
         }
 
         loop {
-            match reader.read_event() {
-                Err(e) => {
-                    return Err(XmlError::Qxml(e));
+            if reader.failed {
+                break;
+            }
+            let event = match reader.read_event().map(Event::into_owned) {
+                Ok(event) => event,
+                Err(e) if reader.recover => {
+                    // The input cannot be read any further; keep what has been read.
+                    reader.report(&XmlError::<Self::Error>::Qxml(e));
+                    reader.failed = true;
+                    break;
                 }
-
-                Ok(Event::Eof) => break,
-
-                Ok(Event::Start(e)) => {
-                    let name = std::str::from_utf8(e.name().into_inner())?.to_string();
-                    let mut attrs = Vec::new();
-                    for a in e.attributes() {
-                        let attr = a?;
-                        attrs.push((
-                            std::str::from_utf8(attr.key.into_inner())?.to_string(),
-                            attr.unescape_value()?.to_string(),
-                        ));
+                Err(e) => return Err(XmlError::Qxml(e)),
+            };
+
+            let depth = reader.context.len();
+            let mut tag_end = false;
+            let result = match event {
+                Event::Eof => {
+                    if reader.recover && !reader.context.is_empty() {
+                        let e = quick_xml::Error::UnexpectedEof(reader.context.join("/"));
+                        reader.report(&XmlError::<Self::Error>::Qxml(e));
+                        reader.failed = true;
                     }
-                    data.process_tag_start(reader, &mut state, name, attrs)?;
+                    break;
                 }
 
-                Ok(Event::Text(e)) => {
-                    let text = e.unescape()?.to_string();
-                    data.process_text(reader, &mut state, text)?;
-                }
-
-                Ok(Event::End(e)) => {
-                    let name = std::str::from_utf8(e.name().into_inner())?.to_string();
-                    let tag_end = name == Self::TAG;
-                    data.process_tag_end(reader, &mut state, name)?;
-                    if tag_end {
-                        break;
+                Event::Start(e) => match read_tag_start(&e) {
+                    Ok((name, attrs)) => {
+                        match data.process_tag_start(reader, &mut state, name, attrs) {
+                            // Only skip the element just opened, not one rejected by a nested
+                            // reader.
+                            Err(XmlError::InvalidTag(name))
+                                if reader.skip_unknown
+                                    && reader.last_tag() == Some(name.as_str()) =>
+                            {
+                                reader.skipped.push(reader.context.join("/"));
+                                reader.skip_element().map_err(XmlError::Qxml)
+                            }
+                            r => r,
+                        }
+                    }
+                    Err(e) => Err(e),
+                },
+
+                Event::Text(e) => match e.unescape() {
+                    Ok(text) => data.process_text(reader, &mut state, text.to_string()),
+                    Err(e) => Err(e.into()),
+                },
+
+                Event::End(e) => match std::str::from_utf8(e.name().into_inner()) {
+                    Ok(name) => {
+                        tag_end = name == Self::TAG;
+                        data.process_tag_end(reader, &mut state, name.to_string())
+                    }
+                    Err(e) => Err(e.into()),
+                },
+
+                _ => Ok(()),
+            };
+
+            match result {
+                Ok(()) => {}
+                // Nothing can be recovered from a broken root element.
+                Err(e) if reader.recover && depth > 1 => {
+                    reader.report(&e);
+                    // Skip the rest of the broken element, if it is still open.
+                    if reader.context.len() >= depth {
+                        if let Err(e) = reader.skip_element() {
+                            reader.report(&XmlError::<Self::Error>::Qxml(e));
+                            reader.failed = true;
+                        }
                     }
                 }
+                Err(e) => return Err(e),
+            }
 
-                _ => {}
+            if tag_end {
+                break;
             }
         }
         Ok(data)
@@ -340,6 +621,17 @@ where
         Ok((ret, r.finish()))
     }
 
+    /// Reads from XML in recovery mode, skipping the elements where errors occur. Returns the
+    /// partially read object and the problems found. Fails only if the root element is broken.
+    fn recover_xml<R: BufRead>(
+        reader: R,
+    ) -> Result<(Self, Vec<XmlDiagnostic>), XmlError<Self::Error>> {
+        let mut r = XmlReader::new(reader);
+        r.recover = true;
+        let ret = Self::deserialize_xml(&mut r, None)?;
+        Ok((ret, r.diagnostics))
+    }
+
     /// Loads from XML file.
     fn load_xml_file<P: AsRef<Path>>(path: P) -> Result<Self, XmlError<Self::Error>> {
         let f = File::open(path)?;
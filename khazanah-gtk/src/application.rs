@@ -11,6 +11,8 @@ use crate::ui;
 
 #[doc(hidden)]
 mod imp {
+    use std::cell::Cell;
+
     use gtk::glib::WeakRef;
 
     use super::*;
@@ -18,6 +20,7 @@ mod imp {
     #[derive(Default)]
     pub struct Application {
         pub window: WeakRef<ui::ApplicationWindow>,
+        pub recovery_offered: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -46,7 +49,12 @@ mod imp {
         fn activate(&self) {
             self.parent_activate();
             let obj = self.obj();
-            obj.window().present();
+            let window = obj.window();
+            window.present();
+            // Only the first activation can find an autosave left by a previous run.
+            if !self.recovery_offered.replace(true) {
+                window.offer_recovery();
+            }
         }
 
         fn shutdown(&self) {
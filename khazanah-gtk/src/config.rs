@@ -3,3 +3,7 @@ pub const APP_NAME: &str = "Khazanah";
 pub const AUTHOR: &str = "ManEnfu";
 pub const VERSION: &str = "0.1.0";
 pub const HOMEPAGE: &str = "https://github.com/ManEnfu/Khazanah";
+/// Number of previous versions kept when saving a project.
+pub const BACKUPS: usize = 3;
+/// Interval in seconds between autosaves of unsaved changes.
+pub const AUTOSAVE_INTERVAL: u32 = 60;
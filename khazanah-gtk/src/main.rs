@@ -4,6 +4,7 @@ use gtk::prelude::*;
 mod application;
 mod config;
 mod models;
+mod recovery;
 mod ui;
 
 /// Entry point.
@@ -2,6 +2,8 @@ use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
+use khazanah_core::ProjectFormat;
+
 use crate::models;
 
 #[doc(hidden)]
@@ -21,6 +23,10 @@ mod imp {
             get = Self::get_author, set = Self::set_author)]
         #[property(name = "description", type = String,
             get = Self::get_description, set = Self::set_description)]
+        #[property(name = "alphabet", type = String,
+            get = Self::get_alphabet, set = Self::set_alphabet)]
+        #[property(name = "store-json", type = bool,
+            get = Self::get_store_json, set = Self::set_store_json)]
         #[property(get, set)]
         pub project_model: RefCell<models::ProjectModel>,
     }
@@ -77,6 +83,36 @@ mod imp {
                 project.language_mut().meta_mut().description = value.clone();
             });
         }
+
+        fn get_alphabet(&self) -> String {
+            self.project_model
+                .borrow()
+                .query(|project| project.language().meta().alphabet.to_owned())
+                .unwrap_or_default()
+        }
+
+        fn set_alphabet(&self, value: String) {
+            self.project_model.borrow_mut().update(|project| {
+                project.language_mut().meta_mut().alphabet = value.clone();
+            });
+        }
+
+        fn get_store_json(&self) -> bool {
+            self.project_model
+                .borrow()
+                .query(|project| project.format() == ProjectFormat::Json)
+                .unwrap_or_default()
+        }
+
+        fn set_store_json(&self, value: bool) {
+            self.project_model.borrow_mut().update(|project| {
+                project.set_format(if value {
+                    ProjectFormat::Json
+                } else {
+                    ProjectFormat::Xml
+                });
+            });
+        }
     }
 
     #[glib::object_subclass]
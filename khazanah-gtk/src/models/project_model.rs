@@ -7,7 +7,10 @@ use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use khazanah_core::project;
-use khazanah_core::Project;
+use khazanah_core::xml::XmlDiagnostic;
+use khazanah_core::{Language, Project};
+
+use crate::config;
 
 mod imp {
     use std::cell::Cell;
@@ -31,6 +34,9 @@ mod imp {
         pub path: RefCell<Option<String>>,
 
         pub title: RefCell<String>,
+
+        /// Incremented on every change, so that views can tell when their caches are stale.
+        pub revision: Cell<u64>,
     }
 
     impl ProjectModel {
@@ -111,6 +117,7 @@ impl ProjectModel {
     /// Sets the current project.
     pub fn set_project(&self, project: Option<Project>) {
         self.imp().project.replace(project);
+        self.bump_revision();
         self.set_dirty(false);
         self.notify_title();
         self.notify_opened();
@@ -133,22 +140,52 @@ impl ProjectModel {
         self.notify_path();
     }
 
-    /// Loads project from a file.
-    pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<(), project::ArchiveError> {
-        let project = Project::load_file(&path)?;
+    /// Creates a new project with an imported language and sets it as the current project.
+    pub fn import_language(&self, language: Language) {
+        let mut project = Project::new();
+        *project.language_mut() = language;
+        self.set_project(Some(project));
+        self.imp().path.replace(None);
+        self.notify_path();
+        // The imported project is not saved yet.
+        self.set_dirty(true);
+    }
+
+    /// Loads project from a file, skipping broken entries. Returns the problems found.
+    pub fn load_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<Vec<XmlDiagnostic>, project::ArchiveError> {
+        let (project, diagnostics) = Project::recover_file(&path)?;
         self.set_project(Some(project));
         self.set_path(path.as_ref().to_string_lossy().to_string());
         self.set_dirty(false);
         self.notify_title();
         self.notify_opened();
-        Ok(())
+        Ok(diagnostics)
+    }
+
+    /// Loads an autosaved project, which was saved to `origin` if any. The project stays dirty
+    /// until it is saved. Returns the problems found.
+    pub fn load_recovery_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        origin: Option<String>,
+    ) -> Result<Vec<XmlDiagnostic>, project::ArchiveError> {
+        let (project, diagnostics) = Project::recover_file(&path)?;
+        self.set_project(Some(project));
+        self.imp().path.replace(origin);
+        self.notify_path();
+        self.set_dirty(true);
+        self.notify_title();
+        Ok(diagnostics)
     }
 
-    /// Saves the project to a file.
+    /// Saves the project to a file, keeping backups of the previous versions.
     pub fn save_file<P: AsRef<Path>>(&self, path: P) -> Result<(), project::ArchiveError> {
         let result = match self.project_mut().as_mut() {
             Some(project) => {
-                project.save_file(&path)?;
+                project.save_file_with_backups(&path, config::BACKUPS)?;
                 Ok(())
             }
             None => Err(project::ArchiveError::WrongMimeType),
@@ -161,12 +198,22 @@ impl ProjectModel {
         result
     }
 
+    /// Saves a copy of the project to a file. Unlike `save_file`, the project stays dirty and
+    /// keeps its path.
+    pub fn autosave_file<P: AsRef<Path>>(&self, path: P) -> Result<(), project::ArchiveError> {
+        match self.project_mut().as_mut() {
+            Some(project) => project.save_file(&path),
+            None => Err(project::ArchiveError::WrongMimeType),
+        }
+    }
+
     /// Updates the state of the project. Marks the project as dirty.
     pub fn update<F, O>(&self, f: F) -> Option<O>
     where
         F: Fn(&mut Project) -> O,
     {
         let ret = self.project_mut().as_mut().map(f);
+        self.bump_revision();
         self.set_dirty(true);
         self.notify_title();
         ret
@@ -181,9 +228,20 @@ impl ProjectModel {
     }
 
     pub fn notify_changes(&self) {
+        self.bump_revision();
         self.set_dirty(true);
         self.notify_title();
     }
+
+    /// Gets the number of changes made to the project since the model was created.
+    pub fn revision(&self) -> u64 {
+        self.imp().revision.get()
+    }
+
+    fn bump_revision(&self) {
+        let revision = &self.imp().revision;
+        revision.set(revision.get().wrapping_add(1));
+    }
 }
 
 impl Default for ProjectModel {
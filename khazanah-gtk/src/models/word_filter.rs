@@ -1,7 +1,14 @@
+use std::collections::HashSet;
+
 use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
+
+use khazanah_core::lexicon::{FieldDefinitions, Query, WordStatus};
 use khazanah_core::PartOfSpeech;
+use uuid::Uuid;
+
+use crate::models::WordObject;
 
 #[derive(Default, Debug, Clone)]
 pub enum WordFilterBy {
@@ -12,14 +19,55 @@ pub enum WordFilterBy {
     Translation(String),
     Pronunciation(String),
     PartOfSpeech(PartOfSpeech),
+    /// Words having exactly this tag.
+    Tag(String),
+    /// Words of this review status.
+    Status(WordStatus),
+    /// Words having a tag containing this text.
+    Tags(String),
+    /// Words whose value of a custom field contains this text. Looks at all fields if the
+    /// field id is `None`.
+    Field(Option<Uuid>, String),
+    /// Words matching all criteria.
+    And(Vec<WordFilterBy>),
+    /// Words of these ids.
+    Ids(HashSet<Uuid>),
+    /// Words satisfying a search query. Custom field names are resolved by the definitions.
+    Query(Query, FieldDefinitions),
+}
+
+impl WordFilterBy {
+    /// Returns `true` if `word` matches the criteria.
+    pub fn matches(&self, word: &WordObject) -> bool {
+        let contains =
+            |haystack: &str, s: &str| haystack.to_lowercase().contains(&s.to_lowercase());
+        match self {
+            Self::AllAttrs(s) => {
+                contains(&word.romanization(), s)
+                    || contains(&word.translation(), s)
+                    || word.tag_list().iter().any(|t| contains(t, s))
+            }
+            Self::Romanization(s) => contains(&word.romanization(), s),
+            Self::Translation(s) => contains(&word.translation(), s),
+            Self::Pronunciation(s) => word.pronunciation().contains(s),
+            Self::PartOfSpeech(pos) => word.part_of_speech_value() == Some(*pos),
+            Self::Tag(tag) => word.has_tag(tag),
+            Self::Status(status) => word.status_value() == *status,
+            Self::Tags(s) => word.tag_list().iter().any(|t| contains(t, s)),
+            Self::Field(Some(id), s) => word.field(*id).map(|v| contains(&v, s)).unwrap_or(false),
+            Self::Field(None, s) => word.field_values().iter().any(|v| contains(v, s)),
+            Self::And(filters) => filters.iter().all(|f| f.matches(word)),
+            Self::Ids(ids) => ids.contains(&word.id()),
+            Self::Query(query, fields) => word.matches_query(query, fields),
+            Self::None => true,
+        }
+    }
 }
 
 #[doc(hidden)]
 mod imp {
     use std::cell::RefCell;
 
-    use crate::models::WordObject;
-
     use super::*;
 
     #[derive(Debug, Default)]
@@ -41,24 +89,7 @@ mod imp {
             let word = item
                 .downcast_ref::<WordObject>()
                 .expect("`KhzWordFilter` expected `KhzWordObject` to match.");
-            match &*self.filter_by.borrow() {
-                WordFilterBy::AllAttrs(s) => {
-                    let s = s.to_lowercase();
-                    word.romanization().to_lowercase().contains(&s)
-                        || word.translation().to_lowercase().contains(&s)
-                }
-                WordFilterBy::Romanization(s) => word
-                    .romanization()
-                    .to_lowercase()
-                    .contains(&s.to_lowercase()),
-                WordFilterBy::Translation(s) => word
-                    .translation()
-                    .to_lowercase()
-                    .contains(&s.to_lowercase()),
-                WordFilterBy::Pronunciation(s) => word.pronunciation().contains(s),
-                WordFilterBy::None => true,
-                _ => false,
-            }
+            self.filter_by.borrow().matches(word)
         }
 
         fn strictness(&self) -> gtk::FilterMatch {
@@ -1,8 +1,11 @@
 use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
+use khazanah_core::lexicon::{
+    Affix, Allomorph, FieldDefinitions, Query, WordStatus, ALL_AFFIX_KINDS, ALL_WORD_STATUSES,
+};
 use khazanah_core::prelude::*;
-use khazanah_core::{Word, ALL_PARTS_OF_SPEECH};
+use khazanah_core::{PartOfSpeech, Word, ALL_PARTS_OF_SPEECH};
 use uuid::Uuid;
 
 use crate::models;
@@ -37,15 +40,27 @@ mod imp {
             get = Self::get_pos, set = Self::set_pos)]
         #[property(name = "part-of-speech-label", 
             get = Self::get_pos_label, type = String)]
+        #[property(name = "affix-kind", type = u32,
+            get = Self::get_affix_kind, set = Self::set_affix_kind)]
+        #[property(name = "allomorphs", type = String,
+            get = Self::get_allomorphs, set = Self::set_allomorphs)]
         #[property(name = "use-xsampa", type = bool,
             get = Self::get_use_xsampa, set = Self::set_use_xsampa)]
         #[property(name = "xsampa-pronunciation", type = String,
             get = Self::get_xsampa_pronunciation, set = Self::set_xsampa_pronunciation)]
+        #[property(name = "tags", type = String,
+            get = Self::get_tags, set = Self::set_tags)]
+        #[property(name = "status", type = u32,
+            get = Self::get_status, set = Self::set_status)]
+        #[property(name = "status-label", type = String,
+            get = Self::get_status_label)]
+        #[property(name = "replaced-by", type = String,
+            get = Self::get_replaced_by)]
         pub inner: RefCell<Option<Inner>>,
     }
 
     impl WordObject {
-        fn query<T, F>(&self, f: F) -> T
+        pub fn query<T, F>(&self, f: F) -> T
         where
             T: Default,
             F: Fn(&Word) -> T,
@@ -60,20 +75,14 @@ mod imp {
             }
         }
 
-        fn update<F>(&self, f: F)
+        pub fn update<F>(&self, f: F)
         where
             F: Fn(&mut Word),
         {
             match self.inner.borrow_mut().as_mut() {
                 Some(Inner::Owned(word)) => f(word),
                 Some(Inner::QueryFromProject { project_model, id }) => {
-                    project_model.update(|project| {
-                        project
-                            .language_mut()
-                            .dictionary_mut()
-                            .word_by_id_mut(*id)
-                            .map(&f)
-                    });
+                    project_model.update(|project| project.update_word(*id, &f));
                 }
                 None => {}
             }
@@ -132,6 +141,60 @@ mod imp {
             })
         }
 
+        fn get_affix_kind(&self) -> u32 {
+            self.query(|word| {
+                ALL_AFFIX_KINDS
+                    .iter()
+                    .position(|&x| x == word.affix().map(|a| a.kind))
+                    .unwrap_or_default() as u32
+            })
+        }
+
+        /// Sets how the word attaches. A word that becomes an affix gets its romanization,
+        /// without hyphens, as its only allomorph.
+        fn set_affix_kind(&self, value: u32) {
+            let kind = ALL_AFFIX_KINDS.get(value as usize).copied().flatten();
+            self.update(|word| match (kind, word.affix_mut()) {
+                (None, _) => word.set_affix(None),
+                (Some(kind), Some(affix)) => affix.kind = kind,
+                (Some(kind), None) => {
+                    let mut affix = Affix::new(kind);
+                    let form = word.romanization().replace('-', "");
+                    if !form.is_empty() {
+                        affix.allomorphs.push(Allomorph::new(form));
+                    }
+                    word.set_affix(Some(affix));
+                }
+            });
+            self.obj().notify_allomorphs();
+        }
+
+        fn get_allomorphs(&self) -> String {
+            self.query(|word| {
+                word.affix()
+                    .map(|a| {
+                        a.allomorphs
+                            .iter()
+                            .map(|allo| allo.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default()
+            })
+        }
+
+        fn set_allomorphs(&self, value: String) {
+            self.update(|word| {
+                if let Some(affix) = word.affix_mut() {
+                    affix.allomorphs = value
+                        .split(',')
+                        .filter_map(|s| s.parse::<Allomorph>().ok())
+                        .filter(|allo| !allo.form.is_empty())
+                        .collect();
+                }
+            });
+        }
+
         fn get_xsampa_pronunciation(&self) -> String {
             self.query(|word| word.xsampa_pronunciation().unwrap_or_default().to_string())
         }
@@ -164,6 +227,76 @@ mod imp {
             obj.notify_pronunciation();
         }
 
+        fn get_tags(&self) -> String {
+            self.query(|word| word.tags().collect::<Vec<_>>().join(", "))
+        }
+
+        fn set_tags(&self, value: String) {
+            self.update(|word| {
+                let tags = word.tags().map(str::to_string).collect::<Vec<_>>();
+                for tag in tags {
+                    word.remove_tag(&tag);
+                }
+                for tag in value.split(',') {
+                    word.add_tag(tag.to_string());
+                }
+            });
+        }
+
+        fn get_status(&self) -> u32 {
+            self.query(|word| {
+                ALL_WORD_STATUSES
+                    .iter()
+                    .position(|&x| x == word.status())
+                    .unwrap_or_default() as u32
+            })
+        }
+
+        fn set_status(&self, value: u32) {
+            self.update(|word| {
+                word.set_status(
+                    ALL_WORD_STATUSES
+                        .get(value as usize)
+                        .copied()
+                        .unwrap_or_default(),
+                );
+                if !word.status().is_retired() {
+                    word.set_replaced_by(None);
+                }
+            });
+            let obj = self.obj();
+            obj.notify_status_label();
+            obj.notify_replaced_by();
+        }
+
+        /// Gets the name of the status, or an empty string for approved words.
+        fn get_status_label(&self) -> String {
+            self.query(|word| match word.status() {
+                WordStatus::Approved => String::new(),
+                status => status.name().to_string(),
+            })
+        }
+
+        /// Gets the romanization of the replacing word.
+        fn get_replaced_by(&self) -> String {
+            let replaced_by = self.query(|word| word.replaced_by());
+            match (self.inner.borrow().as_ref(), replaced_by) {
+                (Some(Inner::QueryFromProject { project_model, .. }), Some(replaced_by)) => {
+                    project_model
+                        .query(|project| {
+                            project
+                                .language()
+                                .dictionary()
+                                .word_by_id(replaced_by)
+                                .map(|w| w.romanization().to_string())
+                        })
+                        .flatten()
+                        .unwrap_or_default()
+                }
+                _ => String::new(),
+            }
+        }
+
         pub fn get_id(&self) -> Uuid {
             if let Some(Inner::QueryFromProject { id, .. }) = self.inner.borrow().as_ref() {
                 *id
@@ -209,8 +342,69 @@ impl WordObject {
         obj
     }
 
+    /// Notifies every property, after the word is changed outside the object.
+    pub fn notify_all(&self) {
+        self.notify_romanization();
+        self.notify_translation();
+        self.notify_pronunciation();
+        self.notify_part_of_speech();
+        self.notify_part_of_speech_label();
+        self.notify_affix_kind();
+        self.notify_allomorphs();
+        self.notify_use_xsampa();
+        self.notify_xsampa_pronunciation();
+        self.notify_tags();
+        self.notify_status();
+        self.notify_status_label();
+        self.notify_replaced_by();
+    }
+
     /// Returs the id of the object.
     pub fn id(&self) -> Uuid {
         self.imp().get_id()
     }
+
+    /// Returns `true` if the word has tag `tag`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.imp().query(|word| word.has_tag(tag))
+    }
+
+    /// Gets the tags of the word.
+    pub fn tag_list(&self) -> Vec<String> {
+        self.imp()
+            .query(|word| word.tags().map(str::to_string).collect())
+    }
+
+    /// Gets the value of custom field of id `field_id`.
+    pub fn field(&self, field_id: Uuid) -> Option<String> {
+        self.imp()
+            .query(|word| word.field(field_id).map(str::to_string))
+    }
+
+    /// Gets the part of speech of the word.
+    pub fn part_of_speech_value(&self) -> Option<PartOfSpeech> {
+        self.imp().query(|word| word.part_of_speech())
+    }
+
+    /// Gets the review status of the word.
+    pub fn status_value(&self) -> WordStatus {
+        self.imp().query(|word| word.status())
+    }
+
+    /// Sets the word replacing this word.
+    pub fn set_replaced_by_id(&self, id: Option<Uuid>) {
+        self.imp().update(|word| word.set_replaced_by(id));
+        self.notify_replaced_by();
+    }
+
+    /// Returns `true` if the word satisfies `query`.
+    pub fn matches_query(&self, query: &Query, fields: &FieldDefinitions) -> bool {
+        self.imp().query(|word| query.matches(word, fields))
+    }
+
+    /// Gets the values of all custom fields of the word.
+    pub fn field_values(&self) -> Vec<String> {
+        self.imp()
+            .query(|word| word.fields().map(|(_, v)| v.to_string()).collect())
+    }
 }
@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
+use uuid::Uuid;
 
 #[derive(Default, Debug, Clone, Copy, glib::Enum)]
 #[enum_type(name = "KhzWordSortBy")]
@@ -16,7 +19,10 @@ pub enum WordSortBy {
 
 #[doc(hidden)]
 mod imp {
-    use std::{cell::Cell, cmp};
+    use std::{
+        cell::{Cell, RefCell},
+        cmp,
+    };
 
     use gtk::prelude::Cast;
 
@@ -28,6 +34,9 @@ mod imp {
     pub struct WordSorter {
         pub sort_by: Cell<WordSortBy>,
         pub descending: Cell<bool>,
+        /// Positions of ranked words, e.g. by relevance to a search. Ranked words come first,
+        /// in the order of their positions, regardless of `sort_by`.
+        pub ranking: RefCell<HashMap<Uuid, usize>>,
     }
 
     #[glib::object_subclass]
@@ -52,6 +61,19 @@ mod imp {
                 .downcast_ref::<WordObject>()
                 .expect("`KhzWordSorter` expected `KhzWordObject` to compare.");
 
+            {
+                let ranking = self.ranking.borrow();
+                if !ranking.is_empty() {
+                    let rank1 = ranking.get(&word1.id()).copied().unwrap_or(usize::MAX);
+                    let rank2 = ranking.get(&word2.id()).copied().unwrap_or(usize::MAX);
+                    match rank1.cmp(&rank2) {
+                        cmp::Ordering::Less => return gtk::Ordering::Smaller,
+                        cmp::Ordering::Greater => return gtk::Ordering::Larger,
+                        cmp::Ordering::Equal => {}
+                    }
+                }
+            }
+
             match self.sort_by.get() {
                 WordSortBy::Romanization => self.compare_by_romanization(word1, word2),
                 WordSortBy::Translation => self.compare_by_translation(word1, word2),
@@ -138,6 +160,16 @@ impl WordSorter {
         self.imp().sort_by.get()
     }
 
+    /// Ranks words by the order of `ids`, or removes the ranking if `ids` is empty.
+    pub fn set_ranking(&self, ids: &[Uuid]) {
+        let ranking: HashMap<Uuid, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        if *self.imp().ranking.borrow() != ranking {
+            self.imp().ranking.replace(ranking);
+            self.changed(gtk::SorterChange::Different);
+        }
+    }
+
     pub fn set_descending(&self, descending: bool) {
         if self.imp().descending.get() != descending {
             self.imp().descending.set(descending);
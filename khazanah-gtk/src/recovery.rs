@@ -0,0 +1,56 @@
+//! Autosaved copies of unsaved projects, kept to recover from a crash.
+
+use std::fs;
+use std::path::PathBuf;
+
+use gtk::glib;
+use khazanah_core::project;
+
+use crate::models::ProjectModel;
+
+/// Gets the directory of the autosaved project.
+fn dir() -> PathBuf {
+    glib::user_data_dir().join("khazanah").join("recovery")
+}
+
+/// Gets the path of the autosaved project.
+pub fn project_path() -> PathBuf {
+    dir().join("autosave.khz")
+}
+
+/// Gets the path of the file that stores where the autosaved project was saved to, if anywhere.
+fn origin_path() -> PathBuf {
+    dir().join("autosave.path")
+}
+
+/// Checks whether there is an autosaved project left, which means the application did not close
+/// properly.
+pub fn pending() -> bool {
+    project_path().exists()
+}
+
+/// Gets where the autosaved project was saved to, if it had been saved.
+pub fn origin() -> Option<String> {
+    fs::read_to_string(origin_path())
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
+/// Autosaves the project in `model`.
+pub fn save(model: &ProjectModel) -> Result<(), project::ArchiveError> {
+    fs::create_dir_all(dir())?;
+    model.autosave_file(project_path())?;
+    fs::write(origin_path(), model.path().unwrap_or_default())?;
+    Ok(())
+}
+
+/// Removes the autosaved project.
+pub fn clear() {
+    for path in [project_path(), origin_path()] {
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::error!("Unable to remove {:?}: {}", path, e);
+            }
+        }
+    }
+}
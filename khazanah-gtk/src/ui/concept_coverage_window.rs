@@ -0,0 +1,259 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use adw::subclass::prelude::*;
+
+use khazanah_core::lexicon::ConceptList;
+
+use crate::models;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    use gtk::glib::subclass::{Signal, SignalType};
+    use once_cell::sync::Lazy;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::ConceptCoverageWindow)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/concept_coverage_window.ui")]
+    pub struct ConceptCoverageWindow {
+        #[template_child]
+        pub list_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub list_names: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub summary_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub coverage_bar: TemplateChild<gtk::LevelBar>,
+        #[template_child]
+        pub missing_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub add_all_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+
+        /// Concept lists, in the order of `list_names`.
+        pub lists: RefCell<Vec<ConceptList>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ConceptCoverageWindow {
+        const NAME: &'static str = "KhzConceptCoverageWindow";
+        type Type = super::ConceptCoverageWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ConceptCoverageWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            for list in ConceptList::builtin() {
+                self.obj().add_list(list);
+            }
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: Lazy<Vec<Signal>> = Lazy::new(|| {
+                vec![Signal::builder("words-added")
+                    .param_types(Vec::<SignalType>::new())
+                    .build()]
+            });
+            SIGNALS.as_ref()
+        }
+    }
+
+    impl WidgetImpl for ConceptCoverageWindow {}
+
+    impl WindowImpl for ConceptCoverageWindow {}
+    impl AdwWindowImpl for ConceptCoverageWindow {}
+}
+
+glib::wrapper! {
+    /// Shows which concepts of a reference wordlist are missing from the dictionary.
+    pub struct ConceptCoverageWindow(ObjectSubclass<imp::ConceptCoverageWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+#[gtk::template_callbacks]
+impl ConceptCoverageWindow {
+    pub fn new(project_model: &models::ProjectModel) -> Self {
+        let ret: Self = glib::Object::builder()
+            .property("project-model", project_model)
+            .build();
+        ret.refresh();
+        ret
+    }
+
+    /// Adds a concept list to the selection.
+    fn add_list(&self, list: ConceptList) {
+        let imp = self.imp();
+        imp.list_names.append(&list.name);
+        imp.lists.borrow_mut().push(list);
+    }
+
+    /// Gets the selected concept list.
+    fn selected_list(&self) -> Option<ConceptList> {
+        let imp = self.imp();
+        imp.lists
+            .borrow()
+            .get(imp.list_dropdown.selected() as usize)
+            .cloned()
+    }
+
+    /// Recomputes the coverage of the selected list.
+    fn refresh(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.missing_list.row_at_index(0) {
+            imp.missing_list.remove(&row);
+        }
+
+        let Some(list) = self.selected_list() else {
+            return;
+        };
+        let Some(coverage) = self
+            .project_model()
+            .query(|project| list.coverage(project.language().dictionary()))
+        else {
+            return;
+        };
+
+        imp.summary_label.set_label(&format!(
+            "{} of {} concepts covered ({:.0}%)",
+            coverage.n_covered(),
+            list.len(),
+            coverage.ratio() * 100.0
+        ));
+        imp.coverage_bar.set_value(coverage.ratio());
+
+        let missing = coverage.missing().collect::<Vec<_>>();
+        imp.add_all_button.set_sensitive(!missing.is_empty());
+
+        for i in missing {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&list.concepts[i].gloss))
+                .subtitle(format!("#{}", i + 1))
+                .build();
+
+            let add_button = gtk::Button::builder()
+                .icon_name("list-add-symbolic")
+                .tooltip_text("Add placeholder word")
+                .valign(gtk::Align::Center)
+                .build();
+            add_button.add_css_class("flat");
+            add_button.connect_clicked(glib::clone!(@weak self as window => move |_| {
+                window.add_placeholders(vec![i]);
+            }));
+            row.add_suffix(&add_button);
+
+            imp.missing_list.append(&row);
+        }
+    }
+
+    /// Adds placeholder words for the concepts of the given indices in the selected list.
+    fn add_placeholders(&self, indices: Vec<usize>) {
+        let Some(list) = self.selected_list() else {
+            return;
+        };
+
+        let n = self
+            .project_model()
+            .update(|project| {
+                list.add_placeholders(
+                    project.language_mut().dictionary_mut(),
+                    indices.iter().copied(),
+                )
+                .len()
+            })
+            .unwrap_or_default();
+
+        self.emit_by_name::<()>("words-added", &[]);
+        self.imp()
+            .toast_overlay
+            .add_toast(adw::Toast::new(&format!("Added {} placeholder words", n)));
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn handle_list_selected(&self, _pspec: glib::ParamSpec, _dropdown: &gtk::DropDown) {
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn handle_add_all_clicked(&self) {
+        let missing = self
+            .selected_list()
+            .and_then(|list| {
+                self.project_model().query(|project| {
+                    list.coverage(project.language().dictionary())
+                        .missing()
+                        .collect::<Vec<_>>()
+                })
+            })
+            .unwrap_or_default();
+        self.add_placeholders(missing);
+    }
+
+    #[template_callback]
+    fn handle_import_clicked(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Import Concept List")
+            .accept_label("_Import")
+            .build();
+
+        dialog.open(
+            Some(self),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as window => move |response| {
+                match response {
+                    Ok(f) => {
+                        let name = f
+                            .path()
+                            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                            .unwrap_or_else(|| "Imported".to_string());
+                        match f.load_contents(Option::<&gio::Cancellable>::None) {
+                            Ok((bytes, _)) => {
+                                let text = String::from_utf8_lossy(&bytes);
+                                window.add_list(ConceptList::from_text(name, &text));
+                                let imp = window.imp();
+                                imp.list_dropdown.set_selected(imp.list_names.n_items() - 1);
+                            }
+                            Err(e) => log::error!("Import concept list error: {e:?}"),
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Import dialog dismissed"),
+                        _ => log::error!("Import dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+}
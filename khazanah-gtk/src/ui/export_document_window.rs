@@ -0,0 +1,159 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use khazanah_core::interchange::{self, DocumentOptions, ALL_DOCUMENT_FORMATS};
+
+use crate::models;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::ExportDocumentWindow)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/export_document_window.ui")]
+    pub struct ExportDocumentWindow {
+        #[template_child]
+        pub format_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub approved_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        pub reverse_index_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ExportDocumentWindow {
+        const NAME: &'static str = "KhzExportDocumentWindow";
+        type Type = super::ExportDocumentWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ExportDocumentWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let names = ALL_DOCUMENT_FORMATS
+                .iter()
+                .map(|f| f.name())
+                .collect::<Vec<_>>();
+            self.format_dropdown
+                .set_model(Some(&gtk::StringList::new(&names)));
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for ExportDocumentWindow {}
+
+    impl WindowImpl for ExportDocumentWindow {}
+    impl AdwWindowImpl for ExportDocumentWindow {}
+}
+
+glib::wrapper! {
+    /// Exports the dictionary as a typeset document in a chosen format.
+    pub struct ExportDocumentWindow(ObjectSubclass<imp::ExportDocumentWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+#[gtk::template_callbacks]
+impl ExportDocumentWindow {
+    pub fn new(project_model: &models::ProjectModel) -> Self {
+        glib::Object::builder()
+            .property("project-model", project_model)
+            .build()
+    }
+
+    /// Gets the options chosen in the window.
+    fn options(&self) -> DocumentOptions {
+        let imp = self.imp();
+        DocumentOptions {
+            format: ALL_DOCUMENT_FORMATS
+                .get(imp.format_dropdown.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            approved_only: imp.approved_switch.is_active(),
+            reverse_index: imp.reverse_index_switch.is_active(),
+        }
+    }
+
+    /// Exports the dictionary to `path`, and closes the window if successful.
+    fn export(&self, path: std::path::PathBuf, options: &DocumentOptions) {
+        let result = self.project_model().query(|project| {
+            let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            interchange::export_document(project.language(), file, options)
+        });
+        match result {
+            Some(Ok(n)) => {
+                log::info!("Exported {} words to {:?}", n, path);
+                self.close();
+            }
+            Some(Err(e)) => {
+                log::error!("Export document error: {e:?}");
+                self.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new(&format!("Cannot export document: {e}")));
+            }
+            None => {}
+        }
+    }
+
+    #[template_callback]
+    fn handle_export_clicked(&self) {
+        let options = self.options();
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Document")
+            .accept_label("_Export")
+            .initial_name(format!("dictionary.{}", options.format.extension()))
+            .build();
+
+        dialog.save(
+            Some(self),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as window => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            window.export(path, &options);
+                        } else {
+                            log::error!("Export dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Export dialog dismissed"),
+                        _ => log::error!("Export dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+}
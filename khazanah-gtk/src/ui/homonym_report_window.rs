@@ -0,0 +1,187 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use adw::subclass::prelude::*;
+
+use khazanah_core::Dictionary;
+use uuid::Uuid;
+
+use crate::models;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    use gtk::glib::subclass::Signal;
+    use once_cell::sync::Lazy;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::HomonymReportWindow)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/homonym_report_window.ui")]
+    pub struct HomonymReportWindow {
+        #[template_child]
+        pub distance_spin_button: TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub report_box: TemplateChild<gtk::Box>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HomonymReportWindow {
+        const NAME: &'static str = "KhzHomonymReportWindow";
+        type Type = super::HomonymReportWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HomonymReportWindow {
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: Lazy<Vec<Signal>> = Lazy::new(|| {
+                vec![Signal::builder("word-selected")
+                    .param_types([String::static_type()])
+                    .build()]
+            });
+            SIGNALS.as_ref()
+        }
+    }
+
+    impl WidgetImpl for HomonymReportWindow {}
+
+    impl WindowImpl for HomonymReportWindow {}
+    impl AdwWindowImpl for HomonymReportWindow {}
+}
+
+glib::wrapper! {
+    /// Shows homonyms and near-duplicate words of the dictionary.
+    pub struct HomonymReportWindow(ObjectSubclass<imp::HomonymReportWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+#[gtk::template_callbacks]
+impl HomonymReportWindow {
+    pub fn new(project_model: &models::ProjectModel) -> Self {
+        let ret: Self = glib::Object::builder()
+            .property("project-model", project_model)
+            .build();
+        ret.refresh();
+        ret
+    }
+
+    /// Recomputes the report.
+    pub fn refresh(&self) {
+        let imp = self.imp();
+
+        while let Some(child) = imp.report_box.first_child() {
+            imp.report_box.remove(&child);
+        }
+
+        let max_distance = imp.distance_spin_button.value_as_int().max(0) as usize;
+        let sections = self
+            .project_model()
+            .query(|project| {
+                let language = project.language();
+                let dictionary = language.dictionary();
+                let report = language.dictionary_homonyms(max_distance);
+
+                let mut sections = report
+                    .groups
+                    .iter()
+                    .map(|group| {
+                        let words = group
+                            .word_ids
+                            .iter()
+                            .map(|id| (*id, word_label(dictionary, id)))
+                            .collect::<Vec<_>>();
+                        (
+                            format!("{} homonyms: {}", group.kind.name(), group.key),
+                            words,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                for pair in report.near_pairs.iter() {
+                    let words = pair
+                        .word_ids
+                        .iter()
+                        .map(|id| (*id, word_label(dictionary, id)))
+                        .collect::<Vec<_>>();
+                    sections.push((
+                        format!("Near-duplicates, distance {}", pair.distance),
+                        words,
+                    ));
+                }
+                sections
+            })
+            .unwrap_or_default();
+
+        imp.stack.set_visible_child_name(match sections.is_empty() {
+            true => "empty",
+            false => "report",
+        });
+
+        for (heading, words) in sections {
+            let label = gtk::Label::builder()
+                .label(heading)
+                .halign(gtk::Align::Start)
+                .build();
+            label.add_css_class("heading");
+            imp.report_box.append(&label);
+
+            let list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .valign(gtk::Align::Start)
+                .build();
+            list.add_css_class("boxed-list");
+            for (id, (title, subtitle)) in words {
+                let row = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(&title))
+                    .subtitle(glib::markup_escape_text(&subtitle))
+                    .activatable(true)
+                    .build();
+                row.connect_activated(glib::clone!(@weak self as window => move |_| {
+                    window.emit_by_name::<()>("word-selected", &[&id.to_string()]);
+                }));
+                list.append(&row);
+            }
+            imp.report_box.append(&list);
+        }
+    }
+
+    #[template_callback]
+    fn handle_distance_changed(&self, _button: &gtk::SpinButton) {
+        self.refresh();
+    }
+}
+
+/// Gets the romanization and the translation of word of id `id`.
+fn word_label(dictionary: &Dictionary, id: &Uuid) -> (String, String) {
+    dictionary
+        .word_by_id(*id)
+        .map(|w| (w.romanization().to_string(), w.translation().to_string()))
+        .unwrap_or_default()
+}
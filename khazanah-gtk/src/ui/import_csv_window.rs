@@ -0,0 +1,387 @@
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use khazanah_core::interchange::{
+    self, CsvOptions, CsvTable, ImportAction, ALL_CSV_COLUMNS, ALL_DUPLICATE_POLICIES,
+};
+
+use crate::models;
+
+/// The maximum number of words shown in the preview.
+const MAX_PREVIEW_ROWS: usize = 20;
+
+/// Delimiters, in the order of `delimiter_dropdown`.
+const DELIMITERS: &[u8] = &[b',', b';', b'\t'];
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    use gtk::glib::subclass::{Signal, SignalType};
+    use once_cell::sync::Lazy;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::ImportCsvWindow)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/import_csv_window.ui")]
+    pub struct ImportCsvWindow {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub import_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub delimiter_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub header_switch: TemplateChild<gtk::Switch>,
+        #[template_child]
+        pub duplicates_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub column_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub preview_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub preview_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+
+        /// The contents of the chosen file.
+        pub text: RefCell<String>,
+        pub table: RefCell<CsvTable>,
+        pub options: RefCell<CsvOptions>,
+        /// Mapping rows, in the order of columns.
+        pub column_rows: RefCell<Vec<adw::ComboRow>>,
+        /// Set while widgets are filled from the options, to ignore their signals.
+        pub filling: Cell<bool>,
+        /// Set once the chosen file is imported, so that it is not imported twice.
+        pub imported: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImportCsvWindow {
+        const NAME: &'static str = "KhzImportCsvWindow";
+        type Type = super::ImportCsvWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for ImportCsvWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let names = ALL_DUPLICATE_POLICIES
+                .iter()
+                .map(|p| p.name())
+                .collect::<Vec<_>>();
+            self.duplicates_dropdown
+                .set_model(Some(&gtk::StringList::new(&names)));
+            self.stack.set_visible_child_name("empty");
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: Lazy<Vec<Signal>> = Lazy::new(|| {
+                vec![Signal::builder("words-added")
+                    .param_types(Vec::<SignalType>::new())
+                    .build()]
+            });
+            SIGNALS.as_ref()
+        }
+    }
+
+    impl WidgetImpl for ImportCsvWindow {}
+
+    impl WindowImpl for ImportCsvWindow {}
+    impl AdwWindowImpl for ImportCsvWindow {}
+}
+
+glib::wrapper! {
+    /// Imports words from a spreadsheet, with a column mapping and a preview.
+    pub struct ImportCsvWindow(ObjectSubclass<imp::ImportCsvWindow>)
+        @extends gtk::Widget, gtk::Window, adw::Window;
+}
+
+#[gtk::template_callbacks]
+impl ImportCsvWindow {
+    pub fn new(project_model: &models::ProjectModel) -> Self {
+        glib::Object::builder()
+            .property("project-model", project_model)
+            .build()
+    }
+
+    /// Loads the contents of a file, guessing its format and column mapping.
+    fn load_text(&self, text: String) {
+        let imp = self.imp();
+
+        match CsvTable::read_detect(&text) {
+            Ok((table, options)) => {
+                imp.filling.set(true);
+                imp.delimiter_dropdown.set_selected(
+                    DELIMITERS
+                        .iter()
+                        .position(|&d| d == options.delimiter)
+                        .unwrap_or_default() as u32,
+                );
+                imp.header_switch.set_active(options.has_header);
+                imp.filling.set(false);
+
+                imp.text.replace(text);
+                imp.table.replace(table);
+                imp.options.replace(options);
+                imp.imported.set(false);
+
+                imp.stack.set_visible_child_name("mapping");
+                self.fill_columns();
+                self.fill_preview();
+            }
+            Err(e) => {
+                log::error!("Import spreadsheet error: {e:?}");
+                imp.toast_overlay
+                    .add_toast(adw::Toast::new(&format!("Cannot read file: {e}")));
+            }
+        }
+    }
+
+    /// Fills the mapping rows, one for each column.
+    fn fill_columns(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.column_list.row_at_index(0) {
+            imp.column_list.remove(&row);
+        }
+
+        let names = ALL_CSV_COLUMNS.iter().map(|c| c.name()).collect::<Vec<_>>();
+        let table = imp.table.borrow();
+        let columns = imp.options.borrow().columns.clone();
+        let mut rows = Vec::new();
+        for i in 0..table.n_columns() {
+            let title = table
+                .header
+                .as_ref()
+                .and_then(|h| h.get(i))
+                .filter(|s| !s.is_empty())
+                .cloned()
+                .unwrap_or_else(|| format!("Column {}", i + 1));
+            let sample = table
+                .rows
+                .first()
+                .and_then(|r| r.get(i))
+                .cloned()
+                .unwrap_or_default();
+
+            let row = adw::ComboRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(glib::markup_escape_text(&sample).as_str())
+                .model(&gtk::StringList::new(&names))
+                .build();
+            row.set_selected(
+                columns
+                    .get(i)
+                    .and_then(|c| ALL_CSV_COLUMNS.iter().position(|x| x == c))
+                    .unwrap_or_default() as u32,
+            );
+            row.connect_selected_notify(glib::clone!(@weak self as window => move |_| {
+                window.handle_mapping_changed();
+            }));
+
+            imp.column_list.append(&row);
+            rows.push(row);
+        }
+        imp.column_rows.replace(rows);
+    }
+
+    /// Shows what importing would do, without changing the dictionary.
+    fn fill_preview(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.preview_list.row_at_index(0) {
+            imp.preview_list.remove(&row);
+        }
+
+        let options = imp.options.borrow().clone();
+        let words = imp.table.borrow().words(&options);
+        let preview = self
+            .project_model()
+            .query(|project| {
+                interchange::preview_import(
+                    project.language().dictionary(),
+                    words.clone(),
+                    options.duplicates,
+                )
+            })
+            .unwrap_or_default();
+
+        let count = |f: fn(&ImportAction) -> bool| preview.iter().filter(|(_, a)| f(a)).count();
+        imp.preview_label.set_label(&format!(
+            "Preview: {} new, {} merged, {} skipped",
+            count(|a| matches!(a, ImportAction::Add)),
+            count(|a| matches!(a, ImportAction::Merge(_))),
+            count(|a| matches!(a, ImportAction::Skip(_))),
+        ));
+        imp.import_button
+            .set_sensitive(!preview.is_empty() && !imp.imported.get());
+
+        for (word, action) in preview.iter().take(MAX_PREVIEW_ROWS) {
+            let subtitle = [word.pronunciation(), word.translation()]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" · ");
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(word.romanization()).as_str())
+                .subtitle(glib::markup_escape_text(&subtitle).as_str())
+                .build();
+
+            let label = gtk::Label::new(Some(match action {
+                ImportAction::Add => "New",
+                ImportAction::Merge(_) => "Merge",
+                ImportAction::Skip(_) => "Skip",
+            }));
+            label.add_css_class("caption");
+            if !matches!(action, ImportAction::Add) {
+                label.add_css_class("dim-label");
+            }
+            row.add_suffix(&label);
+
+            imp.preview_list.append(&row);
+        }
+    }
+
+    /// Responds to changes of the delimiter and the header switch by reading the file again.
+    #[template_callback]
+    fn handle_format_changed(&self) {
+        let imp = self.imp();
+        if imp.filling.get() || imp.text.borrow().is_empty() {
+            return;
+        }
+
+        let mut options = imp.options.borrow().clone();
+        options.delimiter = DELIMITERS
+            .get(imp.delimiter_dropdown.selected() as usize)
+            .copied()
+            .unwrap_or(b',');
+        options.has_header = imp.header_switch.is_active();
+
+        let table = CsvTable::read(imp.text.borrow().as_bytes(), &options);
+        match table {
+            Ok(table) => {
+                options
+                    .columns
+                    .resize(table.n_columns(), interchange::CsvColumn::Ignore);
+                imp.table.replace(table);
+                imp.options.replace(options);
+                self.fill_columns();
+                self.fill_preview();
+            }
+            Err(e) => log::error!("Import spreadsheet error: {e:?}"),
+        }
+    }
+
+    /// Responds to changes of the column mapping and the duplicate handling.
+    #[template_callback]
+    fn handle_mapping_changed(&self) {
+        let imp = self.imp();
+        {
+            let mut options = imp.options.borrow_mut();
+            options.columns = imp
+                .column_rows
+                .borrow()
+                .iter()
+                .map(|row| {
+                    ALL_CSV_COLUMNS
+                        .get(row.selected() as usize)
+                        .copied()
+                        .unwrap_or(interchange::CsvColumn::Ignore)
+                })
+                .collect();
+            options.duplicates = ALL_DUPLICATE_POLICIES
+                .get(imp.duplicates_dropdown.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+        }
+        self.fill_preview();
+    }
+
+    #[template_callback]
+    fn handle_import_clicked(&self) {
+        let imp = self.imp();
+        let options = imp.options.borrow().clone();
+        let words = imp.table.borrow().words(&options);
+
+        let summary = self.project_model().update(|project| {
+            let dictionary = project.language_mut().dictionary_mut();
+            let preview =
+                interchange::preview_import(dictionary, words.clone(), options.duplicates);
+            interchange::apply_import(dictionary, preview)
+        });
+
+        if let Some(summary) = summary {
+            imp.imported.set(true);
+            self.emit_by_name::<()>("words-added", &[]);
+            imp.toast_overlay.add_toast(adw::Toast::new(&format!(
+                "Added {} words, merged {}, skipped {}",
+                summary.added, summary.merged, summary.skipped
+            )));
+            self.fill_preview();
+        }
+    }
+
+    #[template_callback]
+    fn handle_open_clicked(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Spreadsheets"));
+        filter.add_suffix("csv");
+        filter.add_suffix("tsv");
+        filter.add_suffix("txt");
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Import Spreadsheet")
+            .accept_label("_Open")
+            .default_filter(&filter)
+            .build();
+
+        dialog.open(
+            Some(self),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as window => move |response| {
+                match response {
+                    Ok(f) => match f.load_contents(Option::<&gio::Cancellable>::None) {
+                        Ok((bytes, _)) => {
+                            window.load_text(String::from_utf8_lossy(&bytes).to_string());
+                        }
+                        Err(e) => log::error!("Import spreadsheet error: {e:?}"),
+                    },
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Import dialog dismissed"),
+                        _ => log::error!("Import dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+}
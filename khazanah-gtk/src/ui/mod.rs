@@ -18,12 +18,20 @@ mod view_switcher_dropdown;
 mod start_view;
 mod view;
 
+mod concept_coverage_window;
+mod export_document_window;
+mod homonym_report_window;
+mod import_csv_window;
 mod ipa_chart_view_window;
 mod xsampa_view_window;
 
 pub use add_phoneme_button::AddPhonemeButton;
 pub use add_phoneme_row::AddPhonemeRow;
+pub use concept_coverage_window::ConceptCoverageWindow;
+pub use export_document_window::ExportDocumentWindow;
 pub use header_bar::HeaderBar;
+pub use homonym_report_window::HomonymReportWindow;
+pub use import_csv_window::ImportCsvWindow;
 pub use ipa_chart::IpaChart;
 pub use ipa_chart_view_window::IpaChartViewWindow;
 pub use main_menu_button::MainMenuButton;
@@ -31,7 +39,9 @@ pub use start_view::StartView;
 pub use text_area_row::TextAreaRow;
 pub use toolbar_end_controls::ToolbarEndControls;
 pub use toolbar_start_controls::ToolbarStartControls;
-pub use view::{DictionaryView, InventoryView, LanguageView, MainView, View};
+pub use view::{
+    CorpusView, DictionaryView, InventoryView, LanguageView, MainView, StatisticsView, View,
+};
 pub use view_switcher_dropdown::ViewSwitcherDropDown;
 pub use window::ApplicationWindow;
 pub use xsampa_view_window::XSampaViewWindow;
@@ -1,12 +1,16 @@
 use std::fmt::{Debug, Display};
 
+pub use corpus::CorpusView;
 pub use dictionary::DictionaryView;
 pub use inventory::InventoryView;
 pub use language::LanguageView;
+pub use statistics::StatisticsView;
 
+mod corpus;
 mod dictionary;
 mod inventory;
 mod language;
+mod statistics;
 
 /// View that loads and commits its state to a model.
 pub trait View {
@@ -37,13 +41,21 @@ pub enum MainView {
     Language = 0,
     Inventory = 1,
     Dictionary = 2,
+    Corpus = 3,
+    Statistics = 4,
 
     #[default]
     Unknown = u32::MAX,
 }
 
 impl MainView {
-    pub const SELECTABLES: &[Self] = &[Self::Language, Self::Inventory, Self::Dictionary];
+    pub const SELECTABLES: &[Self] = &[
+        Self::Language,
+        Self::Inventory,
+        Self::Dictionary,
+        Self::Corpus,
+        Self::Statistics,
+    ];
 }
 
 impl From<u32> for MainView {
@@ -71,6 +83,8 @@ impl Display for MainView {
             Self::Language => write!(f, "Language"),
             Self::Inventory => write!(f, "Inventory"),
             Self::Dictionary => write!(f, "Dictionary"),
+            Self::Corpus => write!(f, "Corpus"),
+            Self::Statistics => write!(f, "Statistics"),
             Self::Unknown => write!(f, "Unknown"),
         }
     }
@@ -0,0 +1,473 @@
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use khazanah_core::corpus::{self, Sentence, Text};
+use uuid::Uuid;
+
+use crate::models;
+use crate::ui;
+
+/// Delay after the last edit of a source line before its words are looked up again.
+const LOOKUP_DELAY: Duration = Duration::from_millis(500);
+
+#[doc(hidden)]
+pub mod imp {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::CorpusView)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/view/corpus.ui")]
+    pub struct CorpusView {
+        #[template_child]
+        pub text_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub delete_text_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub content_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub title_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub sentence_list: TemplateChild<gtk::ListBox>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+
+        /// Ids of the texts, in the order of `text_list` rows.
+        pub text_ids: RefCell<Vec<Uuid>>,
+
+        pub selected_text_id: Cell<Option<Uuid>>,
+
+        #[property(get, set)]
+        pub bound: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CorpusView {
+        const NAME: &'static str = "KhzCorpusView";
+        type Type = super::CorpusView;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CorpusView {
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for CorpusView {}
+    impl BinImpl for CorpusView {}
+}
+
+glib::wrapper! {
+    /// The view to write example texts as interlinear glossed text.
+    pub struct CorpusView(ObjectSubclass<imp::CorpusView>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[gtk::template_callbacks]
+impl CorpusView {
+    /// Fills the text list from the project.
+    fn load_texts(&self) {
+        let imp = self.imp();
+
+        let mut texts = self
+            .project_model()
+            .query(|project| {
+                project
+                    .corpus()
+                    .iter_texts()
+                    .filter_map(|text| Some((text.id()?, text.title().to_string())))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        texts.sort_by(|a, b| a.1.cmp(&b.1));
+
+        while let Some(row) = imp.text_list.row_at_index(0) {
+            imp.text_list.remove(&row);
+        }
+
+        for (_, title) in texts.iter() {
+            let label = gtk::Label::builder()
+                .label(Self::display_title(title))
+                .halign(gtk::Align::Start)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .build();
+            imp.text_list.append(&label);
+        }
+
+        imp.text_ids
+            .replace(texts.into_iter().map(|(id, _)| id).collect());
+
+        let selected = imp.selected_text_id.get();
+        let idx = selected.and_then(|id| imp.text_ids.borrow().iter().position(|&x| x == id));
+        match idx.and_then(|i| imp.text_list.row_at_index(i as i32)) {
+            Some(row) => imp.text_list.select_row(Some(&row)),
+            None => self.select_text(None),
+        }
+    }
+
+    fn display_title(title: &str) -> &str {
+        if title.is_empty() {
+            "Untitled"
+        } else {
+            title
+        }
+    }
+
+    /// Shows the text of id `id` in the content pane.
+    fn select_text(&self, id: Option<Uuid>) {
+        let imp = self.imp();
+        imp.selected_text_id.set(id);
+        imp.delete_text_button.set_sensitive(id.is_some());
+
+        let Some(title) = id.and_then(|id| {
+            self.project_model().query(|project| {
+                project
+                    .corpus()
+                    .text_by_id(id)
+                    .map(|text| text.title().to_string())
+            })?
+        }) else {
+            imp.content_stack.set_visible_child_name("empty");
+            return;
+        };
+
+        self.set_bound(false);
+        imp.title_entry.set_text(&title);
+        self.load_sentences();
+        self.set_bound(true);
+
+        imp.content_stack.set_visible_child_name("text");
+    }
+
+    /// Fills the sentence list from the selected text.
+    fn load_sentences(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.sentence_list.row_at_index(0) {
+            imp.sentence_list.remove(&row);
+        }
+
+        let sentences = self
+            .query_text(|text| text.sentences().to_vec())
+            .unwrap_or_default();
+
+        for (i, sentence) in sentences.iter().enumerate() {
+            imp.sentence_list
+                .append(&self.build_sentence_row(i, sentence));
+        }
+    }
+
+    /// Queries the selected text.
+    fn query_text<F, O>(&self, f: F) -> Option<O>
+    where
+        F: Fn(&Text) -> O,
+    {
+        let id = self.imp().selected_text_id.get()?;
+        self.project_model()
+            .query(|project| project.corpus().text_by_id(id).map(&f))?
+    }
+
+    /// Updates the selected text.
+    fn update_text<F, O>(&self, f: F) -> Option<O>
+    where
+        F: Fn(&mut Text) -> O,
+    {
+        let id = self.imp().selected_text_id.get()?;
+        self.project_model()
+            .update(|project| project.corpus_mut().text_by_id_mut(id).map(&f))?
+    }
+
+    /// Builds an editable row for the sentence at `index`.
+    fn build_sentence_row(&self, index: usize, sentence: &Sentence) -> adw::ExpanderRow {
+        let row = adw::ExpanderRow::builder()
+            .title(glib::markup_escape_text(&sentence.source))
+            .subtitle(glib::markup_escape_text(&sentence.translation))
+            .build();
+
+        let gloss_box = gtk::FlowBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .column_spacing(12)
+            .row_spacing(6)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+        // Looking up the words is slow, so it is done once here, then after the source line is
+        // left unchanged for a moment.
+        let unknown = Rc::new(RefCell::new(self.unknown_tokens(&sentence.source)));
+        let lookup: Rc<RefCell<Option<glib::SourceId>>> = Rc::default();
+        self.fill_gloss_box(&gloss_box, sentence, &unknown.borrow());
+
+        let fields: [(&str, fn(&mut Sentence) -> &mut String, &str); 4] = [
+            ("Source", |s| &mut s.source, &sentence.source),
+            ("Segmented", |s| &mut s.segmented, &sentence.segmented),
+            ("Gloss", |s| &mut s.gloss, &sentence.gloss),
+            ("Translation", |s| &mut s.translation, &sentence.translation),
+        ];
+
+        let mut entries = Vec::new();
+        for (title, field, value) in fields {
+            let entry = adw::EntryRow::builder().title(title).text(value).build();
+            let is_source = title == "Source";
+            entry.connect_changed(glib::clone!(
+                @weak self as view, @weak row, @weak gloss_box,
+                @strong unknown, @strong lookup => move |entry| {
+                    if !view.bound() {
+                        return;
+                    }
+                    let text = entry.text().to_string();
+                    let sentence = view.update_text(|t| {
+                        let s = t.sentence_mut(index)?;
+                        *field(s) = text.clone();
+                        // Keep the links on their tokens when the source line changes.
+                        s.relink();
+                        Some(s.clone())
+                    }).flatten();
+                    if let Some(sentence) = sentence {
+                        row.set_title(&glib::markup_escape_text(&sentence.source));
+                        row.set_subtitle(&glib::markup_escape_text(&sentence.translation));
+                        view.fill_gloss_box(&gloss_box, &sentence, &unknown.borrow());
+                    }
+                    if is_source {
+                        view.schedule_lookup(index, &gloss_box, &unknown, &lookup);
+                    }
+                }
+            ));
+            row.add_row(&entry);
+            entries.push(entry);
+        }
+
+        let gloss_row = gtk::ListBoxRow::builder()
+            .activatable(false)
+            .child(&gloss_box)
+            .build();
+        row.add_row(&gloss_row);
+
+        let gloss_button = gtk::Button::builder()
+            .icon_name("tools-check-spelling-symbolic")
+            .tooltip_text("Propose Gloss")
+            .valign(gtk::Align::Center)
+            .build();
+        gloss_button.add_css_class("flat");
+        let (segmented_entry, gloss_entry) = (&entries[1], &entries[2]);
+        gloss_button.connect_clicked(glib::clone!(
+            @weak self as view, @weak segmented_entry, @weak gloss_entry,
+            @strong unknown, @strong lookup => move |_| {
+                let Some(proposal) = view
+                    .query_text(|t| t.sentence(index).map(|s| s.source.clone()))
+                    .flatten()
+                    .and_then(|source| view.propose_gloss(&source))
+                else {
+                    return;
+                };
+                if let Some(id) = lookup.take() {
+                    id.remove();
+                }
+                unknown.replace(proposal.unknown_indices().collect());
+                view.update_text(|t| {
+                    if let Some(s) = t.sentence_mut(index) {
+                        s.set_links(proposal.tokens.iter().map(|t| t.word_id()));
+                    }
+                });
+                // Setting the entries also updates the model.
+                segmented_entry.set_text(&proposal.segmented_line());
+                gloss_entry.set_text(&proposal.gloss_line());
+            }
+        ));
+        row.add_action(&gloss_button);
+
+        let delete_button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text("Delete Sentence")
+            .valign(gtk::Align::Center)
+            .build();
+        delete_button.add_css_class("flat");
+        delete_button.connect_clicked(glib::clone!(@weak self as view => move |_| {
+            view.update_text(|t| t.remove_sentence(index));
+            view.load_sentences();
+        }));
+        row.add_action(&delete_button);
+
+        row
+    }
+
+    /// Proposes a gloss for a sentence typed in the language.
+    fn propose_gloss(&self, source: &str) -> Option<corpus::GlossProposal> {
+        self.project_model().query(|project| {
+            let lang = project.language();
+            corpus::propose_gloss(
+                source,
+                lang.dictionary(),
+                lang.phoneme_categories(),
+                lang.phonemic_inventory(),
+            )
+        })
+    }
+
+    /// Gets the indices of the tokens of `source` not found in the dictionary.
+    fn unknown_tokens(&self, source: &str) -> Vec<usize> {
+        self.propose_gloss(source)
+            .map(|p| p.unknown_indices().collect())
+            .unwrap_or_default()
+    }
+
+    /// Looks up the words of the sentence at `index` again after `LOOKUP_DELAY`, replacing the
+    /// pending lookup if any, then refills `gloss_box`.
+    fn schedule_lookup(
+        &self,
+        index: usize,
+        gloss_box: &gtk::FlowBox,
+        unknown: &Rc<RefCell<Vec<usize>>>,
+        lookup: &Rc<RefCell<Option<glib::SourceId>>>,
+    ) {
+        if let Some(id) = lookup.take() {
+            id.remove();
+        }
+        let id = glib::timeout_add_local_once(
+            LOOKUP_DELAY,
+            glib::clone!(@weak self as view, @weak gloss_box,
+                @strong unknown, @strong lookup => move || {
+                lookup.replace(None);
+                let Some(sentence) = view.query_text(|t| t.sentence(index).cloned()).flatten() else {
+                    return;
+                };
+                unknown.replace(view.unknown_tokens(&sentence.source));
+                view.fill_gloss_box(&gloss_box, &sentence, &unknown.borrow());
+            }),
+        );
+        lookup.replace(Some(id));
+    }
+
+    /// Fills `gloss_box` with the lines of `sentence` aligned word by word. Words at `unknown`,
+    /// not found in the dictionary, are highlighted.
+    fn fill_gloss_box(&self, gloss_box: &gtk::FlowBox, sentence: &Sentence, unknown: &[usize]) {
+        while let Some(child) = gloss_box.child_at_index(0) {
+            gloss_box.remove(&child);
+        }
+
+        for (n, column) in sentence.aligned().into_iter().enumerate() {
+            let vbox = gtk::Box::new(gtk::Orientation::Vertical, 2);
+            for (i, word) in column.into_iter().enumerate() {
+                let label = gtk::Label::builder()
+                    .label(word)
+                    .halign(gtk::Align::Start)
+                    .selectable(true)
+                    .build();
+                match i {
+                    0 if unknown.contains(&n) => {
+                        label.add_css_class("heading");
+                        label.add_css_class("error");
+                        label.set_tooltip_text(Some("Not found in the dictionary"));
+                    }
+                    0 => label.add_css_class("heading"),
+                    2 => label.add_css_class("caption"),
+                    _ => label.add_css_class("dim-label"),
+                }
+                vbox.append(&label);
+            }
+            gloss_box.insert(&vbox, -1);
+        }
+    }
+
+    #[template_callback]
+    fn handle_text_list_row_selected(&self, row: Option<&gtk::ListBoxRow>) {
+        let id = row.and_then(|row| {
+            self.imp()
+                .text_ids
+                .borrow()
+                .get(row.index() as usize)
+                .copied()
+        });
+        self.select_text(id);
+    }
+
+    #[template_callback]
+    fn handle_add_text(&self) {
+        let id = self
+            .project_model()
+            .update(|project| project.corpus_mut().add_text(Text::new()));
+        self.imp().selected_text_id.set(id);
+        self.load_texts();
+    }
+
+    #[template_callback]
+    fn handle_delete_text(&self) {
+        if let Some(id) = self.imp().selected_text_id.get() {
+            self.project_model()
+                .update(|project| project.corpus_mut().remove_text_by_id(id));
+            self.load_texts();
+        }
+    }
+
+    #[template_callback]
+    fn handle_title_changed(&self, entry: &adw::EntryRow) {
+        if !self.bound() {
+            return;
+        }
+        let title = entry.text().to_string();
+        self.update_text(|t| t.set_title(title.clone()));
+
+        let imp = self.imp();
+        if let Some(label) = imp
+            .text_list
+            .selected_row()
+            .and_then(|row| row.child())
+            .and_then(|child| child.downcast::<gtk::Label>().ok())
+        {
+            label.set_label(Self::display_title(&title));
+        }
+    }
+
+    #[template_callback]
+    fn handle_add_sentence(&self) {
+        self.update_text(|t| t.add_sentence(Sentence::new()));
+        self.set_bound(false);
+        self.load_sentences();
+        self.set_bound(true);
+    }
+}
+
+impl ui::View for CorpusView {
+    fn load_state(&self) {
+        log::debug!("Loading view state.");
+
+        self.load_texts();
+    }
+
+    fn unload_state(&self) {
+        log::debug!("Unloading view state.");
+
+        self.set_bound(false);
+        self.imp().selected_text_id.set(None);
+    }
+}
@@ -1,9 +1,12 @@
+use gtk::glib::FromVariant;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
-use gtk::{gdk, glib};
+use gtk::{gdk, gio, glib};
 
 use adw::subclass::prelude::*;
 
+use khazanah_core::interchange::{self, CsvColumn, CsvOptions, DuplicatePolicy, LiftOptions};
+
 use crate::models;
 use crate::ui;
 
@@ -53,6 +56,47 @@ mod imp {
                 view.navigate_backward();
             });
 
+            klass.install_action("dictionary.select-word", Some("s"), move |view, _, v| {
+                if let Some(id) = v
+                    .and_then(String::from_variant)
+                    .and_then(|s| uuid::Uuid::try_parse(&s).ok())
+                {
+                    view.imp().sidebar.select_word_by_id(id);
+                }
+            });
+
+            klass.install_action("dictionary.show-coverage", None, move |view, _, _| {
+                view.show_coverage_window();
+            });
+
+            klass.install_action("dictionary.show-homonyms", None, move |view, _, _| {
+                view.show_homonyms_window();
+            });
+
+            klass.install_action("dictionary.import-csv", None, move |view, _, _| {
+                view.show_import_csv_window();
+            });
+
+            klass.install_action("dictionary.export-csv", Some("b"), move |view, _, v| {
+                if let Some(approved_only) = v.and_then(bool::from_variant) {
+                    view.export_csv_dialog(approved_only);
+                }
+            });
+
+            klass.install_action("dictionary.export-document", None, move |view, _, _| {
+                view.show_export_document_window();
+            });
+
+            klass.install_action("dictionary.import-lift", None, move |view, _, _| {
+                view.import_lift_dialog();
+            });
+
+            klass.install_action("dictionary.export-lift", Some("b"), move |view, _, v| {
+                if let Some(approved_only) = v.and_then(bool::from_variant) {
+                    view.export_lift_dialog(approved_only);
+                }
+            });
+
             klass.add_binding_action(
                 gdk::Key::Escape,
                 gdk::ModifierType::empty(),
@@ -184,6 +228,277 @@ impl DictionaryView {
         self.update_buttons_visibility();
     }
 
+    /// Shows the coverage of reference wordlists by the dictionary.
+    fn show_coverage_window(&self) {
+        let window = ui::ConceptCoverageWindow::new(&self.project_model());
+        if let Some(root) = self.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            window.set_transient_for(Some(&root));
+        }
+        window.connect_closure(
+            "words-added",
+            false,
+            glib::closure_local!(@weak-allow-none self as view => move |_: &ui::ConceptCoverageWindow| {
+                if let Some(view) = view {
+                    ui::View::load_state(&*view.imp().sidebar);
+                }
+            }),
+        );
+        window.set_visible(true);
+    }
+
+    /// Shows homonyms and near-duplicate words of the dictionary.
+    fn show_homonyms_window(&self) {
+        let window = ui::HomonymReportWindow::new(&self.project_model());
+        if let Some(root) = self.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            window.set_transient_for(Some(&root));
+        }
+        window.connect_closure(
+            "word-selected",
+            false,
+            glib::closure_local!(@weak-allow-none self as view => move |_: &ui::HomonymReportWindow, id: String| {
+                if let (Some(view), Ok(id)) = (view, uuid::Uuid::try_parse(&id)) {
+                    view.imp().sidebar.select_word_by_id(id);
+                }
+            }),
+        );
+        window.set_visible(true);
+    }
+
+    /// Shows the wizard to import words from a spreadsheet.
+    fn show_import_csv_window(&self) {
+        let window = ui::ImportCsvWindow::new(&self.project_model());
+        if let Some(root) = self.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            window.set_transient_for(Some(&root));
+        }
+        window.connect_closure(
+            "words-added",
+            false,
+            glib::closure_local!(@weak-allow-none self as view => move |_: &ui::ImportCsvWindow| {
+                if let Some(view) = view {
+                    ui::View::load_state(&*view.imp().sidebar);
+                }
+            }),
+        );
+        window.set_visible(true);
+    }
+
+    /// Shows the window to export the dictionary as a typeset document.
+    fn show_export_document_window(&self) {
+        let window = ui::ExportDocumentWindow::new(&self.project_model());
+        if let Some(root) = self.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            window.set_transient_for(Some(&root));
+        }
+        window.set_visible(true);
+    }
+
+    /// Shows `Save File` dialog to export the dictionary as a spreadsheet. The file is tab
+    /// separated if its name ends with `.tsv`.
+    fn export_csv_dialog(&self, approved_only: bool) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Spreadsheet")
+            .accept_label("_Export")
+            .initial_name("dictionary.csv")
+            .build();
+
+        dialog.save(
+            self.root()
+                .and_then(|r| r.downcast::<gtk::Window>().ok())
+                .as_ref(),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as view => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            view.export_csv(path, approved_only);
+                        } else {
+                            log::error!("Export dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Export dialog dismissed"),
+                        _ => log::error!("Export dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Exports the dictionary as a spreadsheet to `path`.
+    fn export_csv(&self, path: std::path::PathBuf, approved_only: bool) {
+        let tsv = path.extension().map(|e| e == "tsv").unwrap_or_default();
+        let options = CsvOptions {
+            columns: vec![
+                CsvColumn::Romanization,
+                CsvColumn::Pronunciation,
+                CsvColumn::XSampa,
+                CsvColumn::Translation,
+                CsvColumn::PartOfSpeech,
+                CsvColumn::Tags,
+                CsvColumn::Status,
+            ],
+            approved_only,
+            ..match tsv {
+                true => CsvOptions::tsv(),
+                false => CsvOptions::default(),
+            }
+        };
+
+        let result = self.project_model().query(|project| {
+            let file = std::fs::File::create(&path)?;
+            interchange::export_csv(project.language().dictionary(), file, &options)
+        });
+        match result {
+            Some(Ok(n)) => log::info!("Exported {} words to {:?}", n, path),
+            Some(Err(e)) => log::error!("Export spreadsheet error: {e:?}"),
+            None => {}
+        }
+    }
+
+    /// Shows `Open File` dialog to import words from a LIFT lexicon.
+    fn import_lift_dialog(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("LIFT Lexicons"));
+        filter.add_suffix("lift");
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Import LIFT Lexicon")
+            .accept_label("_Import")
+            .default_filter(&filter)
+            .build();
+
+        dialog.open(
+            self.root()
+                .and_then(|r| r.downcast::<gtk::Window>().ok())
+                .as_ref(),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as view => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            view.import_lift(path);
+                        } else {
+                            log::error!("Import dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Import dialog dismissed"),
+                        _ => log::error!("Import dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Imports words from the LIFT lexicon at `path`, skipping words that already exist, and
+    /// shows what was imported.
+    fn import_lift(&self, path: std::path::PathBuf) {
+        let result = self.project_model().update(|project| {
+            let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+            let language = project.language_mut();
+            let mut fields = language.field_definitions().clone();
+            let (words, report) =
+                interchange::import_lift(file, language.dictionary(), &mut fields)?;
+            *language.field_definitions_mut() = fields;
+            let dictionary = language.dictionary_mut();
+            let preview = interchange::preview_import(dictionary, words, DuplicatePolicy::Skip);
+            Ok::<_, interchange::Error>((interchange::apply_import(dictionary, preview), report))
+        });
+        let (heading, body) = match result {
+            Some(Ok((summary, report))) => {
+                for message in report.iter() {
+                    log::warn!("Import LIFT: {}", message);
+                }
+                log::info!(
+                    "Imported {} words from {:?}, skipped {}",
+                    summary.added,
+                    path,
+                    summary.skipped
+                );
+                ui::View::load_state(&*self.imp().sidebar);
+
+                let mut body = format!(
+                    "Added {} words, skipped {} duplicates.",
+                    summary.added, summary.skipped
+                );
+                if !report.is_empty() {
+                    body += "\n\nThe following could not be imported:\n";
+                    body += &report.iter().collect::<Vec<_>>().join("\n");
+                }
+                ("Imported LIFT Lexicon", body)
+            }
+            Some(Err(e)) => {
+                log::error!("Import LIFT error: {e:?}");
+                ("Unable to Import LIFT Lexicon", e.to_string())
+            }
+            None => return,
+        };
+
+        let dialog = adw::MessageDialog::builder()
+            .heading(heading)
+            .body(body)
+            .build();
+        if let Some(root) = self.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+            dialog.set_transient_for(Some(&root));
+        }
+        dialog.add_response("close", "_Close");
+        dialog.present();
+    }
+
+    /// Shows `Save File` dialog to export the dictionary as a LIFT lexicon.
+    fn export_lift_dialog(&self, approved_only: bool) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export LIFT Lexicon")
+            .accept_label("_Export")
+            .initial_name("dictionary.lift")
+            .build();
+
+        dialog.save(
+            self.root()
+                .and_then(|r| r.downcast::<gtk::Window>().ok())
+                .as_ref(),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as view => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            view.export_lift(path, approved_only);
+                        } else {
+                            log::error!("Export dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Export dialog dismissed"),
+                        _ => log::error!("Export dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Exports the dictionary as a LIFT lexicon to `path`.
+    fn export_lift(&self, path: std::path::PathBuf, approved_only: bool) {
+        let options = LiftOptions {
+            approved_only,
+            ..Default::default()
+        };
+
+        let result = self.project_model().query(|project| {
+            let file = std::fs::File::create(&path)?;
+            let language = project.language();
+            interchange::export_lift(
+                language.dictionary(),
+                language.field_definitions(),
+                file,
+                &options,
+            )
+        });
+        match result {
+            Some(Ok(n)) => log::info!("Exported {} words to {:?}", n, path),
+            Some(Err(e)) => log::error!("Export LIFT error: {e:?}"),
+            None => {}
+        }
+    }
+
     /// Updates visibility of some buttons.
     fn update_buttons_visibility(&self) {
         let imp = self.imp();
@@ -1,6 +1,6 @@
-use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
+use gtk::{gdk, gio, glib};
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
@@ -8,6 +8,9 @@ use adw::subclass::prelude::*;
 use crate::models;
 use crate::ui;
 
+use khazanah_core::lexicon::{Segmentation, ALL_AFFIX_KINDS, ALL_WORD_STATUSES};
+use khazanah_core::media::AssetKind;
+use khazanah_core::prelude::*;
 use khazanah_core::ALL_PARTS_OF_SPEECH;
 
 #[doc(hidden)]
@@ -32,6 +35,30 @@ mod imp {
         pub xsampa_entry: TemplateChild<adw::EntryRow>,
         #[template_child]
         pub pos_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub affix_kind_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub allomorphs_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub segmentation_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub segmentation_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub homonym_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub tags_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub status_dropdown: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub replaced_by_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub fields_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub fields_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub history_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub media_list: TemplateChild<gtk::ListBox>,
 
         #[property(get, set)]
         pub project_model: RefCell<models::ProjectModel>,
@@ -42,6 +69,8 @@ mod imp {
         pub fields_sensitive: Cell<bool>,
 
         pub form_bindings: RefCell<Vec<glib::Binding>>,
+        /// Handlers connected to the bound word, disconnected on unbind.
+        pub form_handlers: RefCell<Vec<(models::WordObject, glib::SignalHandlerId)>>,
     }
 
     impl Content {
@@ -50,6 +79,8 @@ mod imp {
             let obj = self.obj();
 
             obj.unbind();
+            obj.fill_fields(value.as_ref());
+            obj.fill_media(value.as_ref().map(|w| w.id()));
             if let Some(word) = &value {
                 obj.bind(word);
                 obj.set_fields_sensitive(true);
@@ -123,6 +154,17 @@ impl Content {
             .collect();
         let pos_model = gtk::StringList::new(&pos_list);
         imp.pos_dropdown.set_model(Some(&pos_model));
+
+        let affix_kind_list: Vec<&str> = ALL_AFFIX_KINDS
+            .iter()
+            .map(|kind| kind.map(|v| v.name()).unwrap_or("---"))
+            .collect();
+        let affix_kind_model = gtk::StringList::new(&affix_kind_list);
+        imp.affix_kind_dropdown.set_model(Some(&affix_kind_model));
+
+        let status_list: Vec<&str> = ALL_WORD_STATUSES.iter().map(|s| s.name()).collect();
+        let status_model = gtk::StringList::new(&status_list);
+        imp.status_dropdown.set_model(Some(&status_model));
     }
 
     /// Binds a word to form.
@@ -171,6 +213,356 @@ impl Content {
                 .bidirectional()
                 .build(),
         );
+
+        bindings.push(
+            word.bind_property("affix-kind", &imp.affix_kind_dropdown.get(), "selected")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("affix-kind", &imp.allomorphs_entry.get(), "visible")
+                .sync_create()
+                .transform_to(|_, i: u32| Some((i != 0).to_value()))
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("allomorphs", &imp.allomorphs_entry.get(), "text")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("tags", &imp.tags_entry.get(), "text")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("status", &imp.status_dropdown.get(), "selected")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("status", &imp.replaced_by_entry.get(), "visible")
+                .sync_create()
+                .transform_to(|_, i: u32| {
+                    ALL_WORD_STATUSES
+                        .get(i as usize)
+                        .map(|s| s.is_retired().to_value())
+                })
+                .build(),
+        );
+
+        bindings.push(
+            word.bind_property("replaced-by", &imp.replaced_by_entry.get(), "text")
+                .sync_create()
+                .build(),
+        );
+
+        // Homonyms depend on both the romanization and the pronunciation.
+        let handler = word.connect_notify_local(
+            None,
+            glib::clone!(@weak self as content => move |word, pspec| {
+                if matches!(pspec.name(), "romanization" | "pronunciation") {
+                    content.update_homonym_row(word.id());
+                }
+            }),
+        );
+        imp.form_handlers.borrow_mut().push((word.clone(), handler));
+        self.update_homonym_row(word.id());
+
+        bindings.push(
+            word.bind_property("romanization", &imp.segmentation_label.get(), "label")
+                .sync_create()
+                .transform_to(
+                    glib::clone!(@weak self as content => @default-return None, move |b, _: String| {
+                        let id = b.source()?.downcast::<models::WordObject>().ok()?.id();
+                        Some(content.segmentation_markup(id))
+                    }),
+                )
+                .build(),
+        );
+    }
+
+    /// Shows the romanizations of homonyms of word of id `id`, or hides the homonym warning if
+    /// there is none.
+    fn update_homonym_row(&self, id: uuid::Uuid) {
+        let label = self
+            .project_model()
+            .query(|project| {
+                let language = project.language();
+                language
+                    .dictionary_homonyms_of(id)
+                    .into_iter()
+                    .filter_map(|id| language.dictionary().word_by_id(id))
+                    .map(|w| match w.translation() {
+                        "" => w.romanization().to_string(),
+                        t => format!("{} ‘{}’", w.romanization(), t),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let imp = self.imp();
+        imp.homonym_row
+            .set_subtitle(&glib::markup_escape_text(&label));
+        imp.homonym_row.set_visible(!label.is_empty());
+    }
+
+    /// Gets the segmentation of word of id `id` as markup, with each morpheme linked to its entry.
+    fn segmentation_markup(&self, id: uuid::Uuid) -> String {
+        self.project_model()
+            .query(|project| {
+                project
+                    .language()
+                    .dictionary_segment_word(id)
+                    .map(|seg| {
+                        let mut ret = String::new();
+                        let mut prev = None;
+                        for m in seg.morphemes.iter() {
+                            if let Some(prev) = prev {
+                                ret += &glib::markup_escape_text(Segmentation::separator(prev, m));
+                            }
+                            ret += &format!(
+                                "<a href=\"{}\">{}</a>",
+                                m.word_id,
+                                glib::markup_escape_text(&m.form)
+                            );
+                            prev = Some(m);
+                        }
+                        ret
+                    })
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Populates the custom field rows for `word`.
+    fn fill_fields(&self, word: Option<&models::WordObject>) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.fields_list.row_at_index(0) {
+            imp.fields_list.remove(&row);
+        }
+
+        let defs = self
+            .project_model()
+            .query(|project| {
+                project
+                    .language()
+                    .field_definitions()
+                    .iter_fields()
+                    .map(|f| (f.id().unwrap_or_default(), f.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        imp.fields_label.set_visible(!defs.is_empty());
+        imp.fields_list.set_visible(!defs.is_empty());
+
+        for (field_id, def) in defs {
+            let row = adw::EntryRow::builder()
+                .title(glib::markup_escape_text(def.name()))
+                .build();
+            let value = word.and_then(|w| w.field(field_id)).unwrap_or_default();
+            row.set_text(&value);
+            if !def.options().is_empty() {
+                row.set_tooltip_text(Some(&def.options().join(", ")));
+            }
+
+            let word_id = word.map(|w| w.id());
+            row.connect_changed(glib::clone!(@weak self as content => move |row| {
+                let Some(word_id) = word_id else {
+                    return;
+                };
+                let text = row.text().to_string();
+                if text.is_empty() || def.is_valid(&text) {
+                    row.remove_css_class("error");
+                } else {
+                    row.add_css_class("error");
+                }
+                content.project_model().update(|project| {
+                    project.update_word(word_id, |word| {
+                        word.set_field(field_id, (!text.is_empty()).then(|| text.clone()));
+                    })
+                });
+            }));
+
+            imp.fields_list.append(&row);
+        }
+    }
+
+    /// Populates the media rows for the word of id `word_id`. Recordings get playback controls and
+    /// pictures are shown as thumbnails.
+    fn fill_media(&self, word_id: Option<uuid::Uuid>) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.media_list.row_at_index(0) {
+            imp.media_list.remove(&row);
+        }
+
+        let assets = word_id
+            .and_then(|word_id| {
+                self.project_model().query(|project| {
+                    project
+                        .word_assets(word_id)
+                        .map(|a| {
+                            let bytes = glib::Bytes::from(a.data());
+                            (
+                                a.id().unwrap_or_default(),
+                                a.name().to_string(),
+                                a.kind(),
+                                bytes,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .unwrap_or_default();
+
+        imp.media_list.set_visible(!assets.is_empty());
+
+        for (asset_id, name, kind, bytes) in assets {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&name))
+                .build();
+
+            match kind {
+                AssetKind::Audio => {
+                    let stream = gio::MemoryInputStream::from_bytes(&bytes);
+                    let media = gtk::MediaFile::for_input_stream(&stream);
+                    let controls = gtk::MediaControls::builder()
+                        .media_stream(&media)
+                        .valign(gtk::Align::Center)
+                        .hexpand(true)
+                        .build();
+                    row.add_suffix(&controls);
+                }
+                AssetKind::Image => match gdk::Texture::from_bytes(&bytes) {
+                    Ok(texture) => {
+                        let picture = gtk::Picture::builder()
+                            .paintable(&texture)
+                            .height_request(64)
+                            .build();
+                        row.add_prefix(&picture);
+                    }
+                    Err(e) => log::error!("Cannot load picture {name}: {e}"),
+                },
+                AssetKind::Other => {}
+            }
+
+            let button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text("Remove")
+                .valign(gtk::Align::Center)
+                .build();
+            button.add_css_class("flat");
+            button.connect_clicked(glib::clone!(@weak self as content => move |_| {
+                content.project_model().update(|project| project.remove_asset(asset_id));
+                content.fill_media(word_id);
+            }));
+            row.add_suffix(&button);
+
+            imp.media_list.append(&row);
+        }
+    }
+
+    /// Attaches the file at `path` to the current word as a media asset.
+    fn add_media(&self, path: std::path::PathBuf) {
+        let Some(word_id) = self.word().map(|w| w.id()) else {
+            return;
+        };
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("Cannot read media file {:?}: {e}", path);
+                return;
+            }
+        };
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        // The update closure is `Fn`, so the contents are moved out of a cell.
+        let asset = std::cell::Cell::new(Some((name, data)));
+        self.project_model().update(|project| {
+            let (name, data) = asset.take()?;
+            project.add_asset(word_id, name, data)
+        });
+        self.fill_media(Some(word_id));
+    }
+
+    /// Fills the history popover with the revisions of the current word, newest first.
+    fn fill_history(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.history_list.row_at_index(0) {
+            imp.history_list.remove(&row);
+        }
+
+        let Some(word_id) = self.word().map(|w| w.id()) else {
+            return;
+        };
+        let revisions = self
+            .project_model()
+            .query(|project| project.history().revisions_of(word_id).to_vec())
+            .unwrap_or_default();
+
+        for (index, revision) in revisions.into_iter().enumerate().rev() {
+            let time = glib::DateTime::from_unix_local(revision.timestamp as i64)
+                .and_then(|t| t.format("%Y-%m-%d %H:%M"))
+                .map(|s| s.to_string())
+                .unwrap_or_default();
+            let subtitle = match &revision.reason {
+                Some(reason) => format!("{time} — {reason}"),
+                None => time,
+            };
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&format!(
+                    "{}: {} → {}",
+                    revision.field, revision.old_value, revision.new_value
+                )))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .build();
+
+            let button = gtk::Button::builder()
+                .icon_name("edit-undo-symbolic")
+                .tooltip_text("Restore")
+                .valign(gtk::Align::Center)
+                .build();
+            button.add_css_class("flat");
+            button.connect_clicked(glib::clone!(@weak self as content => move |_| {
+                content.restore_revision(word_id, index);
+            }));
+            row.add_suffix(&button);
+
+            imp.history_list.append(&row);
+        }
+    }
+
+    /// Restores the word of id `word_id` to the value before the revision at `index`.
+    fn restore_revision(&self, word_id: uuid::Uuid, index: usize) {
+        let restored = self
+            .project_model()
+            .update(|project| project.restore_word_revision(word_id, index))
+            .unwrap_or_default();
+        if !restored {
+            return;
+        }
+
+        if let Some(word) = self.word() {
+            word.notify_all();
+            self.fill_fields(Some(&word));
+        }
+        self.fill_history();
     }
 
     /// Unbinds form.
@@ -180,6 +572,10 @@ impl Content {
         for binding in bindings.drain(..) {
             binding.unbind();
         }
+
+        for (word, handler) in self.imp().form_handlers.borrow_mut().drain(..) {
+            word.disconnect(handler);
+        }
     }
 
     /// Clears form fields.
@@ -189,6 +585,102 @@ impl Content {
         imp.translation_entry.set_text("");
         imp.pronunciation_entry.set_text("");
         imp.pos_dropdown.set_selected(0);
+        imp.affix_kind_dropdown.set_selected(0);
+        imp.allomorphs_entry.set_text("");
+        imp.allomorphs_entry.set_visible(false);
+        imp.segmentation_label.set_label("");
+        imp.tags_entry.set_text("");
+        imp.status_dropdown.set_selected(0);
+        imp.replaced_by_entry.set_text("");
+        imp.replaced_by_entry.set_visible(false);
+        imp.homonym_row.set_visible(false);
+    }
+
+    /// Handler for `apply` signal from `replaced_by_entry`. Links the word whose romanization is
+    /// entered as the replacement.
+    #[template_callback]
+    fn handle_replaced_by_apply(&self, entry: &adw::EntryRow) {
+        let Some(word) = self.word() else {
+            return;
+        };
+        let text = entry.text().trim().to_string();
+        if text.is_empty() {
+            entry.remove_css_class("error");
+            word.set_replaced_by_id(None);
+            return;
+        }
+
+        let id = word.id();
+        let replacement = self
+            .project_model()
+            .query(|project| {
+                project
+                    .language()
+                    .dictionary()
+                    .iter_words()
+                    .filter(|w| w.id() != Some(id))
+                    .find(|w| w.romanization() == text)
+                    .and_then(|w| w.id())
+            })
+            .flatten();
+        match replacement {
+            Some(replacement) => {
+                entry.remove_css_class("error");
+                word.set_replaced_by_id(Some(replacement));
+            }
+            None => entry.add_css_class("error"),
+        }
+    }
+
+    /// Handler for `activate-link` signal from `segmentation_label`. Selects the linked word.
+    #[template_callback]
+    fn handle_segmentation_link_activated(&self, uri: &str, _label: &gtk::Label) -> bool {
+        self.activate_action("dictionary.select-word", Some(&uri.to_variant()))
+            .unwrap_or_default();
+        true
+    }
+
+    /// Handler for `clicked` signal from the button to attach media.
+    #[template_callback]
+    fn handle_add_media_button_clicked(&self, _button: &gtk::Button) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Recordings and Pictures"));
+        filter.add_mime_type("audio/*");
+        filter.add_mime_type("image/*");
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Attach Media")
+            .accept_label("_Attach")
+            .default_filter(&filter)
+            .build();
+
+        dialog.open(
+            self.root()
+                .and_then(|r| r.downcast::<gtk::Window>().ok())
+                .as_ref(),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@weak self as content => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            content.add_media(path);
+                        } else {
+                            log::error!("Attach media dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Attach media dialog dismissed"),
+                        _ => log::error!("Attach media dialog error: {e:?}")
+                    }
+                }
+            }),
+        );
+    }
+
+    /// Handler for `show` signal from `history_popover`.
+    #[template_callback]
+    fn handle_history_popover_show(&self, _popover: &gtk::Popover) {
+        self.fill_history();
     }
 
     /// Handler for `clicked` signal `from convert_from_ipa_button`
@@ -3,10 +3,15 @@ use gtk::glib::FromVariant;
 use gtk::prelude::*;
 use gtk::{gio, glib};
 
+use khazanah_core::lexicon::{
+    FieldDefinitions, Query, ReverseIndex, WordStatus, ALL_WORD_STATUSES,
+};
 use khazanah_core::Word;
 use uuid::Uuid;
 
 const EXPECTED_LIST_ITEM: &str = "Expected object to be `GtkListItem`";
+/// Maximum edit distance of keywords in reverse lookup.
+const REVERSE_LOOKUP_MAX_DISTANCE: usize = 1;
 
 use crate::models::{self, WordObject};
 use crate::ui;
@@ -52,6 +57,16 @@ mod imp {
         pub search_bar: TemplateChild<gtk::SearchBar>,
         #[template_child]
         pub search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub reverse_search_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub tag_facet_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub tag_facet_names: TemplateChild<gtk::StringList>,
+        #[template_child]
+        pub status_facet_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub search_error_label: TemplateChild<gtk::Label>,
 
         #[template_child]
         pub search_stack: TemplateChild<gtk::Stack>,
@@ -79,6 +94,11 @@ mod imp {
         #[property(get, set)]
         pub filter_category: RefCell<String>,
 
+        /// Tags of the tag facet, in the order of `tag_facet_names` after "All Tags".
+        pub tag_facet_tags: RefCell<Vec<String>>,
+        /// Index for looking up words by meaning, with the project revision it was built at.
+        pub reverse_index: RefCell<Option<(u64, ReverseIndex)>>,
+
         pub action_group: RefCell<gio::SimpleActionGroup>,
 
         pub selected_id: Cell<Uuid>,
@@ -327,25 +347,26 @@ impl Sidebar {
         self.switch_stack_page();
     }
 
-    /// Deletes a word by its id.
+    /// Deletes a word by its id, along with the words derived from it.
     pub fn delete_word_by_id(&self, id: Uuid) {
-        if self
+        let ids = self
             .project_model()
-            .update(|project| {
-                project
-                    .language_mut()
-                    .dictionary_remove_word_by_id(id, true)
-            })
-            .flatten()
-            .is_some()
-        {
-            log::debug!("Deleted word of id {}", id);
+            .update(|project| project.remove_word(id))
+            .unwrap_or_default();
+        if !ids.is_empty() {
+            log::debug!(
+                "Deleted word of id {} and {} derivative(s)",
+                id,
+                ids.len() - 1
+            );
 
             let list_model = self
                 .list_model()
                 .expect("word list model is not initialized");
 
-            list_model.remove_by_id(&id);
+            for id in ids.iter() {
+                list_model.remove_by_id(id);
+            }
 
             self.switch_stack_page();
         }
@@ -418,31 +439,204 @@ impl Sidebar {
     pub fn handle_search_entry_changed(&self, entry: &gtk::SearchEntry) {
         let filter_text = entry.text().to_string();
 
+        if !self.imp().reverse_search_button.is_active() {
+            self.set_ranking(&[]);
+        }
+
         let filter_by = match self.filter_category().as_str() {
-            "all" => models::WordFilterBy::AllAttrs(filter_text),
+            _ if self.imp().reverse_search_button.is_active() => {
+                self.show_search_error(None);
+                self.reverse_lookup(&filter_text)
+            }
+            "all" => match self.parse_query(&filter_text) {
+                Ok((query, fields)) => {
+                    self.show_search_error(None);
+                    models::WordFilterBy::Query(query, fields)
+                }
+                Err(e) => {
+                    // Keeps the previous results until the query is valid again.
+                    self.show_search_error(Some(&e));
+                    return;
+                }
+            },
             "romanization" => models::WordFilterBy::Romanization(filter_text),
             "translation" => models::WordFilterBy::Translation(filter_text),
+            "tags" => models::WordFilterBy::Tags(filter_text),
+            "fields" => models::WordFilterBy::Field(None, filter_text),
             s => {
                 log::warn!("Unknown filter category: {}", s);
                 models::WordFilterBy::AllAttrs(filter_text)
             }
         };
+        let mut filters = vec![filter_by];
+        if let Some(tag) = self.selected_facet_tag() {
+            filters.push(models::WordFilterBy::Tag(tag));
+        }
+        if let Some(status) = self.selected_facet_status() {
+            filters.push(models::WordFilterBy::Status(status));
+        }
+        let filter_by = match filters.len() {
+            1 => filters.remove(0),
+            _ => models::WordFilterBy::And(filters),
+        };
         log::debug!("Searching by {:?}.", &filter_by);
         self.search_word(filter_by);
     }
 
+    /// Parses a search query and resolves its phonological patterns. Returns the query along
+    /// with the custom field definitions to match it against.
+    fn parse_query(&self, text: &str) -> Result<(Query, FieldDefinitions), String> {
+        let fields = self
+            .project_model()
+            .query(|project| project.language().field_definitions().clone())
+            .unwrap_or_default();
+        let mut query = Query::parse(text, &fields).map_err(|e| e.to_string())?;
+        self.project_model()
+            .query(|project| {
+                let language = project.language();
+                query
+                    .resolve_patterns(language.phoneme_categories(), language.phonemic_inventory())
+                    .map_err(|e| e.to_string())
+            })
+            .transpose()?;
+        Ok((query, fields))
+    }
+
+    /// Looks up words whose translation matches the keywords of `text`, tolerating typos.
+    /// The matches are ranked by relevance in the list.
+    fn reverse_lookup(&self, text: &str) -> models::WordFilterBy {
+        if text.trim().is_empty() {
+            self.set_ranking(&[]);
+            return models::WordFilterBy::None;
+        }
+
+        // Rebuilds the index if the project has changed since it was built.
+        let imp = self.imp();
+        let revision = self.project_model().revision();
+        if imp
+            .reverse_index
+            .borrow()
+            .as_ref()
+            .map_or(true, |(r, _)| *r != revision)
+        {
+            imp.reverse_index.replace(
+                self.project_model()
+                    .query(|project| project.language().dictionary_reverse_index())
+                    .map(|index| (revision, index)),
+            );
+        }
+
+        let ids: Vec<Uuid> = imp
+            .reverse_index
+            .borrow()
+            .as_ref()
+            .map(|(_, index)| {
+                index
+                    .lookup(text, REVERSE_LOOKUP_MAX_DISTANCE)
+                    .into_iter()
+                    .map(|m| m.word_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.set_ranking(&ids);
+        models::WordFilterBy::Ids(ids.into_iter().collect())
+    }
+
+    /// Responds to `toggled` signal from reverse search button.
+    #[template_callback]
+    pub fn handle_reverse_search_toggled(&self, button: &gtk::ToggleButton) {
+        let imp = self.imp();
+        imp.search_entry
+            .set_placeholder_text(Some(match button.is_active() {
+                true => "Search meaning",
+                false => "Search word",
+            }));
+        self.handle_search_entry_changed(&imp.search_entry.get());
+    }
+
+    /// Shows a query error below the search entry, or hides it if `error` is `None`.
+    fn show_search_error(&self, error: Option<&str>) {
+        let imp = self.imp();
+        match error {
+            Some(e) => {
+                imp.search_entry.add_css_class("error");
+                imp.search_error_label.set_label(e);
+                imp.search_error_label.set_visible(true);
+            }
+            None => {
+                imp.search_entry.remove_css_class("error");
+                imp.search_error_label.set_visible(false);
+            }
+        }
+    }
+
     /// Responds to `notify::search-mode-enabled` signal from search bar.
     #[template_callback]
     pub fn handle_search_mode_toggle(&self, _pspec: glib::ParamSpec, bar: &gtk::SearchBar) {
         let imp = self.imp();
 
         if bar.is_search_mode() {
+            self.fill_tag_facet();
         } else {
             imp.search_entry.set_text("");
+            imp.tag_facet_dropdown.set_selected(0);
+            imp.status_facet_dropdown.set_selected(0);
+            imp.reverse_search_button.set_active(false);
+            self.show_search_error(None);
             self.search_word(models::WordFilterBy::None);
         }
     }
 
+    /// Responds to `notify::selected` signal from tag and status facet dropdowns.
+    #[template_callback]
+    pub fn handle_tag_facet_selected(&self, _pspec: glib::ParamSpec, _dropdown: &gtk::DropDown) {
+        self.handle_search_entry_changed(&self.imp().search_entry.get());
+    }
+
+    /// Gets the tag selected in the tag facet, if any.
+    fn selected_facet_tag(&self) -> Option<String> {
+        let imp = self.imp();
+        let selected = imp.tag_facet_dropdown.selected() as usize;
+        selected
+            .checked_sub(1)
+            .and_then(|i| imp.tag_facet_tags.borrow().get(i).cloned())
+    }
+
+    /// Gets the status selected in the status facet, if any.
+    fn selected_facet_status(&self) -> Option<WordStatus> {
+        let selected = self.imp().status_facet_dropdown.selected() as usize;
+        selected
+            .checked_sub(1)
+            .and_then(|i| ALL_WORD_STATUSES.get(i).copied())
+    }
+
+    /// Fills the tag facet with the tags used in the dictionary and their counts.
+    fn fill_tag_facet(&self) {
+        let imp = self.imp();
+        let selected = self.selected_facet_tag();
+
+        let counts = self
+            .project_model()
+            .query(|project| project.language().dictionary().tag_counts())
+            .unwrap_or_default();
+
+        let labels = counts
+            .iter()
+            .map(|(tag, n)| format!("{} ({})", tag, n))
+            .collect::<Vec<_>>();
+        let labels = labels.iter().map(String::as_str).collect::<Vec<_>>();
+        imp.tag_facet_tags
+            .replace(counts.keys().cloned().collect::<Vec<_>>());
+        imp.tag_facet_names
+            .splice(1, imp.tag_facet_names.n_items() - 1, &labels);
+
+        let position = selected
+            .and_then(|tag| imp.tag_facet_tags.borrow().iter().position(|t| *t == tag))
+            .map(|i| i as u32 + 1)
+            .unwrap_or(0);
+        imp.tag_facet_dropdown.set_selected(position);
+    }
+
     /// Updates the search.
     pub fn search_word(&self, filter_by: models::WordFilterBy) {
         if let Some(filter_model) = self.filter_model() {
@@ -479,6 +673,17 @@ impl Sidebar {
         }
     }
 
+    /// Lists words of `ids` first, in that order. Clears the ranking if `ids` is empty.
+    fn set_ranking(&self, ids: &[Uuid]) {
+        if let Some(sorter) = self
+            .sort_model()
+            .and_then(|sm| sm.sorter())
+            .and_then(|s| s.downcast::<models::WordSorter>().ok())
+        {
+            sorter.set_ranking(ids);
+        }
+    }
+
     // Sets the order of the sorting. `false` for ascending, `true` for descending.
     pub fn set_sort_order(&self, descending: bool) {
         if let Some(sorter) = self
@@ -24,6 +24,8 @@ mod imp {
         pub translation_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub pronunciation_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub status_label: TemplateChild<gtk::Label>,
 
         #[property(get, set)]
         pub word: RefCell<Option<WordObject>>,
@@ -100,6 +102,11 @@ impl WordListRow {
         }
         Some("(New word)".to_string())
     }
+
+    #[template_callback(function)]
+    fn is_not_empty(s: Option<String>) -> bool {
+        s.map(|s| !s.is_empty()).unwrap_or_default()
+    }
 }
 
 impl Default for WordListRow {
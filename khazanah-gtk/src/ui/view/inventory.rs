@@ -26,6 +26,8 @@ mod imp {
     #[properties(wrapper_type = super::InventoryView)]
     #[template(resource = "/com/github/manenfu/Khazanah/ui/view/inventory.ui")]
     pub struct InventoryView {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
         #[template_child]
         pub leaflet: TemplateChild<adw::Leaflet>,
 
@@ -54,6 +56,18 @@ mod imp {
                 view.navigate_backward();
             });
 
+            klass.install_action("inventory.learn-weights", None, move |view, _, _| {
+                view.learn_weights();
+            });
+
+            klass.install_action("inventory.reset-weights", None, move |view, _, _| {
+                view.reset_weights();
+            });
+
+            klass.install_action("inventory.clear-weights", None, move |view, _, _| {
+                view.clear_weights();
+            });
+
             klass.add_binding_action(
                 gdk::Key::Escape,
                 gdk::ModifierType::empty(),
@@ -147,6 +161,48 @@ impl InventoryView {
         self.update_buttons_visibility();
     }
 
+    /// Learns the weights of the word generator from the pronunciations of the dictionary.
+    fn learn_weights(&self) {
+        let msg = match self
+            .project_model()
+            .update(|project| project.language_mut().phonotactic_learn_weights())
+        {
+            Some(Ok(n)) => format!("Learned Weights from {} Syllables", n),
+            Some(Err(e)) => {
+                log::error!("Learn weights error: {e}");
+                "Unable to Learn Weights".to_string()
+            }
+            None => return,
+        };
+        self.imp().toast_overlay.add_toast(adw::Toast::new(&msg));
+    }
+
+    /// Resets the weights of the word generator to uniform.
+    fn reset_weights(&self) {
+        if self
+            .project_model()
+            .update(|project| project.language_mut().phonotactic_reset_weights())
+            .is_some()
+        {
+            self.imp()
+                .toast_overlay
+                .add_toast(adw::Toast::new("Reset Weights to Uniform"));
+        }
+    }
+
+    /// Clears the weights of the word generator, so the likeliness of patterns is used again.
+    fn clear_weights(&self) {
+        if self
+            .project_model()
+            .update(|project| project.language_mut().phonotactic_clear_weights())
+            .is_some()
+        {
+            self.imp()
+                .toast_overlay
+                .add_toast(adw::Toast::new("Using Pattern Likeliness"));
+        }
+    }
+
     fn update_buttons_visibility(&self) {
         let imp = self.imp();
         if let Some(header_bar) = imp.header_bar.borrow().as_ref() {
@@ -31,6 +31,8 @@ mod imp {
         pub mora_entry: TemplateChild<adw::ActionRow>,
         #[template_child]
         pub mora_adj: TemplateChild<gtk::Adjustment>,
+        #[template_child]
+        pub minimal_pairs_list: TemplateChild<gtk::ListBox>,
 
         #[property(get, set)]
         pub project_model: RefCell<models::ProjectModel>,
@@ -97,6 +99,7 @@ impl Content {
 
         if let Some(phoneme) = phoneme {
             self.bind(phoneme);
+            self.fill_minimal_pairs(phoneme.id());
             self.set_fields_sensitive(true);
         } else {
             self.clear_fields();
@@ -152,6 +155,62 @@ impl Content {
         );
     }
 
+    /// Lists the phonemes contrasting with phoneme of id `id`, along with the minimal pairs
+    /// attesting each contrast.
+    pub fn fill_minimal_pairs(&self, id: uuid::Uuid) {
+        self.clear_minimal_pairs();
+        let list = self.imp().minimal_pairs_list.get();
+
+        let contrasts = self
+            .project_model()
+            .query(|project| {
+                let language = project.language();
+                let inventory = language.phonemic_inventory();
+                let dictionary = language.dictionary();
+                let sound = |id| {
+                    inventory
+                        .phoneme_by_id(id)
+                        .map(|p| p.sound().to_string())
+                        .unwrap_or_default()
+                };
+                let word = |id| {
+                    dictionary
+                        .word_by_id(id)
+                        .map(|w| format!("{} /{}/", w.romanization(), w.pronunciation()))
+                        .unwrap_or_default()
+                };
+
+                language
+                    .phonemic_inventory_minimal_pairs()
+                    .contrasts_of(id)
+                    .map(|(other, pairs)| {
+                        let title = format!("/{}/ ~ /{}/", sound(id), sound(other));
+                        let rows = pairs
+                            .iter()
+                            .map(|p| format!("{} – {}", word(p.word_ids[0]), word(p.word_ids[1])))
+                            .collect::<Vec<_>>();
+                        (title, rows)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for (title, rows) in contrasts {
+            let expander = adw::ExpanderRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(format!("{} pair(s)", rows.len()))
+                .build();
+            for row in rows {
+                expander.add_row(
+                    &adw::ActionRow::builder()
+                        .title(glib::markup_escape_text(&row).as_str())
+                        .build(),
+                );
+            }
+            list.append(&expander);
+        }
+    }
+
     /// Unbinds form.
     pub fn unbind(&self) {
         let mut bindings = self.imp().form_bindings.borrow_mut();
@@ -166,6 +225,15 @@ impl Content {
         let imp = self.imp();
         imp.sound_entry.set_text("");
         imp.romanization_entry.set_text("");
+        self.clear_minimal_pairs();
+    }
+
+    /// Clears the list of minimal pairs.
+    fn clear_minimal_pairs(&self) {
+        let list = self.imp().minimal_pairs_list.get();
+        while let Some(row) = list.row_at_index(0) {
+            list.remove(&row);
+        }
     }
 }
 
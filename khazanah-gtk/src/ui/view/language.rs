@@ -24,7 +24,11 @@ pub mod imp {
         #[template_child]
         pub author_entry: TemplateChild<adw::EntryRow>,
         #[template_child]
+        pub alphabet_entry: TemplateChild<adw::EntryRow>,
+        #[template_child]
         pub description_area: TemplateChild<ui::TextAreaRow>,
+        #[template_child]
+        pub json_switch: TemplateChild<gtk::Switch>,
 
         #[property(get, set)]
         pub project_model: RefCell<models::ProjectModel>,
@@ -129,6 +133,22 @@ impl LanguageView {
                 .build(),
         );
 
+        bindings.push(
+            meta_object
+                .bind_property("alphabet", &imp.alphabet_entry.get(), "text")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
+        bindings.push(
+            meta_object
+                .bind_property("store-json", &imp.json_switch.get(), "active")
+                .sync_create()
+                .bidirectional()
+                .build(),
+        );
+
         let desc = meta_object.description();
         let desc_buf = self.imp().description_area.buffer();
         desc_buf.set_text(&desc);
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use gtk::glib;
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use khazanah_core::analysis::{self, LexiconStatistics, SyllablePosition};
+
+use crate::models;
+use crate::ui;
+
+/// The maximum number of transitions shown.
+const MAX_BIGRAMS: usize = 50;
+
+/// The width of bars in the tables.
+const BAR_WIDTH: i32 = 160;
+
+#[doc(hidden)]
+pub mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Debug, Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::StatisticsView)]
+    #[template(resource = "/com/github/manenfu/Khazanah/ui/view/statistics.ui")]
+    pub struct StatisticsView {
+        #[template_child]
+        pub content_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub summary_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub sort_dropdown: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub segment_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub position_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub shape_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub syllable_length_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub mora_length_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub bigram_list: TemplateChild<gtk::ListBox>,
+
+        #[property(get, set)]
+        pub project_model: RefCell<models::ProjectModel>,
+
+        pub statistics: RefCell<LexiconStatistics>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StatisticsView {
+        const NAME: &'static str = "KhzStatisticsView";
+        type Type = super::StatisticsView;
+        type ParentType = adw::Bin;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_instance_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for StatisticsView {
+        fn properties() -> &'static [glib::ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for StatisticsView {}
+    impl BinImpl for StatisticsView {}
+}
+
+glib::wrapper! {
+    /// The view to show phoneme and syllable statistics of the dictionary.
+    pub struct StatisticsView(ObjectSubclass<imp::StatisticsView>)
+        @extends gtk::Widget, adw::Bin,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+#[gtk::template_callbacks]
+impl StatisticsView {
+    /// Counts the statistics of the dictionary and fills the tables.
+    fn load_statistics(&self) {
+        let imp = self.imp();
+
+        let statistics = self
+            .project_model()
+            .query(|project| project.language().dictionary_statistics())
+            .unwrap_or_default();
+
+        if statistics.n_words == 0 {
+            imp.content_stack.set_visible_child_name("empty");
+        } else {
+            imp.content_stack.set_visible_child_name("statistics");
+        }
+        imp.summary_label.set_label(&format!(
+            "{} words, {} syllables",
+            statistics.n_words,
+            statistics.syllable_shapes.values().sum::<usize>()
+        ));
+        imp.statistics.replace(statistics);
+
+        self.fill_tables();
+    }
+
+    /// Returns `true` if tables are sorted by frequency rather than by name.
+    fn by_frequency(&self) -> bool {
+        self.imp().sort_dropdown.selected() == 0
+    }
+
+    /// Fills the tables from the counted statistics.
+    fn fill_tables(&self) {
+        let imp = self.imp();
+        let statistics = imp.statistics.borrow();
+
+        self.fill_counts(&imp.segment_list, &statistics.segments, |s| {
+            format!("/{s}/")
+        });
+        self.fill_counts(&imp.shape_list, &statistics.syllable_shapes, String::clone);
+        self.fill_counts(
+            &imp.syllable_length_list,
+            &statistics.syllable_lengths,
+            |n| n.to_string(),
+        );
+        self.fill_counts(&imp.mora_length_list, &statistics.mora_lengths, |n| {
+            n.to_string()
+        });
+
+        let mut bigrams = self.sorted(&statistics.bigrams);
+        if self.by_frequency() {
+            bigrams.truncate(MAX_BIGRAMS);
+        }
+        Self::fill_rows(
+            &imp.bigram_list,
+            bigrams
+                .into_iter()
+                .map(|((a, b), n)| (format!("{a} → {b}"), None, n)),
+        );
+
+        let positions = [
+            SyllablePosition::Onset,
+            SyllablePosition::Nucleus,
+            SyllablePosition::Coda,
+        ];
+        Self::fill_rows(
+            &imp.position_list,
+            self.sorted(&statistics.segments).into_iter().map(|(s, n)| {
+                let subtitle = positions
+                    .iter()
+                    .map(|p| format!("{} {}", p.name(), statistics.position_count(&s, *p)))
+                    .collect::<Vec<_>>()
+                    .join(" · ");
+                (format!("/{s}/"), Some(subtitle), n)
+            }),
+        );
+    }
+
+    /// Sorts `counts` by the selected order.
+    fn sorted<K: Clone + Ord>(&self, counts: &BTreeMap<K, usize>) -> Vec<(K, usize)> {
+        match self.by_frequency() {
+            true => analysis::by_frequency(counts),
+            false => counts.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+        }
+    }
+
+    /// Fills `list` with a row for each count, labelled with `label`.
+    fn fill_counts<K: Clone + Ord>(
+        &self,
+        list: &gtk::ListBox,
+        counts: &BTreeMap<K, usize>,
+        label: impl Fn(&K) -> String,
+    ) {
+        Self::fill_rows(
+            list,
+            self.sorted(counts)
+                .into_iter()
+                .map(|(k, n)| (label(&k), None, n)),
+        );
+    }
+
+    /// Fills `list` with rows of a title, an optional subtitle, and a bar of a count.
+    fn fill_rows(list: &gtk::ListBox, rows: impl Iterator<Item = (String, Option<String>, usize)>) {
+        while let Some(row) = list.row_at_index(0) {
+            list.remove(&row);
+        }
+
+        let rows = rows.collect::<Vec<_>>();
+        let max = rows.iter().map(|r| r.2).max().unwrap_or_default().max(1);
+
+        for (title, subtitle, n) in rows {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .build();
+            if let Some(subtitle) = subtitle {
+                row.set_subtitle(&subtitle);
+            }
+
+            let bar = gtk::LevelBar::builder()
+                .min_value(0.0)
+                .max_value(max as f64)
+                .value(n as f64)
+                .width_request(BAR_WIDTH)
+                .valign(gtk::Align::Center)
+                .build();
+            // Removes the default offsets, which color the bar by how full it is.
+            bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_LOW));
+            bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_HIGH));
+            bar.remove_offset_value(Some(gtk::LEVEL_BAR_OFFSET_FULL));
+            row.add_suffix(&bar);
+
+            let count = gtk::Label::builder()
+                .label(n.to_string())
+                .width_chars(5)
+                .xalign(1.0)
+                .build();
+            count.add_css_class("numeric");
+            row.add_suffix(&count);
+
+            list.append(&row);
+        }
+    }
+
+    /// Responds to `notify::selected` signal from the sort dropdown.
+    #[template_callback]
+    fn handle_sort_selected(&self, _pspec: glib::ParamSpec, _dropdown: &gtk::DropDown) {
+        self.fill_tables();
+    }
+}
+
+impl ui::View for StatisticsView {
+    fn load_state(&self) {
+        log::debug!("Loading view state.");
+
+        self.load_statistics();
+    }
+
+    fn unload_state(&self) {
+        log::debug!("Unloading view state.");
+
+        self.imp().statistics.take();
+    }
+}
@@ -4,8 +4,14 @@ use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib};
 
+use adw::prelude::*;
 use adw::subclass::prelude::*;
 
+use khazanah_core::interchange;
+use khazanah_core::xml::XmlDiagnostic;
+
+use crate::config;
+use crate::recovery;
 use crate::ui::{self, MainView, View};
 
 mod imp {
@@ -38,6 +44,10 @@ mod imp {
         pub dictionary_view: TemplateChild<ui::DictionaryView>,
         #[template_child]
         pub inventory_view: TemplateChild<ui::InventoryView>,
+        #[template_child]
+        pub corpus_view: TemplateChild<ui::CorpusView>,
+        #[template_child]
+        pub statistics_view: TemplateChild<ui::StatisticsView>,
 
         #[template_child]
         pub header_bar: TemplateChild<ui::HeaderBar>,
@@ -85,11 +95,14 @@ mod imp {
             let obj = self.obj();
             obj.setup_gactions();
             obj.setup_bindings();
+            obj.setup_autosave();
 
             let header_bar = &self.header_bar.get();
             self.language_view.connect_headerbar(header_bar);
             self.inventory_view.connect_headerbar(header_bar);
             self.dictionary_view.connect_headerbar(header_bar);
+            self.corpus_view.connect_headerbar(header_bar);
+            self.statistics_view.connect_headerbar(header_bar);
         }
 
         fn properties() -> &'static [glib::ParamSpec] {
@@ -135,6 +148,8 @@ mod imp {
                 return glib::signal::Inhibit(true);
             }
 
+            // Changes are either saved or discarded at this point.
+            recovery::clear();
             glib::signal::Inhibit(false)
         }
     }
@@ -186,6 +201,18 @@ impl ApplicationWindow {
                 }
             })
             .build();
+        // Import project from PolyGlot
+        let import_polyglot_action = gio::ActionEntry::builder("import-polyglot")
+            .activate(|window: &Self, _, _| {
+                if window.project_model().dirty() && !window.force_action() {
+                    log::debug!("Project is dirty.");
+                    window.confirm_save_dialog(Some("win.import-polyglot"));
+                } else {
+                    window.set_force_action(false);
+                    window.import_polyglot_dialog();
+                }
+            })
+            .build();
         // Save project
         let save_action = gio::ActionEntry::builder("save")
             .activate(|window: &Self, _, _| {
@@ -216,6 +243,7 @@ impl ApplicationWindow {
         self.add_action_entries([
             open_action,
             new_action,
+            import_polyglot_action,
             save_action,
             save_as_action,
             go_back_action,
@@ -251,6 +279,97 @@ impl ApplicationWindow {
             .build();
     }
 
+    /// Autosaves unsaved changes periodically.
+    fn setup_autosave(&self) {
+        glib::timeout_add_seconds_local(
+            config::AUTOSAVE_INTERVAL,
+            glib::clone!(@weak self as window => @default-return glib::Continue(false), move || {
+                window.autosave();
+                glib::Continue(true)
+            }),
+        );
+    }
+
+    /// Saves a copy of the current project to the recovery location if it has unsaved changes.
+    fn autosave(&self) {
+        if !self.project_model().opened() || !self.project_model().dirty() {
+            return;
+        }
+        self.commit_all_views();
+        match recovery::save(&self.project_model()) {
+            Ok(_) => log::debug!("Project autosaved: {:?}", recovery::project_path()),
+            Err(e) => log::error!("Error autosaving project: {}", e),
+        }
+    }
+
+    /// Offers to recover the autosaved project if the application did not close properly.
+    pub fn offer_recovery(&self) {
+        if !recovery::pending() {
+            return;
+        }
+        log::info!("Found autosaved project: {:?}", recovery::project_path());
+
+        let body = match recovery::origin() {
+            Some(path) => format!(
+                "Khazanah did not close properly. Unsaved changes to \"{path}\" were autosaved."
+            ),
+            None => {
+                "Khazanah did not close properly. An unsaved project was autosaved.".to_string()
+            }
+        };
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(self)
+            .heading("Recover Unsaved Changes?")
+            .body(body)
+            .build();
+        dialog.add_response("discard", "_Discard");
+        dialog.add_response("recover", "_Recover");
+        dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+        dialog.set_response_appearance("recover", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("recover"));
+        dialog.connect_closure(
+            "response",
+            false,
+            glib::closure_local!(@strong self as window => move |_: &adw::MessageDialog, response: &str| {
+                match response {
+                    "recover" => window.recover_project(),
+                    "discard" => recovery::clear(),
+                    _ => {}
+                }
+            })
+        );
+        dialog.present();
+    }
+
+    /// Opens the autosaved project. It is kept until the project is saved.
+    fn recover_project(&self) {
+        match self
+            .project_model()
+            .load_recovery_file(recovery::project_path(), recovery::origin())
+        {
+            Ok(diagnostics) => {
+                log::info!("Project recovered.");
+                self.finish_open_project();
+                self.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("Recovered Unsaved Changes"));
+
+                if !diagnostics.is_empty() {
+                    for d in diagnostics.iter() {
+                        log::warn!("{d}");
+                    }
+                    self.show_diagnostics(&diagnostics);
+                }
+            }
+            Err(e) => {
+                log::error!("Error recovering project: {}", e);
+                self.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("Unable to Recover Unsaved Changes"));
+            }
+        }
+    }
+
     /// Shows `Open File` dialog.
     fn open_file_dialog(&self) {
         let imp = self.imp();
@@ -305,8 +424,9 @@ impl ApplicationWindow {
         ctx.spawn_local(async move {
             if let Some(window) = self_weak.upgrade() {
                 match window.project_model().load_file(&path) {
-                    Ok(_) => {
+                    Ok(diagnostics) => {
                         log::info!("Project opened: {:?}", &path);
+                        recovery::clear();
                         window.finish_open_project();
                         let msg = format!(
                             "Opened \"{}\"",
@@ -316,6 +436,13 @@ impl ApplicationWindow {
                                 .unwrap_or_default()
                         );
                         window.imp().toast_overlay.add_toast(adw::Toast::new(&msg));
+
+                        if !diagnostics.is_empty() {
+                            for d in diagnostics.iter() {
+                                log::warn!("{d}");
+                            }
+                            window.show_diagnostics(&diagnostics);
+                        }
                     }
                     Err(e) => {
                         log::error!("Error opening file: {}", e);
@@ -333,10 +460,105 @@ impl ApplicationWindow {
         });
     }
 
+    /// Shows the problems found when opening a damaged project file.
+    fn show_diagnostics(&self, diagnostics: &[XmlDiagnostic]) {
+        let body = diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(self)
+            .heading("The Project File Is Damaged")
+            .body(format!(
+                "The following entries could not be read and were skipped. \
+                 Saving the project will discard them.\n\n{body}"
+            ))
+            .build();
+        dialog.add_response("close", "_Close");
+        dialog.present();
+    }
+
+    /// Shows `Open File` dialog to import a PolyGlot dictionary.
+    fn import_polyglot_dialog(&self) {
+        let imp = self.imp();
+        // Skip if dialog is already opened
+        if imp.file_dialog.borrow().is_some() {
+            return;
+        }
+
+        let filter = gtk::FileFilter::new();
+        filter.add_suffix("pgd");
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Import PolyGlot Dictionary")
+            .accept_label("_Import")
+            .default_filter(&filter)
+            .build();
+
+        imp.file_dialog.replace(Some(dialog.clone()));
+
+        dialog.open(
+            Some(self),
+            Option::<&gio::Cancellable>::None,
+            glib::clone!(@strong self as window => move |response| {
+                match response {
+                    Ok(f) => {
+                        if let Some(path) = f.path() {
+                            window.import_polyglot_file(path);
+                        } else {
+                            log::error!("Import dialog error: Invalid path");
+                        }
+                    }
+                    Err(e) => match e.kind::<gtk::DialogError>() {
+                        Some(gtk::DialogError::Dismissed) => log::debug!("Import dialog dismissed"),
+                        _ => log::error!("Import dialog error: {e:?}")
+                    }
+                }
+                window.imp().file_dialog.replace(None);
+            }),
+        );
+    }
+
+    /// Imports a PolyGlot dictionary as a new project, and shows what cannot be converted.
+    pub fn import_polyglot_file<P: AsRef<Path>>(&self, path: P) {
+        log::debug!("Importing PolyGlot file: {:?}", path.as_ref());
+
+        let result = std::fs::File::open(&path)
+            .map_err(interchange::Error::from)
+            .and_then(interchange::import_polyglot);
+        match result {
+            Ok((language, report)) => {
+                self.project_model().import_language(language);
+                self.finish_open_project();
+                self.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("Imported PolyGlot Dictionary"));
+
+                if !report.is_empty() {
+                    let dialog = adw::MessageDialog::builder()
+                        .transient_for(self)
+                        .heading("Some Data Was Not Imported")
+                        .body(report.iter().collect::<Vec<_>>().join("\n"))
+                        .build();
+                    dialog.add_response("close", "_Close");
+                    dialog.present();
+                }
+            }
+            Err(e) => {
+                log::error!("Error importing file: {}", e);
+                self.imp()
+                    .toast_overlay
+                    .add_toast(adw::Toast::new("Unable to Import PolyGlot Dictionary"));
+            }
+        }
+    }
+
     /// Creates a new project and sets it as the current project.
     pub fn new_project(&self) {
         // self.project_model().set_project(Some(Project::new()));
         self.project_model().new_project();
+        recovery::clear();
         self.finish_open_project();
         self.imp()
             .toast_overlay
@@ -415,6 +637,7 @@ impl ApplicationWindow {
                 match window.project_model().save_file(&path) {
                     Ok(_) => {
                         log::info!("Project saved: {:?}", &path);
+                        recovery::clear();
                         let msg = format!(
                             "Saved \"{}\"",
                             path.file_name()
@@ -504,6 +727,8 @@ impl ApplicationWindow {
             MainView::Language => main_stack.set_visible_child(&*imp.language_view),
             MainView::Inventory => main_stack.set_visible_child(&*imp.inventory_view),
             MainView::Dictionary => main_stack.set_visible_child(&*imp.dictionary_view),
+            MainView::Corpus => main_stack.set_visible_child(&*imp.corpus_view),
+            MainView::Statistics => main_stack.set_visible_child(&*imp.statistics_view),
             MainView::Unknown => log::warn!("Attempting to switch to unknown view."),
         }
 
@@ -520,6 +745,8 @@ impl ApplicationWindow {
             MainView::Language => imp.language_view.load_state(),
             MainView::Inventory => imp.inventory_view.load_state(),
             MainView::Dictionary => imp.dictionary_view.load_state(),
+            MainView::Corpus => imp.corpus_view.load_state(),
+            MainView::Statistics => imp.statistics_view.load_state(),
             MainView::Unknown => log::warn!("Attempting to load unknown view."),
         }
     }
@@ -539,6 +766,8 @@ impl ApplicationWindow {
             MainView::Language => imp.language_view.commit_state(),
             MainView::Inventory => imp.inventory_view.commit_state(),
             MainView::Dictionary => imp.dictionary_view.commit_state(),
+            MainView::Corpus => imp.corpus_view.commit_state(),
+            MainView::Statistics => imp.statistics_view.commit_state(),
             MainView::Unknown => {} // _ => log::warn!("Attempting to commit unknown view."),
         }
     }
@@ -558,6 +787,8 @@ impl ApplicationWindow {
             MainView::Language => imp.language_view.unload_state(),
             MainView::Inventory => imp.inventory_view.unload_state(),
             MainView::Dictionary => imp.dictionary_view.unload_state(),
+            MainView::Corpus => imp.corpus_view.unload_state(),
+            MainView::Statistics => imp.statistics_view.unload_state(),
             MainView::Unknown => log::warn!("Attempting to load unknown view."),
         }
     }
@@ -577,6 +808,8 @@ impl ApplicationWindow {
             MainView::Language => imp.language_view.go_back(),
             MainView::Inventory => imp.inventory_view.go_back(),
             MainView::Dictionary => imp.dictionary_view.go_back(),
+            MainView::Corpus => imp.corpus_view.go_back(),
+            MainView::Statistics => imp.statistics_view.go_back(),
             MainView::Unknown => {}
         }
     }